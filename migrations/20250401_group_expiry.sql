@@ -0,0 +1,12 @@
+-- 群组过期与归档
+-- 执行日期：2025-04-01
+
+-- 可选的过期时间（用于活动类群组），以及归档时间
+ALTER TABLE groups ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE groups ADD COLUMN IF NOT EXISTS archived_at TIMESTAMP WITH TIME ZONE;
+
+-- 大部分查询只关心未归档的群组
+CREATE INDEX IF NOT EXISTS idx_groups_active ON groups(created_at) WHERE archived_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_groups_expires_at ON groups(expires_at)
+    WHERE expires_at IS NOT NULL AND archived_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_group_members_group_last_active ON group_members(group_id, last_active DESC);
@@ -0,0 +1,16 @@
+-- 群组人数上限与候补队列
+-- 执行日期：2025-04-02
+
+-- 可选的人数上限，NULL 表示不限制
+ALTER TABLE groups ADD COLUMN IF NOT EXISTS max_members INTEGER;
+ALTER TABLE groups ADD CONSTRAINT max_members_positive CHECK (max_members IS NULL OR max_members > 0);
+
+-- 候补队列，按申请时间先进先出
+CREATE TABLE IF NOT EXISTS group_waitlist (
+    group_id VARCHAR(255) NOT NULL REFERENCES groups(group_id),
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id),
+    requested_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_waitlist_queue ON group_waitlist(group_id, requested_at, user_id);
@@ -0,0 +1,8 @@
+-- 群组分类与标签
+-- 执行日期：2025-04-03
+
+ALTER TABLE groups ADD COLUMN IF NOT EXISTS category VARCHAR(50);
+ALTER TABLE groups ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS idx_groups_category ON groups(category) WHERE archived_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_groups_tags ON groups USING GIN(tags);
@@ -0,0 +1,4 @@
+-- 群组名称模糊搜索：启用 pg_trgm 并为名称建立三元组索引
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_groups_name_trgm ON groups USING GIN (name gin_trgm_ops);
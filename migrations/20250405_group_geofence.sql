@@ -0,0 +1,9 @@
+-- 群组地理围栏：半径（米）或多边形，二者都为空表示不限制位置
+ALTER TABLE groups ADD COLUMN IF NOT EXISTS geofence_radius INTEGER
+    CHECK (geofence_radius IS NULL OR geofence_radius > 0);
+ALTER TABLE groups ADD COLUMN IF NOT EXISTS geofence geography(POLYGON, 4326);
+
+CREATE INDEX IF NOT EXISTS idx_groups_geofence ON groups USING GIST(geofence);
+
+-- 成员最近一次在围栏内的时间，离开围栏后在宽限期内仍可发言
+ALTER TABLE group_members ADD COLUMN IF NOT EXISTS last_in_fence_at TIMESTAMPTZ DEFAULT NOW();
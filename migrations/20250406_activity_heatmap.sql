@@ -0,0 +1,24 @@
+-- 活动热力图：按小时、geohash 网格和活动类型预聚合的活动数量
+CREATE TABLE IF NOT EXISTS activity_heatmap_cells (
+    precision SMALLINT NOT NULL,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    activity_type VARCHAR(50) NOT NULL,
+    cell VARCHAR(12) NOT NULL,
+    -- 网格中心点，便于按视窗过滤
+    latitude DOUBLE PRECISION NOT NULL,
+    longitude DOUBLE PRECISION NOT NULL,
+    weight BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (precision, bucket_start, activity_type, cell)
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_heatmap_bucket ON activity_heatmap_cells(precision, bucket_start);
+
+-- 增量聚合进度：已聚合到的活动创建时间
+CREATE TABLE IF NOT EXISTS activity_heatmap_state (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    aggregated_until TIMESTAMPTZ NOT NULL
+);
+
+INSERT INTO activity_heatmap_state (id, aggregated_until)
+VALUES (TRUE, '-infinity')
+ON CONFLICT (id) DO NOTHING;
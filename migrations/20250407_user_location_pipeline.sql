@@ -0,0 +1,8 @@
+-- 用户实时位置
+-- user_locations 按时间追加记录每次被接受的定位，查询时取每个用户最新的一条
+
+CREATE INDEX IF NOT EXISTS idx_user_locations_user_updated ON user_locations(user_id, updated_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_user_locations_geom ON user_locations USING GIST (
+    (ST_SetSRID(ST_MakePoint(longitude, latitude), 4326)::geography)
+);
@@ -0,0 +1,15 @@
+-- 用户位置隐私设置
+-- 没有记录的用户按 exact（精确位置）处理
+CREATE TABLE IF NOT EXISTS user_privacy_settings (
+    user_id VARCHAR(255) PRIMARY KEY REFERENCES users(user_id) ON DELETE CASCADE,
+    location_precision VARCHAR(10) NOT NULL DEFAULT 'exact',
+    fuzz_radius INTEGER,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT privacy_location_precision CHECK (
+        location_precision IN ('exact', 'fuzzed', 'city', 'hidden')
+    ),
+    CONSTRAINT privacy_fuzz_radius CHECK (
+        (location_precision = 'fuzzed') = (fuzz_radius IS NOT NULL) AND
+        (fuzz_radius IS NULL OR fuzz_radius > 0)
+    )
+);
@@ -0,0 +1,11 @@
+-- 群组位置共享
+-- 成员在 expires_at 之前向群组共享自己的实时位置，过期后自动失效
+CREATE TABLE IF NOT EXISTS group_location_shares (
+    group_id VARCHAR(255) NOT NULL REFERENCES groups(group_id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_location_shares_expires ON group_location_shares(group_id, expires_at);
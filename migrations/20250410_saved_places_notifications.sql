@@ -0,0 +1,108 @@
+-- 用户收藏的地点
+CREATE TABLE IF NOT EXISTS saved_places (
+    place_id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    latitude DOUBLE PRECISION NOT NULL,
+    longitude DOUBLE PRECISION NOT NULL,
+    radius INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT saved_place_latitude_range CHECK (latitude BETWEEN -90 AND 90),
+    CONSTRAINT saved_place_longitude_range CHECK (longitude BETWEEN -180 AND 180),
+    CONSTRAINT saved_place_radius_positive CHECK (radius > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_places_user_id ON saved_places(user_id);
+CREATE INDEX IF NOT EXISTS idx_saved_places_location ON saved_places USING GIST (
+    (ST_SetSRID(ST_MakePoint(longitude, latitude), 4326)::geography)
+);
+
+-- 用户通知收件箱
+CREATE TABLE IF NOT EXISTS notifications (
+    notification_id BIGSERIAL PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, notification_id DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications(user_id) WHERE read_at IS NULL;
+
+-- 新群组落在收藏地点范围内时通知地点的主人（创建者本人除外）
+CREATE OR REPLACE FUNCTION notify_saved_places_on_group()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO notifications (user_id, kind, payload)
+    SELECT
+        sp.user_id,
+        'place_group_created',
+        jsonb_build_object(
+            'place_id', sp.place_id,
+            'place_name', sp.name,
+            'group_id', NEW.group_id,
+            'group_name', NEW.name
+        )
+    FROM saved_places sp
+    WHERE sp.user_id <> NEW.creator_id
+    AND ST_DWithin(
+        ST_SetSRID(ST_MakePoint(sp.longitude, sp.latitude), 4326)::geography,
+        ST_SetSRID(ST_MakePoint(NEW.longitude, NEW.latitude), 4326)::geography,
+        sp.radius
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS saved_places_group_trigger ON groups;
+CREATE TRIGGER saved_places_group_trigger
+AFTER INSERT ON groups
+FOR EACH ROW EXECUTE FUNCTION notify_saved_places_on_group();
+
+-- 收藏地点范围内有人签到时通知地点的主人
+-- 不包含签到者身份和坐标；隐身用户的签到不触发通知；
+-- 同一地点30分钟内只通知一次，避免热门地点刷屏
+CREATE OR REPLACE FUNCTION notify_saved_places_on_checkin()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM user_privacy_settings
+        WHERE user_id = NEW.user_id AND location_precision = 'hidden'
+    ) THEN
+        RETURN NULL;
+    END IF;
+
+    INSERT INTO notifications (user_id, kind, payload)
+    SELECT
+        sp.user_id,
+        'place_checkin',
+        jsonb_build_object(
+            'place_id', sp.place_id,
+            'place_name', sp.name,
+            'activity_id', NEW.activity_id
+        )
+    FROM saved_places sp
+    WHERE sp.user_id <> NEW.user_id
+    AND ST_DWithin(
+        ST_SetSRID(ST_MakePoint(sp.longitude, sp.latitude), 4326)::geography,
+        ST_SetSRID(ST_MakePoint(NEW.longitude, NEW.latitude), 4326)::geography,
+        sp.radius
+    )
+    AND NOT EXISTS (
+        SELECT 1 FROM notifications n
+        WHERE n.user_id = sp.user_id
+        AND n.kind = 'place_checkin'
+        AND n.payload->>'place_id' = sp.place_id
+        AND n.created_at > NOW() - INTERVAL '30 minutes'
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS saved_places_checkin_trigger ON user_activities;
+CREATE TRIGGER saved_places_checkin_trigger
+AFTER INSERT ON user_activities
+FOR EACH ROW
+WHEN (NEW.activity_type = 'USER_CHECKIN')
+EXECUTE FUNCTION notify_saved_places_on_checkin();
@@ -0,0 +1,3 @@
+-- 位置历史记录（轨迹）开关
+-- 未开启的用户只保留用于实时位置的最近定位，开启后按保留期限保存完整轨迹
+ALTER TABLE user_privacy_settings ADD COLUMN IF NOT EXISTS location_history BOOLEAN NOT NULL DEFAULT FALSE;
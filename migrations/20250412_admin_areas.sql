@@ -0,0 +1,47 @@
+-- 离线行政区划地名库，用于逆地理编码
+-- 数据来自本地导入的 GeoNames / 行政边界数据，不依赖外部服务。
+-- 大批量数据可直接用 ogr2ogr / shp2pgsql 导入本表，小批量可使用管理接口导入 GeoJSON。
+CREATE TABLE IF NOT EXISTS admin_areas (
+    area_id BIGSERIAL PRIMARY KEY,
+    -- 行政级别：1 国家，2 省/州，3 城市，4 区县
+    level SMALLINT NOT NULL CHECK (level BETWEEN 1 AND 4),
+    name VARCHAR(255) NOT NULL,
+    country_code VARCHAR(2),
+    -- 行政边界，只有点位数据（如 GeoNames）时为空
+    boundary geography(MULTIPOLYGON, 4326),
+    -- 代表点：点位数据即为该点，边界数据取面内一点
+    location geography(POINT, 4326) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_areas_boundary ON admin_areas USING GIST (boundary);
+CREATE INDEX IF NOT EXISTS idx_admin_areas_location ON admin_areas USING GIST (location);
+CREATE INDEX IF NOT EXISTS idx_admin_areas_level ON admin_areas (level);
+
+-- 解析坐标所在的指定级别行政区名称
+-- 优先取边界包含该点的区域；没有边界的点位数据取 max_distance 米内最近的一个
+CREATE OR REPLACE FUNCTION resolve_admin_area(
+    p_level SMALLINT,
+    p_latitude DOUBLE PRECISION,
+    p_longitude DOUBLE PRECISION,
+    p_max_distance DOUBLE PRECISION
+) RETURNS VARCHAR AS $$
+    SELECT a.name
+    FROM admin_areas a
+    WHERE a.level = p_level
+      AND (
+          (a.boundary IS NOT NULL AND ST_Covers(
+              a.boundary,
+              ST_SetSRID(ST_MakePoint(p_longitude, p_latitude), 4326)::geography
+          ))
+          OR (a.boundary IS NULL AND ST_DWithin(
+              a.location,
+              ST_SetSRID(ST_MakePoint(p_longitude, p_latitude), 4326)::geography,
+              p_max_distance
+          ))
+      )
+    ORDER BY
+        a.boundary IS NULL,
+        ST_Distance(a.location, ST_SetSRID(ST_MakePoint(p_longitude, p_latitude), 4326)::geography)
+    LIMIT 1
+$$ LANGUAGE sql STABLE;
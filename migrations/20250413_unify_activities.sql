@@ -0,0 +1,56 @@
+-- 合并活动存储
+-- 初始迁移同时创建了 activities 和 user_activities 两张表，代码只读写 user_activities，
+-- 这里把 activities 中的历史数据迁入 user_activities 后删除 activities 表。
+-- 类型名称原样保留，未建模的类型在代码中按原始名称读写。
+
+-- 坐标超出范围或ID与现有活动重复的历史数据无法迁入，
+-- 保存到 activities_rejected 供人工核对，不随 activities 表一起删除
+CREATE TABLE IF NOT EXISTS activities_rejected (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    type VARCHAR(50) NOT NULL,
+    content TEXT,
+    latitude DOUBLE PRECISION NOT NULL,
+    longitude DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    reason VARCHAR(30) NOT NULL,
+    rejected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO activities_rejected (
+    id, user_id, type, content, latitude, longitude, created_at, reason
+)
+SELECT
+    a.id, a.user_id, a.type, a.content, a.latitude, a.longitude, a.created_at,
+    CASE
+        WHEN a.latitude BETWEEN -90 AND 90 AND a.longitude BETWEEN -180 AND 180
+        THEN 'duplicate_id'
+        ELSE 'coordinates_out_of_range'
+    END
+FROM activities a
+WHERE NOT (a.latitude BETWEEN -90 AND 90 AND a.longitude BETWEEN -180 AND 180)
+   OR EXISTS (SELECT 1 FROM user_activities ua WHERE ua.activity_id = a.id)
+ON CONFLICT (id) DO NOTHING;
+
+-- 迁移历史数据时不应触发收藏地点的签到通知
+ALTER TABLE user_activities DISABLE TRIGGER saved_places_checkin_trigger;
+
+INSERT INTO user_activities (
+    activity_id, user_id, activity_type, activity_details, latitude, longitude, created_at
+)
+SELECT id, user_id, type, content, latitude, longitude, created_at
+FROM activities
+WHERE latitude BETWEEN -90 AND 90
+  AND longitude BETWEEN -180 AND 180
+ON CONFLICT (activity_id) DO NOTHING;
+
+ALTER TABLE user_activities ENABLE TRIGGER saved_places_checkin_trigger;
+
+DROP TABLE IF EXISTS activities;
+
+-- 地理查询统一使用 user_activities.geom 上的 GIST 索引
+UPDATE user_activities
+SET geom = ST_SetSRID(ST_MakePoint(longitude, latitude), 4326)::geography
+WHERE geom IS NULL;
+
+ALTER TABLE user_activities ALTER COLUMN geom SET NOT NULL;
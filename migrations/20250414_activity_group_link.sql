@@ -0,0 +1,9 @@
+-- 活动关联群组
+-- 创建群组、加入/离开群组和发送消息时自动记录的活动关联到对应群组，
+-- 活动位置为群组坐标；用户自己创建的活动 group_id 为空。
+ALTER TABLE user_activities
+    ADD COLUMN IF NOT EXISTS group_id VARCHAR(255) REFERENCES groups(group_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_user_activities_group
+    ON user_activities(group_id, created_at DESC)
+    WHERE group_id IS NOT NULL;
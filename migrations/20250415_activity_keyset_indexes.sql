@@ -0,0 +1,15 @@
+-- 活动列表按 (created_at, activity_id) 倒序进行游标分页，索引与排序保持一致
+CREATE INDEX IF NOT EXISTS idx_user_activities_keyset
+    ON user_activities(created_at DESC, activity_id DESC);
+
+CREATE INDEX IF NOT EXISTS idx_user_activities_user_keyset
+    ON user_activities(user_id, created_at DESC, activity_id DESC);
+
+DROP INDEX IF EXISTS idx_user_activities_group;
+CREATE INDEX IF NOT EXISTS idx_user_activities_group_keyset
+    ON user_activities(group_id, created_at DESC, activity_id DESC)
+    WHERE group_id IS NOT NULL;
+
+-- 已被上面的索引覆盖
+DROP INDEX IF EXISTS idx_user_activities_created;
+DROP INDEX IF EXISTS idx_user_activities_user_id;
@@ -0,0 +1,11 @@
+-- 用户关注关系
+CREATE TABLE IF NOT EXISTS user_follows (
+    follower_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    followee_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (follower_id, followee_id),
+    CONSTRAINT user_follows_not_self CHECK (follower_id <> followee_id)
+);
+
+-- 查询粉丝列表
+CREATE INDEX IF NOT EXISTS idx_user_follows_followee ON user_follows(followee_id, created_at DESC);
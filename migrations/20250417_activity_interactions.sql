@@ -0,0 +1,26 @@
+-- 活动点赞与评论
+
+-- 点赞，每个用户对同一活动只能点赞一次
+CREATE TABLE IF NOT EXISTS activity_likes (
+    activity_id VARCHAR(255) NOT NULL REFERENCES user_activities(activity_id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (activity_id, user_id)
+);
+
+-- 评论，parent_id 指向被回复的评论，顶层评论为空
+-- 删除的评论保留占位（deleted_at 非空、content 为空），回复仍然挂在原来的位置
+CREATE TABLE IF NOT EXISTS activity_comments (
+    comment_id BIGSERIAL PRIMARY KEY,
+    activity_id VARCHAR(255) NOT NULL REFERENCES user_activities(activity_id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    parent_id BIGINT REFERENCES activity_comments(comment_id) ON DELETE CASCADE,
+    content TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ,
+    CONSTRAINT activity_comments_content_present CHECK (deleted_at IS NOT NULL OR content IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_comments_activity ON activity_comments(activity_id, comment_id);
+CREATE INDEX IF NOT EXISTS idx_activity_comments_parent ON activity_comments(parent_id)
+    WHERE parent_id IS NOT NULL;
@@ -0,0 +1,67 @@
+-- 活动可见范围
+-- public: 所有人；followers: 关注发布者的用户；group: 关联群组（group_id）的成员；private: 仅发布者自己
+ALTER TABLE user_activities
+    ADD COLUMN IF NOT EXISTS visibility VARCHAR(20) NOT NULL DEFAULT 'public'
+    CONSTRAINT user_activities_visibility_check
+        CHECK (visibility IN ('public', 'followers', 'group', 'private'));
+
+-- 自动记录的群组活动（创建、加入、离开群组和发送消息）只对群组成员可见
+UPDATE user_activities SET visibility = 'group' WHERE group_id IS NOT NULL;
+
+-- 活动对指定用户是否可见，所有读取活动的查询都通过它过滤
+CREATE OR REPLACE FUNCTION activity_visible_to(
+    p_owner_id VARCHAR,
+    p_visibility VARCHAR,
+    p_group_id VARCHAR,
+    p_viewer_id VARCHAR
+) RETURNS BOOLEAN AS $$
+    SELECT p_owner_id = p_viewer_id
+        OR p_visibility = 'public'
+        OR (p_visibility = 'followers' AND EXISTS (
+            SELECT 1 FROM user_follows f
+            WHERE f.follower_id = p_viewer_id AND f.followee_id = p_owner_id
+        ))
+        OR (p_visibility = 'group' AND EXISTS (
+            SELECT 1 FROM group_members gm
+            WHERE gm.group_id = p_group_id AND gm.user_id = p_viewer_id
+        ))
+$$ LANGUAGE sql STABLE;
+
+-- 收藏地点的签到通知只发给能看到该签到的用户
+CREATE OR REPLACE FUNCTION notify_saved_places_on_checkin()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM user_privacy_settings
+        WHERE user_id = NEW.user_id AND location_precision = 'hidden'
+    ) THEN
+        RETURN NULL;
+    END IF;
+
+    INSERT INTO notifications (user_id, kind, payload)
+    SELECT
+        sp.user_id,
+        'place_checkin',
+        jsonb_build_object(
+            'place_id', sp.place_id,
+            'place_name', sp.name,
+            'activity_id', NEW.activity_id
+        )
+    FROM saved_places sp
+    WHERE sp.user_id <> NEW.user_id
+    AND activity_visible_to(NEW.user_id, NEW.visibility, NEW.group_id, sp.user_id)
+    AND ST_DWithin(
+        ST_SetSRID(ST_MakePoint(sp.longitude, sp.latitude), 4326)::geography,
+        ST_SetSRID(ST_MakePoint(NEW.longitude, NEW.latitude), 4326)::geography,
+        sp.radius
+    )
+    AND NOT EXISTS (
+        SELECT 1 FROM notifications n
+        WHERE n.user_id = sp.user_id
+        AND n.kind = 'place_checkin'
+        AND n.payload->>'place_id' = sp.place_id
+        AND n.created_at > NOW() - INTERVAL '30 minutes'
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,17 @@
+-- 签到照片
+-- 文件保存在媒体存储中，这里只记录对象键；keeps_location 表示上传者选择保留了 EXIF 中的 GPS 信息
+CREATE TABLE IF NOT EXISTS activity_photos (
+    photo_id VARCHAR(255) PRIMARY KEY,
+    activity_id VARCHAR(255) NOT NULL REFERENCES user_activities(activity_id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    storage_key VARCHAR(512) NOT NULL,
+    thumbnail_key VARCHAR(512) NOT NULL,
+    content_type VARCHAR(50) NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    keeps_location BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_photos_activity ON activity_photos(activity_id, created_at);
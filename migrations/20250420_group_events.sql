@@ -0,0 +1,48 @@
+-- 群组聚会
+-- 由群组管理员创建，有开始和结束时间；位置默认为群组所在位置
+-- 取消的聚会保留记录（cancelled_at 非空），不再出现在附近搜索中
+CREATE TABLE IF NOT EXISTS group_events (
+    event_id VARCHAR(255) PRIMARY KEY,
+    group_id VARCHAR(255) NOT NULL REFERENCES groups(group_id) ON DELETE CASCADE,
+    creator_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    title VARCHAR(100) NOT NULL,
+    description TEXT,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    location_name VARCHAR(255) NOT NULL,
+    latitude DOUBLE PRECISION NOT NULL,
+    longitude DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    cancelled_at TIMESTAMPTZ,
+    -- 开始前提醒已发送的时间，避免重复提醒
+    reminder_sent_at TIMESTAMPTZ,
+    CONSTRAINT group_events_time_range CHECK (ends_at > starts_at),
+    CONSTRAINT group_events_latitude_range CHECK (latitude BETWEEN -90 AND 90),
+    CONSTRAINT group_events_longitude_range CHECK (longitude BETWEEN -180 AND 180)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_events_group ON group_events(group_id, starts_at);
+CREATE INDEX IF NOT EXISTS idx_group_events_location ON group_events USING GIST (
+    (ST_SetSRID(ST_MakePoint(longitude, latitude), 4326)::geography)
+) WHERE cancelled_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_group_events_reminder ON group_events(starts_at)
+    WHERE reminder_sent_at IS NULL AND cancelled_at IS NULL;
+
+-- 成员对聚会的回复
+CREATE TABLE IF NOT EXISTS event_rsvps (
+    event_id VARCHAR(255) NOT NULL REFERENCES group_events(event_id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL
+        CONSTRAINT event_rsvps_status_check CHECK (status IN ('going', 'maybe', 'not_going')),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_rsvps_user ON event_rsvps(user_id);
+
+-- 聚会相关的活动关联到聚会，出现在群组的活动流中
+ALTER TABLE user_activities
+    ADD COLUMN IF NOT EXISTS event_id VARCHAR(255) REFERENCES group_events(event_id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_user_activities_event ON user_activities(event_id)
+    WHERE event_id IS NOT NULL;
@@ -0,0 +1,34 @@
+-- 消息类型，取值与 MessageType 一致：0 文本，3 投票
+ALTER TABLE messages
+    ADD COLUMN IF NOT EXISTS message_type INTEGER NOT NULL DEFAULT 0;
+
+-- 群聊投票，一条投票消息对应一个投票，问题保存在消息内容中
+-- closes_at 为空时投票一直开放
+CREATE TABLE IF NOT EXISTS message_polls (
+    message_id VARCHAR(255) PRIMARY KEY REFERENCES messages(message_id) ON DELETE CASCADE,
+    multiple_choice BOOLEAN NOT NULL DEFAULT FALSE,
+    anonymous BOOLEAN NOT NULL DEFAULT FALSE,
+    closes_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 投票选项，option_id 为选项在投票中的序号（从0开始）
+CREATE TABLE IF NOT EXISTS message_poll_options (
+    message_id VARCHAR(255) NOT NULL REFERENCES message_polls(message_id) ON DELETE CASCADE,
+    option_id INTEGER NOT NULL,
+    text VARCHAR(100) NOT NULL,
+    PRIMARY KEY (message_id, option_id)
+);
+
+-- 成员的投票，多选投票每个选项一行
+CREATE TABLE IF NOT EXISTS message_poll_votes (
+    message_id VARCHAR(255) NOT NULL,
+    option_id INTEGER NOT NULL,
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    voted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (message_id, option_id, user_id),
+    FOREIGN KEY (message_id, option_id)
+        REFERENCES message_poll_options(message_id, option_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_poll_votes_user ON message_poll_votes(message_id, user_id);
@@ -0,0 +1,74 @@
+-- 地图聚合点与矢量瓦片中的活动位置按发布者的位置隐私设置处理
+-- 计算规则与 LocationPrivacy::apply 一致，修改时需同步修改两处
+
+-- 摘要中从 p_offset 开始的4个字节按大端序转换为 [0, 1) 之间的数
+CREATE OR REPLACE FUNCTION privacy_digest_unit(p_digest BYTEA, p_offset INTEGER)
+RETURNS DOUBLE PRECISION AS $$
+    SELECT (
+        (get_byte(p_digest, p_offset)::BIGINT << 24)
+        | (get_byte(p_digest, p_offset + 1)::BIGINT << 16)
+        | (get_byte(p_digest, p_offset + 2)::BIGINT << 8)
+        | get_byte(p_digest, p_offset + 3)::BIGINT
+    )::DOUBLE PRECISION / 4294967296.0
+$$ LANGUAGE sql IMMUTABLE;
+
+-- 将经度规范到 [-180, 180]
+CREATE OR REPLACE FUNCTION privacy_wrap_longitude(p_longitude DOUBLE PRECISION)
+RETURNS DOUBLE PRECISION AS $$
+    SELECT CASE
+        WHEN p_longitude BETWEEN -180 AND 180 THEN p_longitude
+        ELSE (p_longitude + 180) - 360 * floor((p_longitude + 180) / 360) - 180
+    END
+$$ LANGUAGE sql IMMUTABLE;
+
+-- 计算用户位置对查看者展示的位置，隐身用户不返回行，查看者自己的位置保持精确
+CREATE OR REPLACE FUNCTION obscure_location(
+    p_user_id VARCHAR,
+    p_viewer_id VARCHAR,
+    p_secret TEXT,
+    p_latitude DOUBLE PRECISION,
+    p_longitude DOUBLE PRECISION,
+    p_at TIMESTAMP WITH TIME ZONE
+) RETURNS TABLE (latitude DOUBLE PRECISION, longitude DOUBLE PRECISION) AS $$
+DECLARE
+    v_precision VARCHAR;
+    v_radius INTEGER;
+    v_digest BYTEA;
+    v_angle DOUBLE PRECISION;
+    v_distance DOUBLE PRECISION;
+BEGIN
+    IF p_user_id IS DISTINCT FROM p_viewer_id THEN
+        SELECT s.location_precision, s.fuzz_radius INTO v_precision, v_radius
+        FROM user_privacy_settings s
+        WHERE s.user_id = p_user_id;
+    END IF;
+    v_precision := COALESCE(v_precision, 'exact');
+
+    IF v_precision = 'exact' THEN
+        latitude := p_latitude;
+        longitude := p_longitude;
+    ELSIF v_precision = 'fuzzed' AND v_radius > 0 THEN
+        -- 偏移由密钥、用户ID和所在小时决定，在圆内均匀取点
+        v_digest := sha256(
+            convert_to(p_secret, 'UTF8') || '\x3a'::BYTEA
+            || convert_to(p_user_id, 'UTF8') || '\x3a'::BYTEA
+            || int8send(floor(extract(epoch FROM p_at) / 3600)::BIGINT)
+        );
+        v_angle := 2 * pi() * privacy_digest_unit(v_digest, 0);
+        v_distance := v_radius * sqrt(privacy_digest_unit(v_digest, 4));
+        latitude := LEAST(GREATEST(p_latitude + v_distance * cos(v_angle) / 111320.0, -90), 90);
+        longitude := privacy_wrap_longitude(
+            p_longitude + v_distance * sin(v_angle)
+                / GREATEST(111320.0 * cos(radians(p_latitude)), 1)
+        );
+    ELSIF v_precision = 'city' THEN
+        -- 城市级位置为 0.1 度网格的中心
+        latitude := LEAST(GREATEST((floor(p_latitude / 0.1) + 0.5) * 0.1, -90), 90);
+        longitude := privacy_wrap_longitude((floor(p_longitude / 0.1) + 0.5) * 0.1);
+    ELSE
+        RETURN;
+    END IF;
+
+    RETURN NEXT;
+END;
+$$ LANGUAGE plpgsql STABLE;
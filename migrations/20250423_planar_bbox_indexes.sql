@@ -0,0 +1,4 @@
+-- 矩形范围查询按经纬度平面判断相交（矩形的边沿经线和纬线，而不是大圆弧），
+-- 在 geography 列转换为 geometry 的表达式上建立 GIST 索引
+CREATE INDEX IF NOT EXISTS idx_groups_geom_planar ON groups USING GIST ((geom::geometry));
+CREATE INDEX IF NOT EXISTS idx_user_activities_geom_planar ON user_activities USING GIST ((geom::geometry));
@@ -0,0 +1,7 @@
+-- 热力图改为按位置隐私设置处理后的位置聚合，清空按真实位置聚合的数据，
+-- 由聚合任务在保留期内重新聚合
+DELETE FROM activity_heatmap_cells;
+
+UPDATE activity_heatmap_state
+SET aggregated_until = '-infinity'
+WHERE id;
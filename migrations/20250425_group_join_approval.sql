@@ -0,0 +1,12 @@
+-- 需要管理员审批才能加入的群组
+ALTER TABLE groups ADD COLUMN IF NOT EXISTS requires_approval BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- 待审批的加入申请，通过后加入群组（群组已满时进入候补队列），拒绝或撤回后删除
+CREATE TABLE IF NOT EXISTS group_join_requests (
+    group_id VARCHAR(255) NOT NULL REFERENCES groups(group_id),
+    user_id VARCHAR(255) NOT NULL REFERENCES users(user_id),
+    requested_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_join_requests_queue ON group_join_requests(group_id, requested_at, user_id);
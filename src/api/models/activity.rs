@@ -1,21 +1,11 @@
 // 活动相关的数据结构定义
 
+use crate::api::models::photo::ActivityPhoto;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-/// 活动类型枚举
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum ActivityType {
-    /// 群组创建
-    GroupCreated,
-    /// 用户加入群组
-    UserJoined,
-    /// 消息发送
-    MessageSent,
-    /// 用户签到
-    UserCheckedIn,
-}
+/// 活动类型和可见范围，与数据库实体共用
+pub use crate::database::models::activity::{ActivityType, ActivityVisibility};
 
 /// 获取附近活动请求
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,8 +16,10 @@ pub struct GetNearbyActivitiesRequest {
     pub longitude: f64,
     /// 搜索半径（米）
     pub radius: u32,
-    /// 活动数量限制
-    pub limit: u32,
+    /// 每页数量，默认20，不超过配置的最大值
+    pub limit: Option<u32>,
+    /// 上一页返回的游标，为空时从最新的活动开始
+    pub cursor: Option<String>,
 }
 
 /// 活动详情
@@ -41,6 +33,8 @@ pub struct ActivityDetail {
     pub group_id: String,
     /// 群组名称
     pub group_name: String,
+    /// 关联的群组聚会ID，仅聚会相关的活动有值
+    pub event_id: Option<String>,
     /// 关联的用户ID
     pub user_id: String,
     /// 用户名称
@@ -53,15 +47,33 @@ pub struct ActivityDetail {
     pub latitude: f64,
     /// 发生位置的经度
     pub longitude: f64,
+    /// 发生位置所在的行政区名称，由离线地名库解析
+    pub place_name: Option<String>,
     /// 与查询位置的距离（米）
     pub distance: Option<f64>,
+    /// 点赞数
+    pub like_count: i64,
+    /// 评论数（不含已删除的评论）
+    pub comment_count: i64,
+    /// 当前用户是否已点赞
+    pub liked: bool,
+    /// 可见范围
+    pub visibility: ActivityVisibility,
+    /// 附带的照片
+    pub photos: Vec<ActivityPhoto>,
 }
 
 /// 获取附近活动响应
+///
+/// 按隐私设置过滤后单页可能少于请求的数量，是否还有下一页以 `has_more` 为准。
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetNearbyActivitiesResponse {
     /// 活动列表
     pub activities: Vec<ActivityDetail>,
+    /// 下一页游标
+    pub next_cursor: Option<String>,
+    /// 是否还有更多
+    pub has_more: bool,
 }
 
 /// 创建用户活动请求
@@ -75,6 +87,11 @@ pub struct CreateUserActivityRequest {
     pub latitude: f64,
     /// 经度
     pub longitude: f64,
+    /// 可见范围，默认所有人可见
+    #[serde(default)]
+    pub visibility: ActivityVisibility,
+    /// 关联的群组ID，可见范围为 `group` 时必填，只能关联自己所在的群组
+    pub group_id: Option<String>,
 }
 
 /// 创建用户活动响应
@@ -118,7 +135,9 @@ pub struct NearbyUser {
     /// 用户昵称
     pub nickname: String,
     /// 最后一次活动
-    pub last_activity: UserActivity,
+    pub last_activity: Option<UserActivity>,
+    /// 位置最后更新时间
+    pub location_updated_at: DateTime<Utc>,
     /// 与查询位置的距离（米）
     pub distance: Option<f64>,
 }
@@ -133,8 +152,10 @@ pub struct FindNearbyUsersResponse {
 /// 查找用户活动请求
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FindUserActivitiesRequest {
-    /// 活动数量限制
-    pub limit: u32,
+    /// 每页数量，默认20，不超过配置的最大值
+    pub limit: Option<u32>,
+    /// 上一页返回的游标，为空时从最新的活动开始
+    pub cursor: Option<String>,
     /// 要查询的用户ID，如果不提供则查询当前认证用户的活动
     pub user_id: Option<String>,
 }
@@ -153,8 +174,10 @@ pub struct FindUserActivitiesResponse {
 /// 查找群组活动请求
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FindGroupActivitiesRequest {
-    /// 活动数量限制
-    pub limit: u32,
+    /// 每页数量，默认20，不超过配置的最大值
+    pub limit: Option<u32>,
+    /// 上一页返回的游标，为空时从最新的活动开始
+    pub cursor: Option<String>,
 }
 
 /// 查找群组活动响应
@@ -162,13 +185,19 @@ pub struct FindGroupActivitiesRequest {
 pub struct FindGroupActivitiesResponse {
     /// 活动列表
     pub activities: Vec<ActivityDetail>,
+    /// 下一页游标
+    pub next_cursor: Option<String>,
+    /// 是否还有更多
+    pub has_more: bool,
 }
 
 /// 获取全部活动请求
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetAllActivitiesRequest {
-    /// 活动数量限制
+    /// 每页数量，默认20，不超过配置的最大值
     pub limit: Option<u32>,
+    /// 上一页返回的游标，为空时从最新的活动开始
+    pub cursor: Option<String>,
 }
 
 /// 获取全部活动响应
@@ -176,4 +205,57 @@ pub struct GetAllActivitiesRequest {
 pub struct GetAllActivitiesResponse {
     /// 活动列表
     pub activities: Vec<ActivityDetail>,
+    /// 下一页游标
+    pub next_cursor: Option<String>,
+    /// 是否还有更多
+    pub has_more: bool,
+}
+
+/// 获取活动热力图请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetActivityHeatmapRequest {
+    /// 西边界经度
+    pub min_longitude: f64,
+    /// 南边界纬度
+    pub min_latitude: f64,
+    /// 东边界经度，小于西边界时表示跨越180度经线
+    pub max_longitude: f64,
+    /// 北边界纬度
+    pub max_latitude: f64,
+    /// 统计最近多少小时的活动，默认24小时
+    #[serde(default = "default_heatmap_hours")]
+    pub hours: u32,
+    /// 只统计指定类型的活动
+    pub activity_type: Option<ActivityType>,
+    /// 网格精度（geohash 位数，4-7），默认5
+    #[serde(default = "default_heatmap_precision")]
+    pub precision: u8,
+}
+
+fn default_heatmap_hours() -> u32 {
+    24
+}
+
+fn default_heatmap_precision() -> u8 {
+    5
+}
+
+/// 热力图网格
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeatmapCellInfo {
+    /// 网格的 geohash
+    pub cell: String,
+    /// 网格中心纬度
+    pub latitude: f64,
+    /// 网格中心经度
+    pub longitude: f64,
+    /// 时间窗口内的活动数量
+    pub weight: i64,
+}
+
+/// 获取活动热力图响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetActivityHeatmapResponse {
+    /// 网格列表，按活动数量从高到低排序
+    pub cells: Vec<HeatmapCellInfo>,
 }
@@ -0,0 +1,95 @@
+// 管理接口相关的数据结构定义
+
+use crate::api::models::group::GroupCategory;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 导入群组时每个 GeoJSON 要素的属性
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportGroupProperties {
+    /// 群组名称
+    pub name: String,
+    /// 群组位置名称
+    pub location_name: String,
+    /// 群组描述
+    pub description: Option<String>,
+    /// 群组分类
+    pub category: Option<GroupCategory>,
+    /// 群组标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 人数上限
+    pub max_members: Option<i32>,
+    /// 过期时间
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// 导入成功的群组
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportedGroup {
+    /// 要素在 FeatureCollection 中的序号（从0开始）
+    pub index: usize,
+    /// 新创建的群组ID
+    pub group_id: String,
+}
+
+/// 导入失败的要素
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportFeatureError {
+    /// 要素在 FeatureCollection 中的序号（从0开始）
+    pub index: usize,
+    /// 失败原因
+    pub message: String,
+}
+
+/// 导入群组响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportGroupsResponse {
+    /// 成功创建的群组
+    pub created: Vec<ImportedGroup>,
+    /// 导入失败的要素
+    pub errors: Vec<ImportFeatureError>,
+}
+
+/// 行政区划级别
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminAreaLevel {
+    /// 国家
+    Country,
+    /// 省/州
+    Province,
+    /// 城市
+    City,
+    /// 区县
+    District,
+}
+
+/// 导入行政区划时每个 GeoJSON 要素的属性
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportAdminAreaProperties {
+    /// 行政级别
+    pub level: AdminAreaLevel,
+    /// 行政区名称
+    pub name: String,
+    /// ISO 3166-1 二位国家代码
+    pub country_code: Option<String>,
+}
+
+/// 导入成功的行政区
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportedAdminArea {
+    /// 要素在 FeatureCollection 中的序号（从0开始）
+    pub index: usize,
+    /// 新创建的行政区ID
+    pub area_id: i64,
+}
+
+/// 导入行政区划响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportAdminAreasResponse {
+    /// 成功导入的行政区
+    pub created: Vec<ImportedAdminArea>,
+    /// 导入失败的要素
+    pub errors: Vec<ImportFeatureError>,
+}
@@ -0,0 +1,134 @@
+// 群组聚会相关的数据结构定义
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 聚会回复，与数据库实体共用
+pub use crate::database::models::event::RsvpStatus;
+
+/// 创建聚会请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateEventRequest {
+    /// 标题
+    pub title: String,
+    /// 描述（可选）
+    pub description: Option<String>,
+    /// 开始时间
+    pub starts_at: DateTime<Utc>,
+    /// 结束时间
+    pub ends_at: DateTime<Utc>,
+    /// 聚会地点纬度，与经度同时为空时使用群组位置
+    pub latitude: Option<f64>,
+    /// 聚会地点经度，与纬度同时为空时使用群组位置
+    pub longitude: Option<f64>,
+    /// 地点名称，为空时使用群组的位置名称
+    pub location_name: Option<String>,
+}
+
+/// 聚会状态，按当前时间计算
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventStatus {
+    /// 尚未开始
+    Upcoming,
+    /// 正在进行
+    Ongoing,
+    /// 已结束
+    Ended,
+    /// 已取消
+    Cancelled,
+}
+
+/// 聚会详情
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventDetail {
+    /// 聚会ID
+    pub event_id: String,
+    /// 所属群组ID
+    pub group_id: String,
+    /// 所属群组名称
+    pub group_name: String,
+    /// 标题
+    pub title: String,
+    /// 描述
+    pub description: Option<String>,
+    /// 开始时间
+    pub starts_at: DateTime<Utc>,
+    /// 结束时间
+    pub ends_at: DateTime<Utc>,
+    /// 地点名称
+    pub location_name: String,
+    /// 地点纬度
+    pub latitude: f64,
+    /// 地点经度
+    pub longitude: f64,
+    /// 当前状态
+    pub status: EventStatus,
+    /// 回复参加的人数
+    pub going_count: i64,
+    /// 回复可能参加的人数
+    pub maybe_count: i64,
+    /// 当前用户的回复，未回复时为空
+    pub my_rsvp: Option<RsvpStatus>,
+    /// 与查询位置的距离（米），仅附近搜索有值
+    pub distance: Option<f64>,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+}
+
+/// 获取群组聚会列表请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListGroupEventsRequest {
+    /// 是否包含已结束和已取消的聚会，默认不包含
+    #[serde(default)]
+    pub include_past: bool,
+    /// 数量限制，默认20
+    pub limit: Option<u32>,
+}
+
+/// 获取附近聚会请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetNearbyEventsRequest {
+    /// 纬度
+    pub latitude: f64,
+    /// 经度
+    pub longitude: f64,
+    /// 搜索半径（米）
+    pub radius: u32,
+    /// 数量限制，默认20
+    pub limit: Option<u32>,
+}
+
+/// 聚会列表响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListEventsResponse {
+    /// 聚会列表
+    pub events: Vec<EventDetail>,
+}
+
+/// 回复聚会请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RsvpEventRequest {
+    /// 回复
+    pub status: RsvpStatus,
+}
+
+/// 回复或撤回回复的响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RsvpEventResponse {
+    /// 聚会ID
+    pub event_id: String,
+    /// 当前用户的回复，撤回后为空
+    pub my_rsvp: Option<RsvpStatus>,
+    /// 回复参加的人数
+    pub going_count: i64,
+    /// 回复可能参加的人数
+    pub maybe_count: i64,
+}
+
+/// 取消聚会响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelEventResponse {
+    /// 被取消的聚会ID
+    pub event_id: String,
+}
@@ -0,0 +1,49 @@
+// 首页信息流相关的数据结构定义
+
+use crate::api::models::activity::ActivityDetail;
+use serde::{Deserialize, Serialize};
+
+/// 获取首页信息流请求
+///
+/// 提供当前位置时会合并附近的公开签到，并按距离参与排序。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetHomeFeedRequest {
+    /// 当前位置纬度
+    pub latitude: Option<f64>,
+    /// 当前位置经度
+    pub longitude: Option<f64>,
+    /// 附近签到的搜索半径（米），默认使用配置的最大搜索半径
+    pub radius: Option<u32>,
+    /// 返回数量，默认20，不超过配置的最大值
+    pub limit: Option<u32>,
+}
+
+/// 信息流条目的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedSource {
+    /// 关注的用户
+    Following,
+    /// 加入的群组
+    Group,
+    /// 附近的公开签到
+    Nearby,
+}
+
+/// 首页信息流条目
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeedItem {
+    /// 活动详情，提供当前位置时 `distance` 为与当前位置的距离
+    pub activity: ActivityDetail,
+    /// 来源，同时满足多个来源时取关注 > 群组 > 附近
+    pub source: FeedSource,
+    /// 排序分数，越大越靠前
+    pub score: f64,
+}
+
+/// 获取首页信息流响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetHomeFeedResponse {
+    /// 信息流条目，按排序分数降序
+    pub items: Vec<FeedItem>,
+}
@@ -0,0 +1,31 @@
+// 用户关注相关的数据结构定义
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 关注或取消关注的响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FollowResponse {
+    /// 对方的公开用户ID
+    pub user_id: String,
+    /// 当前是否已关注
+    pub following: bool,
+}
+
+/// 关注列表或粉丝列表中的用户
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FollowUserInfo {
+    /// 公开用户ID
+    pub user_id: String,
+    /// 昵称
+    pub nickname: String,
+    /// 关注时间
+    pub followed_at: DateTime<Utc>,
+}
+
+/// 关注列表或粉丝列表响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FollowListResponse {
+    /// 用户列表，按关注时间倒序
+    pub users: Vec<FollowUserInfo>,
+}
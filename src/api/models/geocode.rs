@@ -0,0 +1,27 @@
+// 逆地理编码相关的数据结构定义
+
+use serde::{Deserialize, Serialize};
+
+/// 逆地理编码请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReverseGeocodeRequest {
+    /// 纬度
+    pub latitude: f64,
+    /// 经度
+    pub longitude: f64,
+}
+
+/// 逆地理编码响应，地名库中没有对应数据的级别为空
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReverseGeocodeResponse {
+    /// 国家
+    pub country: Option<String>,
+    /// 省/州
+    pub province: Option<String>,
+    /// 城市
+    pub city: Option<String>,
+    /// 区县
+    pub district: Option<String>,
+    /// 用于展示的地名，与自动填充的群组位置名称一致
+    pub display_name: Option<String>,
+}
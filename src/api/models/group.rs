@@ -1,7 +1,66 @@
 // 群组相关的数据结构定义
 
-use serde::{Deserialize, Serialize};
+use crate::api::models::common::Location;
+pub use crate::database::models::group::{GroupAccess, GroupSortBy};
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// ------------------------
+// 枚举类型
+// ------------------------
+
+/// 群组分类
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupCategory {
+    /// 美食
+    Food,
+    /// 运动
+    Sports,
+    /// 学习
+    Study,
+    /// 社交
+    Social,
+    /// 户外
+    Outdoor,
+    /// 娱乐
+    Entertainment,
+    /// 活动
+    Event,
+    /// 其他
+    Other,
+}
+
+impl GroupCategory {
+    /// 数据库中保存的分类名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupCategory::Food => "food",
+            GroupCategory::Sports => "sports",
+            GroupCategory::Study => "study",
+            GroupCategory::Social => "social",
+            GroupCategory::Outdoor => "outdoor",
+            GroupCategory::Entertainment => "entertainment",
+            GroupCategory::Event => "event",
+            GroupCategory::Other => "other",
+        }
+    }
+
+    /// 从数据库中保存的分类名称解析
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "food" => Some(GroupCategory::Food),
+            "sports" => Some(GroupCategory::Sports),
+            "study" => Some(GroupCategory::Study),
+            "social" => Some(GroupCategory::Social),
+            "outdoor" => Some(GroupCategory::Outdoor),
+            "entertainment" => Some(GroupCategory::Entertainment),
+            "event" => Some(GroupCategory::Event),
+            "other" => Some(GroupCategory::Other),
+            _ => None,
+        }
+    }
+}
 
 // ------------------------
 // API 请求参数类型
@@ -12,8 +71,8 @@ use chrono::{DateTime, Utc};
 pub struct CreateGroupRequest {
     /// 群组名称
     pub name: String,
-    /// 群组位置名称（如城市、地区等）
-    pub location_name: String,
+    /// 群组位置名称（如城市、地区等），省略时按坐标从离线地名库解析
+    pub location_name: Option<String>,
     /// 纬度
     pub latitude: f64,
     /// 经度
@@ -22,6 +81,22 @@ pub struct CreateGroupRequest {
     pub description: Option<String>,
     /// 可选的群组密码
     pub password: Option<String>,
+    /// 可选的过期时间（活动类群组），到期后群组自动归档
+    pub expires_at: Option<DateTime<Utc>>,
+    /// 可选的人数上限
+    pub max_members: Option<i32>,
+    /// 可选的群组分类
+    pub category: Option<GroupCategory>,
+    /// 群组标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 可选的地理围栏半径（米），以群组位置为圆心
+    pub geofence_radius: Option<u32>,
+    /// 可选的地理围栏多边形顶点，与半径二选一
+    pub geofence_polygon: Option<Vec<Location>>,
+    /// 是否需要管理员审批才能加入，默认不需要
+    #[serde(default)]
+    pub requires_approval: bool,
 }
 
 /// 搜索附近群组请求
@@ -34,12 +109,72 @@ pub struct SearchNearbyGroupsRequest {
     /// 搜索半径（米），默认5000米
     #[serde(default = "default_radius")]
     pub radius: u32,
+    /// 必须同时包含的标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 分类
+    pub category: Option<GroupCategory>,
+    /// 加入方式
+    pub access: Option<GroupAccess>,
+    /// 最少成员数
+    pub min_members: Option<u32>,
+    /// 最近多少小时内有活跃
+    pub active_within_hours: Option<u32>,
+    /// 排序方式，默认按距离
+    #[serde(default)]
+    pub sort_by: GroupSortBy,
+    /// 页码，从1开始
+    #[serde(default = "default_page")]
+    pub page: u32,
+    /// 每页数量
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+}
+
+/// 按名称搜索群组的查询参数
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchGroupsByNameParams {
+    /// 可选的纬度，与经度同时提供时距离越近的群组排名越靠前
+    pub latitude: Option<f64>,
+    /// 可选的经度
+    pub longitude: Option<f64>,
+    /// 必须同时包含的标签，以逗号分隔
+    pub tags: Option<String>,
+    /// 分类
+    pub category: Option<GroupCategory>,
+    /// 加入方式
+    pub access: Option<GroupAccess>,
+    /// 最少成员数
+    pub min_members: Option<u32>,
+    /// 最近多少小时内有活跃
+    pub active_within_hours: Option<u32>,
+    /// 排序方式，默认按名称匹配度
+    #[serde(default = "default_name_sort")]
+    pub sort_by: GroupSortBy,
+    /// 页码，从1开始
+    #[serde(default = "default_page")]
+    pub page: u32,
+    /// 每页数量
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
 }
 
 fn default_radius() -> u32 {
     5000
 }
 
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    20
+}
+
+fn default_name_sort() -> GroupSortBy {
+    GroupSortBy::Relevance
+}
+
 /// 加入群组请求
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JoinGroupRequest {
@@ -116,6 +251,23 @@ pub struct GroupDetailedInfo {
     pub location_name: String,
     /// 是否需要密码才能加入
     pub is_password_required: bool,
+    /// 过期时间（如果设置）
+    pub expires_at: Option<DateTime<Utc>>,
+    /// 人数上限（如果设置）
+    pub max_members: Option<i32>,
+    /// 群组分类
+    pub category: Option<GroupCategory>,
+    /// 群组标签
+    pub tags: Vec<String>,
+    /// 地理围栏半径（米，如果设置）
+    pub geofence_radius: Option<i32>,
+    /// 是否设置了地理围栏，设置后需在围栏内才能加入和发言
+    pub is_geofenced: bool,
+    /// 是否需要管理员审批才能加入
+    pub requires_approval: bool,
+    /// 名称搜索的匹配得分，越高越相关，仅名称搜索时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
 }
 
 /// 群组心跳请求
@@ -145,6 +297,40 @@ pub struct NearbyGroupInfo {
 pub struct JoinGroupWithPasswordRequest {
     /// 密码
     pub password: Option<String>,
+    /// 群组已满时是否加入候补队列
+    #[serde(default)]
+    pub join_waitlist: bool,
+    /// 当前纬度（群组设置了地理围栏时必填）
+    pub latitude: Option<f64>,
+    /// 当前经度（群组设置了地理围栏时必填）
+    pub longitude: Option<f64>,
+}
+
+/// 加入群组结果响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupJoinStatusResponse {
+    /// 请求是否成功，加入群组、进入候补队列或提交申请时均为 true（与原加入群组响应兼容）
+    pub success: bool,
+    /// 是否已成为群组成员
+    pub joined: bool,
+    /// 是否已进入候补队列
+    pub waitlisted: bool,
+    /// 候补队列中的位置（从1开始），仅在候补时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waitlist_position: Option<i64>,
+    /// 是否已提交加入申请，等待管理员审批
+    pub pending_approval: bool,
+}
+
+/// 待审批的加入申请
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupJoinRequestInfo {
+    /// 申请者公开ID
+    pub public_user_id: String,
+    /// 申请者昵称
+    pub nickname: String,
+    /// 申请时间
+    pub requested_at: DateTime<Utc>,
 }
 
 /// 设置成员角色请求 (别名，与UpdateMemberRoleRequest相同)
@@ -163,4 +349,4 @@ pub type GroupMember = GroupMemberProfile;
 pub type JoinGroupResponse = GroupJoinResponse;
 
 /// 附近群组请求 (别名，与SearchNearbyGroupsRequest相同)
-pub type NearbyGroupsRequest = SearchNearbyGroupsRequest; 
\ No newline at end of file
+pub type NearbyGroupsRequest = SearchNearbyGroupsRequest;
@@ -0,0 +1,72 @@
+// 活动点赞与评论相关的数据结构定义
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 点赞或取消点赞的响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActivityLikeResponse {
+    /// 活动ID
+    pub activity_id: String,
+    /// 当前是否已点赞
+    pub liked: bool,
+    /// 点赞数
+    pub like_count: i64,
+}
+
+/// 发表评论请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateActivityCommentRequest {
+    /// 评论内容
+    pub content: String,
+    /// 回复的评论ID，为空时为顶层评论
+    pub parent_id: Option<i64>,
+}
+
+/// 获取评论列表请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListActivityCommentsRequest {
+    /// 上一页最后一条评论的ID，为空时从最早的评论开始
+    pub after_id: Option<i64>,
+    /// 评论数量限制，默认50
+    pub limit: Option<u32>,
+}
+
+/// 活动评论
+///
+/// 已删除但仍有回复的评论保留占位，`is_deleted` 为 `true`，作者和内容为空。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActivityComment {
+    /// 评论ID
+    pub comment_id: i64,
+    /// 回复的评论ID，顶层评论为空
+    pub parent_id: Option<i64>,
+    /// 作者的公开用户ID
+    pub user_id: Option<String>,
+    /// 作者昵称
+    pub nickname: Option<String>,
+    /// 评论内容
+    pub content: Option<String>,
+    /// 是否已删除
+    pub is_deleted: bool,
+    /// 当前用户是否可以删除（评论作者或活动发布者）
+    pub can_delete: bool,
+    /// 发表时间
+    pub created_at: DateTime<Utc>,
+}
+
+/// 获取评论列表响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListActivityCommentsResponse {
+    /// 评论列表，按发表顺序排列，根据 `parent_id` 组织成树
+    pub comments: Vec<ActivityComment>,
+    /// 下一页的 `after_id`，没有更多评论时为空
+    pub next_after_id: Option<i64>,
+}
+
+/// 删除评论响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteActivityCommentResponse {
+    /// 被删除的评论ID
+    pub comment_id: i64,
+}
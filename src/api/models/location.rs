@@ -0,0 +1,110 @@
+// 用户位置相关的数据结构定义
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 上报用户位置请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateLocationRequest {
+    /// 纬度
+    pub latitude: f64,
+    /// 经度
+    pub longitude: f64,
+    /// 定位误差（米）
+    pub accuracy: Option<f64>,
+}
+
+/// 定位未被记录的原因
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationRejectReason {
+    /// 距上一次记录的定位时间过短
+    Throttled,
+    /// 定位误差超过允许范围
+    LowAccuracy,
+}
+
+/// 上报用户位置响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateLocationResponse {
+    /// 定位是否被记录
+    pub accepted: bool,
+    /// 未被记录的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<LocationRejectReason>,
+    /// 记录时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 位置隐私级别
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationPrecision {
+    /// 展示精确位置
+    Exact,
+    /// 在 `fuzz_radius` 米内随机偏移
+    Fuzzed,
+    /// 只展示城市级位置
+    City,
+    /// 隐身，不出现在附近用户和其他人的活动列表中
+    Hidden,
+}
+
+/// 位置隐私设置，用于查询和更新
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrivacySettings {
+    /// 位置隐私级别
+    pub location_precision: LocationPrecision,
+    /// 模糊半径（米），仅 `fuzzed` 时需要
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fuzz_radius: Option<u32>,
+    /// 是否保存位置历史（轨迹），默认不保存
+    #[serde(default)]
+    pub location_history: bool,
+}
+
+/// 开始向群组共享位置请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartLocationSharingRequest {
+    /// 共享时长（分钟），默认60分钟
+    #[serde(default = "default_sharing_minutes")]
+    pub duration_minutes: u32,
+}
+
+fn default_sharing_minutes() -> u32 {
+    60
+}
+
+/// 开始向群组共享位置响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartLocationSharingResponse {
+    /// 共享自动停止的时间
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 群组成员共享的位置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SharedLocation {
+    /// 成员公开ID
+    pub public_user_id: String,
+    /// 成员昵称
+    pub nickname: String,
+    /// 纬度，共享开始后尚未上报位置时为空
+    pub latitude: Option<f64>,
+    /// 经度，共享开始后尚未上报位置时为空
+    pub longitude: Option<f64>,
+    /// 定位误差（米）
+    pub accuracy: Option<f64>,
+    /// 位置上报时间
+    pub updated_at: Option<DateTime<Utc>>,
+    /// 共享自动停止的时间
+    pub sharing_expires_at: DateTime<Utc>,
+}
+
+/// 群组共享位置列表响应
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GroupSharedLocationsResponse {
+    /// 正在共享位置的成员
+    pub locations: Vec<SharedLocation>,
+}
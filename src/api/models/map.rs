@@ -0,0 +1,85 @@
+// 地图视窗查询相关的数据结构定义
+
+use serde::{Deserialize, Serialize};
+
+/// 经纬度矩形
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct BoundingBox {
+    /// 西边界经度
+    pub min_longitude: f64,
+    /// 南边界纬度
+    pub min_latitude: f64,
+    /// 东边界经度，小于西边界时表示跨越180度经线
+    pub max_longitude: f64,
+    /// 北边界纬度
+    pub max_latitude: f64,
+}
+
+/// 地图视窗查询请求，矩形与多边形二选一
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MapQueryRequest {
+    /// 经纬度矩形
+    pub bbox: Option<BoundingBox>,
+    /// GeoJSON 多边形（Polygon 或 MultiPolygon 几何对象）
+    pub polygon: Option<serde_json::Value>,
+    /// 地图缩放级别（0-22），决定最多返回的结果数量
+    pub zoom: u8,
+    /// 页码，从1开始
+    #[serde(default = "default_page")]
+    pub page: u32,
+    /// 每页数量
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    50
+}
+
+/// 地图图层
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MapLayer {
+    /// 群组
+    Groups,
+    /// 用户活动
+    Activities,
+}
+
+impl MapLayer {
+    /// 图层名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MapLayer::Groups => "groups",
+            MapLayer::Activities => "activities",
+        }
+    }
+}
+
+/// 地图聚合点
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MapCluster {
+    /// 中心点纬度
+    pub latitude: f64,
+    /// 中心点经度
+    pub longitude: f64,
+    /// 包含的点数量
+    pub count: i64,
+    /// 只包含一个点时，该点的ID（群组ID或活动ID）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// 只包含一个点时，该点的名称（群组名称或活动类型）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// 地图瓦片聚合响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MapClustersResponse {
+    /// 聚合点列表，缩放级别足够大时每个聚合点只包含一个点
+    pub clusters: Vec<MapCluster>,
+}
@@ -1,5 +1,6 @@
 // 消息相关的数据结构定义
 
+use crate::api::models::poll::{CreatePollRequest, PollDetail};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +14,8 @@ pub enum MessageType {
     Image,
     /// 语音消息
     Voice,
+    /// 投票消息
+    Poll,
     /// 系统消息
     System,
 }
@@ -24,8 +27,14 @@ pub struct SendGroupMessageRequest {
     pub group_id: String,
     /// 消息类型
     pub message_type: MessageType,
-    /// 消息内容
+    /// 消息内容，投票消息为投票的问题
     pub content: String,
+    /// 投票设置，仅投票消息需要
+    pub poll: Option<CreatePollRequest>,
+    /// 当前纬度（群组设置了地理围栏时需要）
+    pub latitude: Option<f64>,
+    /// 当前经度（群组设置了地理围栏时需要）
+    pub longitude: Option<f64>,
 }
 
 /// 消息发送响应
@@ -78,6 +87,8 @@ pub struct MessageDetailedInfo {
     pub message_type: MessageType,
     /// 消息内容
     pub content: String,
+    /// 投票及其当前结果，仅投票消息有值
+    pub poll: Option<PollDetail>,
     /// 发送时间
     pub sent_at: DateTime<Utc>,
 }
@@ -0,0 +1,53 @@
+// 通知相关的数据结构定义
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 获取通知列表请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListNotificationsRequest {
+    /// 上一页最后一条通知的ID，为空时从最新的通知开始
+    pub before_id: Option<i64>,
+    /// 通知数量限制，默认20
+    pub limit: Option<u32>,
+}
+
+/// 通知
+///
+/// `kind` 决定 `payload` 的内容：
+/// - `place_group_created`: 收藏地点范围内创建了新群组，包含 place_id、place_name、group_id、group_name
+/// - `place_checkin`: 收藏地点范围内有人签到，包含 place_id、place_name、activity_id
+/// - `event_reminder`: 回复参加或可能参加的群组聚会即将开始，包含 event_id、group_id、title、starts_at、location_name、rsvp
+/// - `event_cancelled`: 回复参加或可能参加的群组聚会被取消，包含 event_id、group_id、title、starts_at
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Notification {
+    /// 通知ID
+    pub notification_id: i64,
+    /// 通知类型
+    pub kind: String,
+    /// 通知内容
+    pub payload: Value,
+    /// 通知时间
+    pub created_at: DateTime<Utc>,
+    /// 是否已读
+    pub is_read: bool,
+}
+
+/// 获取通知列表响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListNotificationsResponse {
+    /// 通知列表，按时间从新到旧排序
+    pub notifications: Vec<Notification>,
+    /// 未读通知数量
+    pub unread_count: i64,
+    /// 下一页的 before_id，没有更多时为空
+    pub next_before_id: Option<i64>,
+}
+
+/// 全部标记为已读响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MarkAllNotificationsReadResponse {
+    /// 被标记为已读的通知数量
+    pub updated: u64,
+}
@@ -0,0 +1,34 @@
+// 活动照片相关的数据结构定义
+
+use serde::{Deserialize, Serialize};
+
+/// 活动照片
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActivityPhoto {
+    /// 照片ID
+    pub photo_id: String,
+    /// 原图地址
+    pub url: String,
+    /// 缩略图地址
+    pub thumbnail_url: String,
+    /// 宽度（像素）
+    pub width: i32,
+    /// 高度（像素）
+    pub height: i32,
+}
+
+/// 媒体文件下载地址中的签名参数
+#[derive(Debug, Deserialize)]
+pub struct MediaFileParams {
+    /// 地址的过期时间（Unix 时间戳，秒）
+    pub expires: Option<i64>,
+    /// 对象键和过期时间的签名
+    pub signature: Option<String>,
+}
+
+/// 删除照片响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteActivityPhotoResponse {
+    /// 被删除的照片ID
+    pub photo_id: String,
+}
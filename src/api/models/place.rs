@@ -0,0 +1,41 @@
+// 收藏地点相关的数据结构定义
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 收藏地点请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSavedPlaceRequest {
+    /// 地点名称
+    pub name: String,
+    /// 纬度
+    pub latitude: f64,
+    /// 经度
+    pub longitude: f64,
+    /// 关注范围半径（米）
+    pub radius: u32,
+}
+
+/// 收藏的地点
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedPlace {
+    /// 地点ID
+    pub place_id: String,
+    /// 地点名称
+    pub name: String,
+    /// 纬度
+    pub latitude: f64,
+    /// 经度
+    pub longitude: f64,
+    /// 关注范围半径（米）
+    pub radius: u32,
+    /// 收藏时间
+    pub created_at: DateTime<Utc>,
+}
+
+/// 收藏地点列表响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedPlacesResponse {
+    /// 地点列表
+    pub places: Vec<SavedPlace>,
+}
@@ -0,0 +1,64 @@
+// 群聊投票相关的数据结构定义
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 发起投票的设置，随投票消息一起发送，消息内容为投票的问题
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePollRequest {
+    /// 选项文字，按顺序编号为选项ID（从0开始）
+    pub options: Vec<String>,
+    /// 是否允许多选，默认单选
+    #[serde(default)]
+    pub multiple_choice: bool,
+    /// 是否匿名，匿名投票只公开各选项的票数
+    #[serde(default)]
+    pub anonymous: bool,
+    /// 截止时间（可选），为空时一直开放
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+/// 投票请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VotePollRequest {
+    /// 选择的选项ID，覆盖之前的选择；为空时撤回投票
+    pub option_ids: Vec<i32>,
+}
+
+/// 投票者
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PollVoterInfo {
+    /// 用户的公开ID，而非登录ID
+    pub user_id: String,
+    pub nickname: String,
+}
+
+/// 投票选项及其结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PollOptionResult {
+    pub option_id: i32,
+    pub text: String,
+    pub vote_count: i64,
+    /// 选择该选项的成员，匿名投票时为空
+    pub voters: Option<Vec<PollVoterInfo>>,
+}
+
+/// 投票及其当前结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PollDetail {
+    /// 投票消息ID
+    pub message_id: String,
+    pub group_id: String,
+    /// 投票的问题
+    pub question: String,
+    pub multiple_choice: bool,
+    pub anonymous: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+    /// 是否已截止
+    pub closed: bool,
+    pub options: Vec<PollOptionResult>,
+    /// 参与投票的人数
+    pub total_voters: i64,
+    /// 当前用户选择的选项ID
+    pub my_votes: Vec<i32>,
+}
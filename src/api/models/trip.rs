@@ -0,0 +1,61 @@
+// 行程轨迹相关的数据结构定义
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 获取行程列表请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListTripsRequest {
+    /// 行程数量限制，默认20
+    pub limit: Option<u32>,
+}
+
+/// 行程摘要
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TripInfo {
+    /// 行程ID
+    pub trip_id: i32,
+    /// 开始时间
+    pub started_at: DateTime<Utc>,
+    /// 结束时间
+    pub ended_at: DateTime<Utc>,
+    /// 持续时长（秒）
+    pub duration_secs: i64,
+    /// 行程距离（米）
+    pub distance: f64,
+    /// 定位点数量
+    pub point_count: i64,
+}
+
+/// 获取行程列表响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListTripsResponse {
+    /// 行程列表，按开始时间从新到旧排序
+    pub trips: Vec<TripInfo>,
+}
+
+/// 行程导出格式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TripExportFormat {
+    /// GeoJSON Feature（LineString）
+    #[default]
+    Geojson,
+    /// GPX 1.1
+    Gpx,
+}
+
+/// 导出行程请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportTripRequest {
+    /// 导出格式，默认 GeoJSON
+    #[serde(default)]
+    pub format: TripExportFormat,
+}
+
+/// 删除位置历史响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteLocationHistoryResponse {
+    /// 删除的定位数量
+    pub deleted: u64,
+}
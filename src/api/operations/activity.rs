@@ -3,7 +3,17 @@
 
 use crate::AppState;
 use crate::api::models::activity::*;
+use crate::api::operations::photo::photo_to_response;
+use crate::database::models::activity::{ActivityCursor, ActivityEntity, HeatmapFilter};
+use crate::database::models::privacy::{MAX_PRIVACY_MARGIN, distance_meters};
 use crate::database::operations::activity::ActivityOperation;
+use crate::database::operations::geocode::GeocodeOperation;
+use crate::database::operations::group::GroupOperation;
+use crate::database::operations::interaction::InteractionOperation;
+use crate::database::operations::location::LocationOperation;
+use crate::database::operations::photo::PhotoOperation;
+use crate::database::operations::privacy::PrivacyOperation;
+use crate::tasks::activity_heatmap::HEATMAP_PRECISIONS;
 use crate::utils::Claims;
 use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
 use axum::{
@@ -13,9 +23,32 @@ use axum::{
 };
 use std::sync::Arc;
 
+/// 未指定时活动列表的每页数量
+const DEFAULT_ACTIVITY_PAGE_SIZE: u32 = 20;
+
+/// 解析活动列表的分页参数，返回 (游标, 每页数量)
+fn parse_page_params(
+    state: &AppState,
+    cursor: Option<&str>,
+    limit: Option<u32>,
+) -> Result<(Option<ActivityCursor>, i64), String> {
+    let cursor = match cursor {
+        Some(cursor) => Some(ActivityCursor::decode(cursor).ok_or("无效的分页游标")?),
+        None => None,
+    };
+    let limit = limit
+        .unwrap_or(DEFAULT_ACTIVITY_PAGE_SIZE)
+        .clamp(1, state.config.activity_max_page_size.max(1));
+
+    Ok((cursor, limit as i64))
+}
+
 /// 获取附近活动
+///
+/// 按 (发生时间, 活动ID) 倒序分页。
 pub async fn get_nearby_activities(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Query(params): Query<GetNearbyActivitiesRequest>,
 ) -> impl IntoResponse {
     tracing::debug!(
@@ -49,57 +82,70 @@ pub async fn get_nearby_activities(
         );
     }
 
+    let (cursor, limit) = match parse_page_params(&state, params.cursor.as_deref(), params.limit) {
+        Ok(page) => page,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<GetNearbyActivitiesResponse>(
+                    error_codes::VALIDATION_ERROR,
+                    msg,
+                ),
+            );
+        }
+    };
+
     // 创建活动存储库实例
     let repo = Arc::new(ActivityOperation::new(Arc::new(state.pool.clone())));
 
     // 从数据库获取附近活动，只获取用户签到类型的活动
-    match repo
+    // 按隐私设置偏移后的位置可能落在真实位置之外，先扩大范围查询再按偏移后的位置过滤，
+    // 游标取自过滤前的数据库结果，保证翻页不遗漏
+    let result = match repo
         .find_nearby_activities_by_type(
-            params.latitude,
-            params.longitude,
-            radius,
-            params.limit as i64,
-            &["USER_CHECKIN"],
+            &claims.sub,
+            (params.latitude, params.longitude),
+            radius + MAX_PRIVACY_MARGIN,
+            &[ActivityType::UserCheckedIn],
+            cursor.as_ref(),
+            limit,
         )
         .await
     {
-        Ok(activities) => {
+        Ok(page) => apply_location_privacy(&state, &claims.sub, page.activities)
+            .await
+            .map(|activities| (activities, page.next_cursor)),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((activities, next_cursor)) => {
+            let origin = (params.latitude, params.longitude);
+            let activities: Vec<ActivityEntity> = activities
+                .into_iter()
+                .filter(|a| distance_meters(origin, (a.latitude, a.longitude)) <= radius)
+                .collect();
             tracing::debug!("从数据库获取到 {} 条附近活动", activities.len());
 
             // 将数据库实体转换为API响应格式
-            let activity_details: Vec<ActivityDetail> = activities
+            let mut activity_details: Vec<ActivityDetail> = activities
                 .into_iter()
-                .map(|activity| ActivityDetail {
-                    id: activity.id,
-                    activity_type: match activity.activity_type {
-                        2 => ActivityType::UserCheckedIn,
-                        10 => ActivityType::GroupCreated,
-                        11 => ActivityType::UserJoined,
-                        20 => ActivityType::MessageSent,
-                        _ => ActivityType::UserCheckedIn,
-                    },
-                    group_id: activity.group_id.unwrap_or_default(),
-                    group_name: String::new(),
-                    user_id: activity.user_id,
-                    user_name: String::new(),
-                    description: activity.description,
-                    occurred_at: activity.created_at,
-                    latitude: activity.latitude,
-                    longitude: activity.longitude,
-                    distance: None,
-                })
+                .map(|activity| activity_detail(activity, None))
                 .collect();
 
-            // 限制返回数量
-            let mut result = activity_details;
-            let limit = params.limit as usize;
-            if result.len() > limit {
-                result.truncate(limit);
-            }
+            fill_place_names(&state, &mut activity_details).await;
+
+            fill_interactions(&state, &claims.sub, &mut activity_details).await;
+
+            fill_photos(&state, &mut activity_details).await;
 
             (
                 StatusCode::OK,
-                success_to_api_response(GetNearbyActivitiesResponse { activities: result }),
+                success_to_api_response(GetNearbyActivitiesResponse {
+                    activities: activity_details,
+                    has_more: next_cursor.is_some(),
+                    next_cursor: next_cursor.map(|c| c.encode()),
+                }),
             )
         }
         Err(e) => {
@@ -115,6 +161,101 @@ pub async fn get_nearby_activities(
     }
 }
 
+/// 热力图单次最多返回的网格数量
+const MAX_HEATMAP_CELLS: i64 = 5000;
+
+/// 获取活动热力图
+///
+/// 从后台任务预聚合的网格中读取，不直接扫描活动表。网格按发布者位置隐私设置处理后的位置聚合，
+/// 不包含隐身用户的活动。
+pub async fn get_activity_heatmap(
+    State(state): State<AppState>,
+    Query(params): Query<GetActivityHeatmapRequest>,
+) -> impl IntoResponse {
+    let valid_bounds = [params.min_longitude, params.max_longitude]
+        .iter()
+        .all(|lon| (-180.0..=180.0).contains(lon))
+        && [params.min_latitude, params.max_latitude]
+            .iter()
+            .all(|lat| (-90.0..=90.0).contains(lat))
+        && params.min_latitude <= params.max_latitude;
+    if !valid_bounds {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<GetActivityHeatmapResponse>(
+                error_codes::VALIDATION_ERROR,
+                "非法的地理坐标".to_string(),
+            ),
+        );
+    }
+
+    if !HEATMAP_PRECISIONS.contains(&(params.precision as i32)) {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<GetActivityHeatmapResponse>(
+                error_codes::VALIDATION_ERROR,
+                format!("网格精度必须是 {:?} 之一", HEATMAP_PRECISIONS),
+            ),
+        );
+    }
+
+    // 时间窗口不超过热力图数据的保留时间
+    let max_hours = (state.config.activity_heatmap_retention().as_secs() / 3600).max(1) as u32;
+    if params.hours == 0 || params.hours > max_hours {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<GetActivityHeatmapResponse>(
+                error_codes::VALIDATION_ERROR,
+                format!("时间窗口必须在1-{}小时之间", max_hours),
+            ),
+        );
+    }
+
+    let repo = ActivityOperation::new(Arc::new(state.pool.clone()));
+    let filter = HeatmapFilter {
+        precision: params.precision as i16,
+        hours: params.hours as i32,
+        activity_type: params.activity_type.as_ref().map(ActivityType::as_db_str),
+        bounds: (
+            params.min_longitude,
+            params.min_latitude,
+            params.max_longitude,
+            params.max_latitude,
+        ),
+        limit: MAX_HEATMAP_CELLS,
+    };
+
+    match repo.find_heatmap_cells(&filter).await {
+        Ok(cells) => (
+            StatusCode::OK,
+            success_to_api_response(GetActivityHeatmapResponse {
+                cells: cells
+                    .into_iter()
+                    .map(|cell| HeatmapCellInfo {
+                        cell: cell.cell,
+                        latitude: cell.latitude,
+                        longitude: cell.longitude,
+                        weight: cell.weight,
+                    })
+                    .collect(),
+            }),
+        ),
+        Err(e) => {
+            tracing::error!("获取活动热力图失败: {}", e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<GetActivityHeatmapResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取活动热力图失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 活动类型名称的最大长度，与 user_activities.activity_type 字段一致
+const MAX_ACTIVITY_TYPE_LEN: usize = 50;
+
 /// 创建用户活动
 pub async fn create_user_activity(
     State(state): State<AppState>,
@@ -145,25 +286,72 @@ pub async fn create_user_activity(
         );
     }
 
+    // 未建模的活动类型按原始名称保存，长度受数据库字段限制
+    let activity_type_len = payload.activity_type.as_db_str().chars().count();
+    if activity_type_len == 0 || activity_type_len > MAX_ACTIVITY_TYPE_LEN {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<CreateUserActivityResponse>(
+                error_codes::VALIDATION_ERROR,
+                format!("活动类型长度必须在1-{}个字符之间", MAX_ACTIVITY_TYPE_LEN),
+            ),
+        );
+    }
+
+    // 仅群组可见的活动必须关联群组，且只能关联自己所在的群组
+    if let Some(group_id) = payload.group_id.as_deref() {
+        match GroupOperation::new(Arc::new(state.pool.clone()))
+            .has_user(group_id, user_id)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                return (
+                    StatusCode::OK,
+                    error_to_api_response::<CreateUserActivityResponse>(
+                        error_codes::PERMISSION_DENIED,
+                        "用户不是该群组成员".to_string(),
+                    ),
+                );
+            }
+            Err(e) => {
+                tracing::error!(
+                    "检查用户 {} 是否为群组 {} 成员失败: {}",
+                    user_id,
+                    group_id,
+                    e
+                );
+                return (
+                    StatusCode::OK,
+                    error_to_api_response::<CreateUserActivityResponse>(
+                        error_codes::INTERNAL_ERROR,
+                        format!("检查群组成员失败: {}", e),
+                    ),
+                );
+            }
+        }
+    } else if payload.visibility == ActivityVisibility::Group {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<CreateUserActivityResponse>(
+                error_codes::VALIDATION_ERROR,
+                "仅群组可见的活动需要指定群组".to_string(),
+            ),
+        );
+    }
+
     // 创建活动存储库实例
     let repo = Arc::new(ActivityOperation::new(Arc::new(state.pool.clone())));
 
-    // 将枚举转换为字符串
-    let activity_type_str = match payload.activity_type {
-        ActivityType::UserCheckedIn => "USER_CHECKIN",
-        ActivityType::GroupCreated => "GROUP_CREATE",
-        ActivityType::UserJoined => "USER_JOINED",
-        ActivityType::MessageSent => "MESSAGE_SENT",
-    };
-
     // 创建活动
     match repo
         .create_activity(
             user_id,
-            activity_type_str,
+            &payload.activity_type,
             payload.description.as_deref(),
-            payload.latitude,
-            payload.longitude,
+            (payload.latitude, payload.longitude),
+            payload.visibility,
+            payload.group_id.as_deref(),
         )
         .await
     {
@@ -209,15 +397,35 @@ pub async fn find_user_activities(
         target_user_id
     );
 
+    let (cursor, limit) = match parse_page_params(&state, params.cursor.as_deref(), params.limit) {
+        Ok(page) => page,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<FindUserActivitiesResponse>(
+                    error_codes::VALIDATION_ERROR,
+                    msg,
+                ),
+            );
+        }
+    };
+
     // 创建活动存储库实例
     let repo = Arc::new(ActivityOperation::new(Arc::new(state.pool.clone())));
 
     // 获取用户活动列表
-    match repo
-        .find_user_activities(&target_user_id, params.limit as i64)
+    let result = match repo
+        .find_user_activities(&claims.sub, &target_user_id, cursor.as_ref(), limit)
         .await
     {
-        Ok(activities) => {
+        Ok(page) => apply_location_privacy(&state, &claims.sub, page.activities)
+            .await
+            .map(|activities| (activities, page.next_cursor)),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((activities, next_cursor)) => {
             tracing::debug!(
                 "成功获取用户 {} 的 {} 条活动记录",
                 target_user_id,
@@ -225,45 +433,21 @@ pub async fn find_user_activities(
             );
 
             // 将数据库实体转换为API响应格式
-            let activity_details = activities
+            let mut activity_details = activities
                 .into_iter()
-                .map(|activity| ActivityDetail {
-                    id: activity.id,
-                    activity_type: match activity.activity_type {
-                        2 => ActivityType::UserCheckedIn,
-                        10 => ActivityType::GroupCreated,
-                        11 => ActivityType::UserJoined,
-                        20 => ActivityType::MessageSent,
-                        _ => ActivityType::UserCheckedIn,
-                    },
-                    group_id: activity.group_id.unwrap_or_default(),
-                    group_name: String::new(),
-                    user_id: activity.user_id,
-                    user_name: String::new(),
-                    description: activity.description,
-                    occurred_at: activity.created_at,
-                    latitude: activity.latitude,
-                    longitude: activity.longitude,
-                    distance: None,
-                })
+                .map(|activity| activity_detail(activity, None))
                 .collect::<Vec<_>>();
+            fill_place_names(&state, &mut activity_details).await;
+            fill_interactions(&state, &claims.sub, &mut activity_details).await;
 
-            // 计算下一页游标
-            let next_cursor = if !activity_details.is_empty() {
-                Some(activity_details.last().unwrap().id.clone())
-            } else {
-                None
-            };
-
-            // 判断是否还有更多记录
-            let has_more = activity_details.len() == params.limit as usize;
+            fill_photos(&state, &mut activity_details).await;
 
             (
                 StatusCode::OK,
                 success_to_api_response(FindUserActivitiesResponse {
                     activities: activity_details,
-                    next_cursor,
-                    has_more,
+                    has_more: next_cursor.is_some(),
+                    next_cursor: next_cursor.map(|c| c.encode()),
                 }),
             )
         }
@@ -283,45 +467,54 @@ pub async fn find_user_activities(
 /// 获取群组活动
 pub async fn get_group_activities(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Path(group_id): Path<String>,
     Query(params): Query<FindGroupActivitiesRequest>,
 ) -> impl IntoResponse {
+    let (cursor, limit) = match parse_page_params(&state, params.cursor.as_deref(), params.limit) {
+        Ok(page) => page,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<FindGroupActivitiesResponse>(
+                    error_codes::VALIDATION_ERROR,
+                    msg,
+                ),
+            );
+        }
+    };
+
     // 创建活动仓库实例
     let activity_repo = ActivityOperation::new(Arc::new(state.pool.clone()));
 
-    match activity_repo
-        .find_group_activities(&group_id, params.limit as i64)
+    let result = match activity_repo
+        .find_group_activities(&claims.sub, &group_id, cursor.as_ref(), limit)
         .await
     {
-        Ok(activities) => {
+        Ok(page) => apply_location_privacy(&state, &claims.sub, page.activities)
+            .await
+            .map(|activities| (activities, page.next_cursor)),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((activities, next_cursor)) => {
             // 转换为API响应格式
-            let activity_details = activities
+            let mut activity_details: Vec<ActivityDetail> = activities
                 .into_iter()
-                .map(|activity| ActivityDetail {
-                    id: activity.id,
-                    activity_type: match activity.activity_type {
-                        2 => ActivityType::UserCheckedIn,
-                        10 => ActivityType::GroupCreated,
-                        11 => ActivityType::UserJoined,
-                        20 => ActivityType::MessageSent,
-                        _ => ActivityType::UserCheckedIn,
-                    },
-                    group_id: activity.group_id.unwrap_or_default(),
-                    group_name: String::new(),
-                    user_id: activity.user_id,
-                    user_name: String::new(),
-                    description: activity.description,
-                    occurred_at: activity.created_at,
-                    latitude: activity.latitude,
-                    longitude: activity.longitude,
-                    distance: None,
-                })
+                .map(|activity| activity_detail(activity, None))
                 .collect();
+            fill_place_names(&state, &mut activity_details).await;
+            fill_interactions(&state, &claims.sub, &mut activity_details).await;
+
+            fill_photos(&state, &mut activity_details).await;
 
             (
                 StatusCode::OK,
                 success_to_api_response(FindGroupActivitiesResponse {
                     activities: activity_details,
+                    has_more: next_cursor.is_some(),
+                    next_cursor: next_cursor.map(|c| c.encode()),
                 }),
             )
         }
@@ -341,55 +534,57 @@ pub async fn get_group_activities(
 /// 获取所有活动（最新活动）
 pub async fn get_all_activities(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Query(params): Query<GetAllActivitiesRequest>,
 ) -> impl IntoResponse {
-    let limit = params.limit.unwrap_or(20);
+    let (cursor, limit) = match parse_page_params(&state, params.cursor.as_deref(), params.limit) {
+        Ok(page) => page,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<GetAllActivitiesResponse>(
+                    error_codes::VALIDATION_ERROR,
+                    msg,
+                ),
+            );
+        }
+    };
     tracing::debug!("请求获取最新的 {} 条活动", limit);
 
     // 创建活动存储库实例
     let repo = Arc::new(ActivityOperation::new(Arc::new(state.pool.clone())));
 
     // 获取最新活动
-    match repo
-        .find_nearby_activities(
-            0.0, // 使用0,0作为默认坐标
-            0.0,
-            100000.0, // 足够大的搜索半径
-            limit as i64,
-        )
+    let result = match repo
+        .find_recent_activities(&claims.sub, cursor.as_ref(), limit)
         .await
     {
-        Ok(activities) => {
+        Ok(page) => apply_location_privacy(&state, &claims.sub, page.activities)
+            .await
+            .map(|activities| (activities, page.next_cursor)),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((activities, next_cursor)) => {
             tracing::debug!("成功获取 {} 条最新活动", activities.len());
 
             // 将数据库实体转换为API响应格式
-            let activity_details: Vec<ActivityDetail> = activities
+            let mut activity_details: Vec<ActivityDetail> = activities
                 .into_iter()
-                .map(|activity| ActivityDetail {
-                    id: activity.id,
-                    activity_type: match activity.activity_type {
-                        2 => ActivityType::UserCheckedIn,
-                        10 => ActivityType::GroupCreated,
-                        11 => ActivityType::UserJoined,
-                        20 => ActivityType::MessageSent,
-                        _ => ActivityType::UserCheckedIn,
-                    },
-                    group_id: activity.group_id.unwrap_or_default(),
-                    group_name: String::new(),
-                    user_id: activity.user_id,
-                    user_name: String::new(),
-                    description: activity.description,
-                    occurred_at: activity.created_at,
-                    latitude: activity.latitude,
-                    longitude: activity.longitude,
-                    distance: None,
-                })
+                .map(|activity| activity_detail(activity, None))
                 .collect();
+            fill_place_names(&state, &mut activity_details).await;
+            fill_interactions(&state, &claims.sub, &mut activity_details).await;
+
+            fill_photos(&state, &mut activity_details).await;
 
             (
                 StatusCode::OK,
                 success_to_api_response(GetAllActivitiesResponse {
                     activities: activity_details,
+                    has_more: next_cursor.is_some(),
+                    next_cursor: next_cursor.map(|c| c.encode()),
                 }),
             )
         }
@@ -406,9 +601,13 @@ pub async fn get_all_activities(
     }
 }
 
+/// 附近用户查询单次最多返回的用户数量
+const MAX_NEARBY_USERS: u32 = 500;
+
 /// 查找附近用户
 pub async fn find_nearby_users(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Query(params): Query<FindNearbyUsersRequest>,
 ) -> impl IntoResponse {
     tracing::debug!(
@@ -442,16 +641,18 @@ pub async fn find_nearby_users(
     let radius = params.radius as f64;
     let radius = radius.min(state.config.max_search_radius);
 
-    // 创建活动存储库实例
-    let db_operation = ActivityOperation::new(Arc::new(state.pool.clone()));
+    // 创建位置存储库实例
+    let db_operation = LocationOperation::new(Arc::new(state.pool.clone()));
 
-    // 查询附近用户
+    // 查询附近用户，距离按隐私设置偏移后的位置计算，超出半径的用户被过滤
     match db_operation
         .find_nearby_users(
+            (&claims.sub, &state.config.location_fuzz_secret),
             params.latitude,
             params.longitude,
             radius,
-            params.limit as i64,
+            params.limit.min(MAX_NEARBY_USERS) as i64,
+            state.config.location_stale_after().as_secs(),
         )
         .await
     {
@@ -462,20 +663,15 @@ pub async fn find_nearby_users(
                 .map(|user| NearbyUser {
                     user_id: user.user_id,
                     nickname: user.nickname,
-                    last_activity: UserActivity {
-                        id: user.last_activity_id.unwrap_or_default(),
-                        activity_type: match user.last_activity_type.as_deref().unwrap_or("") {
-                            "USER_CHECKIN" => ActivityType::UserCheckedIn,
-                            "GROUP_CREATE" => ActivityType::GroupCreated,
-                            "USER_JOINED" => ActivityType::UserJoined,
-                            "MESSAGE_SENT" => ActivityType::MessageSent,
-                            _ => ActivityType::UserCheckedIn,
+                    last_activity: user.last_activity_id.zip(user.last_activity_type).map(
+                        |(id, activity_type)| UserActivity {
+                            id,
+                            activity_type,
+                            description: user.last_activity_description.unwrap_or_default(),
+                            occurred_at: user.last_activity_time.unwrap_or_else(chrono::Utc::now),
                         },
-                        description: user.last_activity_description.unwrap_or_default(),
-                        occurred_at: user
-                            .last_activity_time
-                            .unwrap_or_else(|| chrono::Utc::now()),
-                    },
+                    ),
+                    location_updated_at: user.location_updated_at,
                     distance: user.distance,
                 })
                 .collect();
@@ -497,3 +693,144 @@ pub async fn find_nearby_users(
         }
     }
 }
+
+/// 将活动实体转换为API响应格式，`distance` 为与查询位置的距离（米）
+///
+/// 群组名称、用户名称、地名、互动统计和照片由调用方按需填充。
+pub(crate) fn activity_detail(activity: ActivityEntity, distance: Option<f64>) -> ActivityDetail {
+    ActivityDetail {
+        id: activity.id,
+        activity_type: activity.activity_type,
+        group_id: activity.group_id.unwrap_or_default(),
+        group_name: String::new(),
+        event_id: activity.event_id,
+        user_id: activity.user_id,
+        user_name: String::new(),
+        description: activity.description,
+        occurred_at: activity.created_at,
+        latitude: activity.latitude,
+        longitude: activity.longitude,
+        place_name: None,
+        distance,
+        like_count: 0,
+        comment_count: 0,
+        liked: false,
+        visibility: activity.visibility,
+        photos: Vec::new(),
+    }
+}
+
+/// 按活动发布者的位置隐私设置处理活动位置
+///
+/// 隐身用户的活动被移除，模糊或城市级用户的活动位置被替换为偏移后的位置，
+/// 查看者自己的活动保持精确位置。
+pub(crate) async fn apply_location_privacy(
+    state: &AppState,
+    viewer_id: &str,
+    activities: Vec<ActivityEntity>,
+) -> Result<Vec<ActivityEntity>, sqlx::Error> {
+    let mut user_ids: Vec<String> = activities
+        .iter()
+        .filter(|a| a.user_id != viewer_id)
+        .map(|a| a.user_id.clone())
+        .collect();
+    user_ids.sort();
+    user_ids.dedup();
+
+    let settings = PrivacyOperation::new(Arc::new(state.pool.clone()))
+        .find_location_privacy(&user_ids)
+        .await?;
+
+    Ok(activities
+        .into_iter()
+        .filter_map(|mut activity| {
+            if activity.user_id == viewer_id {
+                return Some(activity);
+            }
+            let privacy = settings.get(&activity.user_id).copied().unwrap_or_default();
+            let (latitude, longitude) = privacy.apply(
+                &state.config.location_fuzz_secret,
+                &activity.user_id,
+                (activity.latitude, activity.longitude),
+                activity.created_at,
+            )?;
+            activity.latitude = latitude;
+            activity.longitude = longitude;
+            Some(activity)
+        })
+        .collect())
+}
+
+/// 按活动位置批量解析行政区名称并填入 `place_name`
+///
+/// 必须在 [`apply_location_privacy`] 之后调用，地名只反映偏移后的位置。
+/// 地名库查询失败不影响活动列表本身，仅记录日志。
+pub(crate) async fn fill_place_names(state: &AppState, activities: &mut [ActivityDetail]) {
+    let points: Vec<(f64, f64)> = activities
+        .iter()
+        .map(|a| (a.latitude, a.longitude))
+        .collect();
+
+    match GeocodeOperation::new(Arc::new(state.pool.clone()))
+        .reverse_many(&points)
+        .await
+    {
+        Ok(places) => {
+            for (activity, place) in activities.iter_mut().zip(places) {
+                activity.place_name = place.display_name();
+            }
+        }
+        Err(e) => tracing::warn!("解析活动地名失败: {}", e),
+    }
+}
+
+/// 批量填入活动的点赞数、评论数以及查看者是否已点赞
+///
+/// 统计失败不影响活动列表本身，仅记录日志。
+pub(crate) async fn fill_interactions(
+    state: &AppState,
+    viewer_id: &str,
+    activities: &mut [ActivityDetail],
+) {
+    let activity_ids: Vec<String> = activities.iter().map(|a| a.id.clone()).collect();
+
+    match InteractionOperation::new(Arc::new(state.pool.clone()))
+        .count_interactions(&activity_ids, viewer_id)
+        .await
+    {
+        Ok(mut counts) => {
+            for activity in activities.iter_mut() {
+                let counts = counts.remove(&activity.id).unwrap_or_default();
+                activity.like_count = counts.like_count;
+                activity.comment_count = counts.comment_count;
+                activity.liked = counts.liked;
+            }
+        }
+        Err(e) => tracing::warn!("统计活动点赞和评论失败: {}", e),
+    }
+}
+
+/// 批量填入活动附带的照片地址
+///
+/// 查询失败不影响活动列表本身，仅记录日志；无法生成地址的照片被跳过。
+pub(crate) async fn fill_photos(state: &AppState, activities: &mut [ActivityDetail]) {
+    let activity_ids: Vec<String> = activities.iter().map(|a| a.id.clone()).collect();
+
+    match PhotoOperation::new(Arc::new(state.pool.clone()))
+        .find_by_activities(&activity_ids)
+        .await
+    {
+        Ok(mut photos) => {
+            for activity in activities.iter_mut() {
+                for photo in photos.remove(&activity.id).unwrap_or_default() {
+                    let photo_id = photo.photo_id.clone();
+                    match photo_to_response(state, photo).await {
+                        Ok(photo) => activity.photos.push(photo),
+                        Err(e) => tracing::warn!("生成照片 {} 的访问地址失败: {}", photo_id, e),
+                    }
+                }
+            }
+        }
+        Err(e) => tracing::warn!("获取活动照片失败: {}", e),
+    }
+}
@@ -0,0 +1,480 @@
+// 管理处理器
+// 处理需要管理员权限的批量导入导出请求
+
+use crate::AppState;
+use crate::api::models::admin::*;
+use crate::api::operations::group::{MAX_GROUP_TAG_LEN, MAX_GROUP_TAGS, normalize_tags};
+use crate::database::models::geocode::AdminLevel;
+use crate::database::models::group::NewGroup;
+use crate::database::operations::activity::ActivityOperation;
+use crate::database::operations::geocode::GeocodeOperation;
+use crate::database::operations::group::GroupOperation;
+use crate::database::operations::user::UserOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    body::Body,
+    extract::{Extension, Json, Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use futures_util::{Stream, StreamExt, stream};
+use serde_json::{Value, json};
+use std::io;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// 单次导入最多的要素数量
+const MAX_IMPORT_FEATURES: usize = 1000;
+
+/// 名称与位置名称的最大长度（字符）
+const MAX_NAME_LEN: usize = 255;
+
+/// GeoJSON 的 Content-Type
+const GEOJSON_CONTENT_TYPE: &str = "application/geo+json";
+
+/// 以 GeoJSON FeatureCollection 流式导出所有未归档的群组
+pub async fn export_groups(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Response {
+    tracing::info!("管理员 {} 正在导出群组", claims.sub);
+
+    let pool = state.pool.clone();
+    let body = spawn_feature_collection(move |tx| async move {
+        let repo = GroupOperation::new(Arc::new(pool));
+        let features = repo.stream_active_groups().map(|row| {
+            row.map(|group| {
+                json!({
+                    "type": "Feature",
+                    "geometry": {
+                        "type": "Point",
+                        "coordinates": [group.longitude, group.latitude],
+                    },
+                    "properties": {
+                        "group_id": group.id,
+                        "name": group.name,
+                        "location_name": group.location_name,
+                        "description": group.description,
+                        "category": group.category,
+                        "tags": group.tags,
+                        "max_members": group.max_members,
+                        "expires_at": group.expires_at,
+                        "created_at": group.created_at,
+                    },
+                })
+            })
+        });
+        send_feature_collection(tx, features).await;
+    });
+
+    geojson_response(body, "groups.geojson")
+}
+
+/// 以 GeoJSON FeatureCollection 流式导出指定用户（公开ID）的全部活动
+pub async fn export_user_activities(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(public_user_id): Path<String>,
+) -> Response {
+    tracing::info!(
+        "管理员 {} 正在导出用户 {} 的活动",
+        claims.sub,
+        public_user_id
+    );
+
+    let user_id = match UserOperation::find_by_public_id(&state.pool, &public_user_id).await {
+        Ok(Some(user)) => user.user_id,
+        Ok(None) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<()>(error_codes::NOT_FOUND, "用户不存在".to_string()),
+            )
+                .into_response();
+        }
+        Err(err) => {
+            tracing::error!("查询用户 {} 失败: {}", public_user_id, err);
+            return (
+                StatusCode::OK,
+                error_to_api_response::<()>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("查询用户失败: {}", err),
+                ),
+            )
+                .into_response();
+        }
+    };
+
+    let pool = state.pool.clone();
+    let body = spawn_feature_collection(move |tx| async move {
+        let repo = ActivityOperation::new(Arc::new(pool));
+        let features = repo.stream_user_activities(&user_id).map(|row| {
+            row.map(|activity| {
+                json!({
+                    "type": "Feature",
+                    "geometry": {
+                        "type": "Point",
+                        "coordinates": [activity.longitude, activity.latitude],
+                    },
+                    "properties": {
+                        "activity_id": activity.id,
+                        "user_id": public_user_id,
+                        "activity_type": activity.activity_type,
+                        "description": activity.description,
+                        "created_at": activity.created_at,
+                    },
+                })
+            })
+        });
+        send_feature_collection(tx, features).await;
+    });
+
+    geojson_response(body, "activities.geojson")
+}
+
+/// 从 GeoJSON FeatureCollection 批量创建群组
+///
+/// 每个要素必须是 Point 几何对象，属性见 [`ImportGroupProperties`]。
+/// 校验或创建失败的要素不影响其他要素，错误按要素序号返回。
+pub async fn import_groups(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<Value>,
+) -> impl IntoResponse {
+    let features = match payload.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => payload.get("features").and_then(Value::as_array),
+        _ => None,
+    };
+    let Some(features) = features else {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<ImportGroupsResponse>(
+                error_codes::VALIDATION_ERROR,
+                "请求体必须是GeoJSON FeatureCollection".to_string(),
+            ),
+        );
+    };
+    if features.len() > MAX_IMPORT_FEATURES {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<ImportGroupsResponse>(
+                error_codes::VALIDATION_ERROR,
+                format!("单次最多导入{}个要素", MAX_IMPORT_FEATURES),
+            ),
+        );
+    }
+
+    tracing::info!("管理员 {} 正在导入 {} 个群组", claims.sub, features.len());
+
+    let repo = GroupOperation::new(Arc::new(state.pool.clone()));
+    let mut created = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, feature) in features.iter().enumerate() {
+        let (longitude, latitude, properties) = match parse_group_feature(feature) {
+            Ok(parsed) => parsed,
+            Err(message) => {
+                errors.push(ImportFeatureError { index, message });
+                continue;
+            }
+        };
+
+        let tags = normalize_tags(properties.tags);
+        let description = properties.description.unwrap_or_default();
+        let new_group = NewGroup {
+            name: properties.name.trim(),
+            location_name: properties.location_name.trim(),
+            latitude,
+            longitude,
+            description: &description,
+            password: None,
+            creator_id: &claims.sub,
+            expires_at: properties.expires_at,
+            max_members: properties.max_members,
+            category: properties.category.map(|c| c.as_str()),
+            tags: &tags,
+            geofence_radius: None,
+            geofence_polygon: None,
+            requires_approval: false,
+        };
+
+        match repo.create(&new_group).await {
+            Ok(group_id) => created.push(ImportedGroup { index, group_id }),
+            Err(err) => {
+                tracing::warn!("导入第 {} 个群组失败: {}", index, err);
+                errors.push(ImportFeatureError {
+                    index,
+                    message: format!("创建群组失败: {}", err),
+                });
+            }
+        }
+    }
+
+    tracing::info!(
+        "管理员 {} 导入群组完成: 成功 {} 个，失败 {} 个",
+        claims.sub,
+        created.len(),
+        errors.len()
+    );
+
+    (
+        StatusCode::OK,
+        success_to_api_response(ImportGroupsResponse { created, errors }),
+    )
+}
+
+/// 校验单个要素，返回 (经度, 纬度, 属性)
+fn parse_group_feature(feature: &Value) -> Result<(f64, f64, ImportGroupProperties), String> {
+    if feature.get("type").and_then(Value::as_str) != Some("Feature") {
+        return Err("要素类型必须是Feature".to_string());
+    }
+
+    let geometry = feature.get("geometry").ok_or("缺少geometry")?;
+    if geometry.get("type").and_then(Value::as_str) != Some("Point") {
+        return Err("几何类型必须是Point".to_string());
+    }
+    let (longitude, latitude) = match geometry
+        .get("coordinates")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        Some([lon, lat, ..]) => match (lon.as_f64(), lat.as_f64()) {
+            (Some(lon), Some(lat)) => (lon, lat),
+            _ => return Err("坐标必须是数字".to_string()),
+        },
+        _ => return Err("缺少坐标".to_string()),
+    };
+    if !(-180.0..=180.0).contains(&longitude) || !(-90.0..=90.0).contains(&latitude) {
+        return Err("非法的地理坐标".to_string());
+    }
+
+    let properties: ImportGroupProperties =
+        serde_json::from_value(feature.get("properties").cloned().unwrap_or(Value::Null))
+            .map_err(|e| format!("属性无效: {}", e))?;
+
+    for (field, value) in [
+        ("name", &properties.name),
+        ("location_name", &properties.location_name),
+    ] {
+        let len = value.trim().chars().count();
+        if len == 0 || len > MAX_NAME_LEN {
+            return Err(format!("{}长度必须在1-{}个字符之间", field, MAX_NAME_LEN));
+        }
+    }
+    if properties.max_members.is_some_and(|max| max < 1) {
+        return Err("人数上限必须大于0".to_string());
+    }
+    if properties.expires_at.is_some_and(|t| t <= Utc::now()) {
+        return Err("过期时间必须晚于当前时间".to_string());
+    }
+    let tags = normalize_tags(properties.tags.clone());
+    if tags.len() > MAX_GROUP_TAGS || tags.iter().any(|t| t.chars().count() > MAX_GROUP_TAG_LEN) {
+        return Err(format!(
+            "最多{}个标签，每个标签不超过{}个字符",
+            MAX_GROUP_TAGS, MAX_GROUP_TAG_LEN
+        ));
+    }
+
+    Ok((longitude, latitude, properties))
+}
+
+/// 从 GeoJSON FeatureCollection 导入离线地名库的行政区划
+///
+/// 要素可以是行政边界（Polygon、MultiPolygon）或 GeoNames 一类的点位（Point），
+/// 属性见 [`ImportAdminAreaProperties`]。全量数据建议直接用 ogr2ogr 导入 admin_areas 表。
+pub async fn import_admin_areas(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<Value>,
+) -> impl IntoResponse {
+    let features = match payload.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => payload.get("features").and_then(Value::as_array),
+        _ => None,
+    };
+    let Some(features) = features else {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<ImportAdminAreasResponse>(
+                error_codes::VALIDATION_ERROR,
+                "请求体必须是GeoJSON FeatureCollection".to_string(),
+            ),
+        );
+    };
+    if features.len() > MAX_IMPORT_FEATURES {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<ImportAdminAreasResponse>(
+                error_codes::VALIDATION_ERROR,
+                format!("单次最多导入{}个要素", MAX_IMPORT_FEATURES),
+            ),
+        );
+    }
+
+    tracing::info!(
+        "管理员 {} 正在导入 {} 个行政区划",
+        claims.sub,
+        features.len()
+    );
+
+    let repo = GeocodeOperation::new(Arc::new(state.pool.clone()));
+    let mut created = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, feature) in features.iter().enumerate() {
+        let (geometry, properties) = match parse_admin_area_feature(feature) {
+            Ok(parsed) => parsed,
+            Err(message) => {
+                errors.push(ImportFeatureError { index, message });
+                continue;
+            }
+        };
+
+        let level = match properties.level {
+            AdminAreaLevel::Country => AdminLevel::Country,
+            AdminAreaLevel::Province => AdminLevel::Province,
+            AdminAreaLevel::City => AdminLevel::City,
+            AdminAreaLevel::District => AdminLevel::District,
+        };
+        let country_code = properties
+            .country_code
+            .as_deref()
+            .map(|code| code.trim().to_ascii_uppercase());
+
+        match repo
+            .import_area(
+                level,
+                properties.name.trim(),
+                country_code.as_deref(),
+                &geometry.to_string(),
+            )
+            .await
+        {
+            Ok(area_id) => created.push(ImportedAdminArea { index, area_id }),
+            Err(err) => {
+                tracing::warn!("导入第 {} 个行政区划失败: {}", index, err);
+                errors.push(ImportFeatureError {
+                    index,
+                    message: format!("导入行政区划失败: {}", err),
+                });
+            }
+        }
+    }
+
+    tracing::info!(
+        "管理员 {} 导入行政区划完成: 成功 {} 个，失败 {} 个",
+        claims.sub,
+        created.len(),
+        errors.len()
+    );
+
+    (
+        StatusCode::OK,
+        success_to_api_response(ImportAdminAreasResponse { created, errors }),
+    )
+}
+
+/// 校验单个行政区划要素，返回 (几何对象, 属性)
+fn parse_admin_area_feature(
+    feature: &Value,
+) -> Result<(&Value, ImportAdminAreaProperties), String> {
+    if feature.get("type").and_then(Value::as_str) != Some("Feature") {
+        return Err("要素类型必须是Feature".to_string());
+    }
+
+    let geometry = feature.get("geometry").ok_or("缺少geometry")?;
+    match geometry.get("type").and_then(Value::as_str) {
+        Some("Point" | "Polygon" | "MultiPolygon") => {}
+        _ => return Err("几何类型必须是Point、Polygon或MultiPolygon".to_string()),
+    }
+    if !geometry.get("coordinates").is_some_and(Value::is_array) {
+        return Err("缺少坐标".to_string());
+    }
+
+    let properties: ImportAdminAreaProperties =
+        serde_json::from_value(feature.get("properties").cloned().unwrap_or(Value::Null))
+            .map_err(|e| format!("属性无效: {}", e))?;
+
+    let len = properties.name.trim().chars().count();
+    if len == 0 || len > MAX_NAME_LEN {
+        return Err(format!("name长度必须在1-{}个字符之间", MAX_NAME_LEN));
+    }
+    if properties
+        .country_code
+        .as_deref()
+        .is_some_and(|code| code.trim().len() != 2 || !code.trim().is_ascii())
+    {
+        return Err("country_code必须是两位国家代码".to_string());
+    }
+
+    Ok((geometry, properties))
+}
+
+type Chunk = Result<String, io::Error>;
+
+/// 在后台任务中生成响应内容，返回按块读取的响应体
+fn spawn_feature_collection<F, Fut>(producer: F) -> Body
+where
+    F: FnOnce(mpsc::Sender<Chunk>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<Chunk>(32);
+    tokio::spawn(producer(tx));
+
+    Body::from_stream(stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    }))
+}
+
+/// 逐个写出要素，组成一个 FeatureCollection
+///
+/// 查询中途出错时发送错误以中断响应，客户端断开时停止查询。
+async fn send_feature_collection<S>(tx: mpsc::Sender<Chunk>, features: S)
+where
+    S: Stream<Item = Result<Value, sqlx::Error>>,
+{
+    let mut features = std::pin::pin!(features);
+
+    if tx
+        .send(Ok(r#"{"type":"FeatureCollection","features":["#.to_string()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let mut first = true;
+    while let Some(feature) = features.next().await {
+        let chunk = match feature {
+            Ok(feature) if first => Ok(feature.to_string()),
+            Ok(feature) => Ok(format!(",{}", feature)),
+            Err(err) => {
+                tracing::error!("导出GeoJSON失败: {}", err);
+                let _ = tx.send(Err(io::Error::other(err))).await;
+                return;
+            }
+        };
+        first = false;
+        if tx.send(chunk).await.is_err() {
+            return;
+        }
+    }
+
+    let _ = tx.send(Ok("]}".to_string())).await;
+}
+
+/// 构建以附件形式下载的 GeoJSON 响应
+fn geojson_response(body: Body, filename: &str) -> Response {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, GEOJSON_CONTENT_TYPE)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(body)
+        .unwrap_or_else(|e| {
+            tracing::error!("构建GeoJSON响应失败: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
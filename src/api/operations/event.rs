@@ -0,0 +1,540 @@
+// 群组聚会处理器
+// 处理聚会的创建、查询、取消以及成员回复
+
+use crate::AppState;
+use crate::api::models::event::*;
+use crate::database::models::event::{EventEntity, NewEvent};
+use crate::database::operations::event::EventOperation;
+use crate::database::operations::group::GroupOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response, validate_content};
+use axum::{
+    extract::{Extension, Json, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+
+/// 聚会标题的最大长度（字符）
+const MAX_EVENT_TITLE_LEN: usize = 100;
+
+/// 地点名称的最大长度（字符）
+const MAX_EVENT_LOCATION_NAME_LEN: usize = 255;
+
+/// 单个聚会的最长持续时间（天）
+const MAX_EVENT_DURATION_DAYS: i64 = 7;
+
+/// 未指定时聚会列表的返回数量
+const DEFAULT_EVENT_PAGE_SIZE: u32 = 20;
+
+/// 聚会列表单次最多返回的数量
+const MAX_EVENT_PAGE_SIZE: u32 = 100;
+
+/// 将聚会实体转换为API响应格式，状态按 `now` 计算
+fn event_to_response(event: EventEntity, now: DateTime<Utc>) -> EventDetail {
+    let status = if event.cancelled_at.is_some() {
+        EventStatus::Cancelled
+    } else if now >= event.ends_at {
+        EventStatus::Ended
+    } else if now >= event.starts_at {
+        EventStatus::Ongoing
+    } else {
+        EventStatus::Upcoming
+    };
+
+    EventDetail {
+        event_id: event.event_id,
+        group_id: event.group_id,
+        group_name: event.group_name,
+        title: event.title,
+        description: event.description,
+        starts_at: event.starts_at,
+        ends_at: event.ends_at,
+        location_name: event.location_name,
+        latitude: event.latitude,
+        longitude: event.longitude,
+        status,
+        going_count: event.going_count,
+        maybe_count: event.maybe_count,
+        my_rsvp: event.my_rsvp,
+        distance: event.distance,
+        created_at: event.created_at,
+    }
+}
+
+/// 校验创建聚会的请求
+fn validate_create_request<'a>(
+    payload: &'a CreateEventRequest,
+    group_id: &'a str,
+    creator_id: &'a str,
+) -> Result<NewEvent<'a>, String> {
+    let title = payload.title.trim();
+    let title_len = title.chars().count();
+    if title_len == 0 || title_len > MAX_EVENT_TITLE_LEN {
+        return Err(format!(
+            "聚会标题长度必须在1-{}个字符之间",
+            MAX_EVENT_TITLE_LEN
+        ));
+    }
+    if title.chars().any(char::is_control) {
+        return Err("聚会标题不能包含控制字符".to_string());
+    }
+
+    let description = match payload.description.as_deref().map(str::trim) {
+        Some("") | None => None,
+        Some(description) => Some(validate_content(description)?),
+    };
+
+    let location = match (payload.latitude, payload.longitude) {
+        (Some(latitude), Some(longitude))
+            if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) =>
+        {
+            Some((latitude, longitude))
+        }
+        (None, None) => None,
+        _ => return Err("非法的地理坐标".to_string()),
+    };
+
+    let location_name = match payload.location_name.as_deref().map(str::trim) {
+        Some("") | None => None,
+        Some(name) if name.chars().count() > MAX_EVENT_LOCATION_NAME_LEN => {
+            return Err(format!(
+                "地点名称不能超过{}个字符",
+                MAX_EVENT_LOCATION_NAME_LEN
+            ));
+        }
+        Some(name) => Some(name),
+    };
+
+    if payload.ends_at <= payload.starts_at {
+        return Err("结束时间必须晚于开始时间".to_string());
+    }
+    if payload.ends_at <= Utc::now() {
+        return Err("结束时间必须晚于当前时间".to_string());
+    }
+    if payload.ends_at - payload.starts_at > Duration::days(MAX_EVENT_DURATION_DAYS) {
+        return Err(format!("聚会持续时间不能超过{}天", MAX_EVENT_DURATION_DAYS));
+    }
+
+    Ok(NewEvent {
+        group_id,
+        creator_id,
+        title,
+        description,
+        starts_at: payload.starts_at,
+        ends_at: payload.ends_at,
+        location,
+        location_name,
+    })
+}
+
+/// 创建群组聚会，只有群组管理员可以创建
+///
+/// 未指定地点时使用群组的位置，创建后在群组的活动流中记录一条创建聚会的活动。
+pub async fn create_event(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(group_id): Path<String>,
+    Json(payload): Json<CreateEventRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+
+    let new_event = match validate_create_request(&payload, &group_id, user_id) {
+        Ok(new_event) => new_event,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<EventDetail>(error_codes::VALIDATION_ERROR, msg),
+            );
+        }
+    };
+
+    match GroupOperation::new(Arc::new(state.pool.clone()))
+        .is_admin(&group_id, user_id)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<EventDetail>(
+                    error_codes::PERMISSION_DENIED,
+                    "只有群组管理员可以创建聚会".to_string(),
+                ),
+            );
+        }
+        Err(e) => {
+            tracing::error!(
+                "检查用户 {} 是否为群组 {} 管理员失败: {}",
+                user_id,
+                group_id,
+                e
+            );
+            return (
+                StatusCode::OK,
+                error_to_api_response::<EventDetail>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("检查管理员权限失败: {}", e),
+                ),
+            );
+        }
+    }
+
+    let repo = EventOperation::new(Arc::new(state.pool.clone()));
+
+    let result = match repo.create_event(&new_event).await {
+        Ok(Some(event_id)) => repo.find_by_id(&event_id, user_id).await,
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(Some(event)) => {
+            tracing::info!(
+                "用户 {} 在群组 {} 创建了聚会 {}",
+                user_id,
+                group_id,
+                event.event_id
+            );
+            (
+                StatusCode::OK,
+                success_to_api_response(event_to_response(event, Utc::now())),
+            )
+        }
+        Ok(None) => (
+            StatusCode::OK,
+            error_to_api_response::<EventDetail>(
+                error_codes::NOT_FOUND,
+                "群组不存在或已归档".to_string(),
+            ),
+        ),
+        Err(e) => {
+            tracing::error!("用户 {} 在群组 {} 创建聚会失败: {}", user_id, group_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<EventDetail>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("创建聚会失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 获取群组的聚会，默认只返回未结束且未取消的聚会
+pub async fn get_group_events(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(group_id): Path<String>,
+    Query(params): Query<ListGroupEventsRequest>,
+) -> impl IntoResponse {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_EVENT_PAGE_SIZE)
+        .clamp(1, MAX_EVENT_PAGE_SIZE);
+
+    match EventOperation::new(Arc::new(state.pool.clone()))
+        .find_by_group(&group_id, &claims.sub, params.include_past, limit as i64)
+        .await
+    {
+        Ok(events) => {
+            let now = Utc::now();
+            (
+                StatusCode::OK,
+                success_to_api_response(ListEventsResponse {
+                    events: events
+                        .into_iter()
+                        .map(|e| event_to_response(e, now))
+                        .collect(),
+                }),
+            )
+        }
+        Err(e) => {
+            tracing::error!("获取群组 {} 的聚会失败: {}", group_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<ListEventsResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取聚会失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 查找附近即将开始或正在进行的聚会
+pub async fn get_nearby_events(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<GetNearbyEventsRequest>,
+) -> impl IntoResponse {
+    if !(-90.0..=90.0).contains(&params.latitude) || !(-180.0..=180.0).contains(&params.longitude) {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<ListEventsResponse>(
+                error_codes::VALIDATION_ERROR,
+                "非法的地理坐标".to_string(),
+            ),
+        );
+    }
+
+    let radius = (params.radius as f64).min(state.config.max_search_radius);
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_EVENT_PAGE_SIZE)
+        .clamp(1, MAX_EVENT_PAGE_SIZE);
+
+    match EventOperation::new(Arc::new(state.pool.clone()))
+        .find_nearby(
+            &claims.sub,
+            (params.latitude, params.longitude),
+            radius,
+            limit as i64,
+        )
+        .await
+    {
+        Ok(events) => {
+            let now = Utc::now();
+            (
+                StatusCode::OK,
+                success_to_api_response(ListEventsResponse {
+                    events: events
+                        .into_iter()
+                        .map(|e| event_to_response(e, now))
+                        .collect(),
+                }),
+            )
+        }
+        Err(e) => {
+            tracing::error!("获取附近聚会失败: {}", e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<ListEventsResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取附近聚会失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 获取聚会详情
+pub async fn get_event(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(event_id): Path<String>,
+) -> impl IntoResponse {
+    match EventOperation::new(Arc::new(state.pool.clone()))
+        .find_by_id(&event_id, &claims.sub)
+        .await
+    {
+        Ok(Some(event)) => (
+            StatusCode::OK,
+            success_to_api_response(event_to_response(event, Utc::now())),
+        ),
+        Ok(None) => (
+            StatusCode::OK,
+            error_to_api_response::<EventDetail>(error_codes::NOT_FOUND, "聚会不存在".to_string()),
+        ),
+        Err(e) => {
+            tracing::error!("获取聚会 {} 失败: {}", event_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<EventDetail>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取聚会失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 查找可以回复的聚会：聚会未取消、未结束，且用户是所属群组的成员
+async fn find_rsvp_event(
+    state: &AppState,
+    event_id: &str,
+    user_id: &str,
+) -> Result<EventEntity, (i32, String)> {
+    let internal = |e: sqlx::Error| {
+        tracing::error!("查询聚会 {} 失败: {}", event_id, e);
+        (error_codes::INTERNAL_ERROR, format!("查询聚会失败: {}", e))
+    };
+
+    let event = EventOperation::new(Arc::new(state.pool.clone()))
+        .find_by_id(event_id, user_id)
+        .await
+        .map_err(internal)?
+        .ok_or((error_codes::NOT_FOUND, "聚会不存在".to_string()))?;
+
+    if event.cancelled_at.is_some() {
+        return Err((error_codes::VALIDATION_ERROR, "聚会已取消".to_string()));
+    }
+    if event.ends_at <= Utc::now() {
+        return Err((error_codes::VALIDATION_ERROR, "聚会已结束".to_string()));
+    }
+
+    let is_member = GroupOperation::new(Arc::new(state.pool.clone()))
+        .has_user(&event.group_id, user_id)
+        .await
+        .map_err(internal)?;
+    if !is_member {
+        return Err((
+            error_codes::PERMISSION_DENIED,
+            "只有群组成员可以回复聚会".to_string(),
+        ));
+    }
+
+    Ok(event)
+}
+
+/// 更新回复后重新统计人数
+async fn rsvp_response(
+    state: &AppState,
+    event_id: &str,
+    user_id: &str,
+) -> Result<RsvpEventResponse, (i32, String)> {
+    let event = EventOperation::new(Arc::new(state.pool.clone()))
+        .find_by_id(event_id, user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("查询聚会 {} 失败: {}", event_id, e);
+            (error_codes::INTERNAL_ERROR, format!("查询聚会失败: {}", e))
+        })?
+        .ok_or((error_codes::NOT_FOUND, "聚会不存在".to_string()))?;
+
+    Ok(RsvpEventResponse {
+        event_id: event.event_id,
+        my_rsvp: event.my_rsvp,
+        going_count: event.going_count,
+        maybe_count: event.maybe_count,
+    })
+}
+
+/// 回复聚会（参加、可能参加或不参加），已回复时覆盖
+pub async fn rsvp_event(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(event_id): Path<String>,
+    Json(payload): Json<RsvpEventRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+
+    let result = async {
+        find_rsvp_event(&state, &event_id, user_id).await?;
+        EventOperation::new(Arc::new(state.pool.clone()))
+            .set_rsvp(&event_id, user_id, payload.status)
+            .await
+            .map_err(|e| {
+                tracing::error!("用户 {} 回复聚会 {} 失败: {}", user_id, event_id, e);
+                (error_codes::INTERNAL_ERROR, format!("回复聚会失败: {}", e))
+            })?;
+        rsvp_response(&state, &event_id, user_id).await
+    }
+    .await;
+
+    match result {
+        Ok(response) => (StatusCode::OK, success_to_api_response(response)),
+        Err((code, msg)) => (
+            StatusCode::OK,
+            error_to_api_response::<RsvpEventResponse>(code, msg),
+        ),
+    }
+}
+
+/// 撤回对聚会的回复，未回复时同样返回成功
+pub async fn remove_event_rsvp(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(event_id): Path<String>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+
+    let result = async {
+        EventOperation::new(Arc::new(state.pool.clone()))
+            .remove_rsvp(&event_id, user_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("用户 {} 撤回聚会 {} 的回复失败: {}", user_id, event_id, e);
+                (error_codes::INTERNAL_ERROR, format!("撤回回复失败: {}", e))
+            })?;
+        rsvp_response(&state, &event_id, user_id).await
+    }
+    .await;
+
+    match result {
+        Ok(response) => (StatusCode::OK, success_to_api_response(response)),
+        Err((code, msg)) => (
+            StatusCode::OK,
+            error_to_api_response::<RsvpEventResponse>(code, msg),
+        ),
+    }
+}
+
+/// 取消聚会，只有群组管理员可以取消
+///
+/// 回复了参加或可能参加的成员会收到取消通知。
+pub async fn cancel_event(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(event_id): Path<String>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+    let repo = EventOperation::new(Arc::new(state.pool.clone()));
+
+    let result = async {
+        let event = repo
+            .find_by_id(&event_id, user_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let is_admin = GroupOperation::new(Arc::new(state.pool.clone()))
+            .is_admin(&event.group_id, user_id)
+            .await?;
+        if !is_admin {
+            return Ok(None);
+        }
+        repo.cancel_event(&event_id, user_id).await.map(Some)
+    }
+    .await;
+
+    match result {
+        Ok(Some(true)) => {
+            tracing::info!("用户 {} 取消了聚会 {}", user_id, event_id);
+            (
+                StatusCode::OK,
+                success_to_api_response(CancelEventResponse { event_id }),
+            )
+        }
+        Ok(Some(false)) => (
+            StatusCode::OK,
+            error_to_api_response::<CancelEventResponse>(
+                error_codes::VALIDATION_ERROR,
+                "聚会已取消或已结束".to_string(),
+            ),
+        ),
+        Ok(None) => (
+            StatusCode::OK,
+            error_to_api_response::<CancelEventResponse>(
+                error_codes::PERMISSION_DENIED,
+                "只有群组管理员可以取消聚会".to_string(),
+            ),
+        ),
+        Err(sqlx::Error::RowNotFound) => (
+            StatusCode::OK,
+            error_to_api_response::<CancelEventResponse>(
+                error_codes::NOT_FOUND,
+                "聚会不存在".to_string(),
+            ),
+        ),
+        Err(e) => {
+            tracing::error!("用户 {} 取消聚会 {} 失败: {}", user_id, event_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<CancelEventResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("取消聚会失败: {}", e),
+                ),
+            )
+        }
+    }
+}
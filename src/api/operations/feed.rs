@@ -0,0 +1,316 @@
+// 首页信息流处理器
+// 在读取时合并关注用户、所在群组和附近公开签到的活动（fan-out-on-read），
+// 各用户、各群组最近的活动在 Redis 中短暂缓存
+
+use crate::AppState;
+use crate::api::models::activity::{ActivityDetail, ActivityType};
+use crate::api::models::feed::*;
+use crate::api::operations::activity::{
+    activity_detail, apply_location_privacy, fill_interactions, fill_photos, fill_place_names,
+};
+use crate::cache::keys::{group_timeline_key, user_timeline_key};
+use crate::cache::models::feed::CachedTimelineActivity;
+use crate::cache::operations::feed::FeedCacheOperations;
+use crate::database::models::activity::ActivityEntity;
+use crate::database::models::privacy::{MAX_PRIVACY_MARGIN, distance_meters};
+use crate::database::operations::activity::ActivityOperation;
+use crate::database::operations::follow::FollowOperation;
+use crate::database::operations::group::GroupOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// 未指定时信息流的返回数量
+const DEFAULT_FEED_SIZE: u32 = 20;
+
+/// 每个用户、每个群组的时间线缓存的最近活动数量
+const TIMELINE_LENGTH: i64 = 50;
+
+/// 合并时最多读取的关注用户数量（按关注时间倒序）
+const MAX_FEED_FOLLOWEES: i64 = 500;
+
+/// 附近签到的最大候选数量
+const MAX_NEARBY_CANDIDATES: i64 = 100;
+
+/// 时间线来源类型
+#[derive(Clone, Copy)]
+enum Timeline {
+    User,
+    Group,
+}
+
+impl Timeline {
+    fn cache_key(self, id: &str) -> String {
+        match self {
+            Timeline::User => user_timeline_key(id),
+            Timeline::Group => group_timeline_key(id),
+        }
+    }
+
+    fn owner_id(self, activity: &ActivityEntity) -> Option<&str> {
+        match self {
+            Timeline::User => Some(&activity.user_id),
+            Timeline::Group => activity.group_id.as_deref(),
+        }
+    }
+}
+
+/// 读取多个用户或群组的时间线
+///
+/// 先批量读取 Redis 缓存，未命中的一次性从数据库加载后写回缓存（包括空时间线）。
+/// 缓存不可用时直接查询数据库。
+async fn load_timelines(
+    state: &AppState,
+    timeline: Timeline,
+    ids: &[String],
+) -> Result<Vec<ActivityEntity>, sqlx::Error> {
+    let cache = FeedCacheOperations::new(state.redis.clone());
+    let keys: Vec<String> = ids.iter().map(|id| timeline.cache_key(id)).collect();
+
+    let cached = match cache.get_timelines(&keys).await {
+        Ok(cached) => cached,
+        Err(e) => {
+            tracing::warn!("读取时间线缓存失败: {}", e);
+            vec![None; ids.len()]
+        }
+    };
+
+    let mut activities = Vec::new();
+    let mut missing = Vec::new();
+    for (id, timeline) in ids.iter().zip(cached) {
+        match timeline {
+            Some(timeline) => activities.extend(timeline.into_iter().map(ActivityEntity::from)),
+            None => missing.push(id.clone()),
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok(activities);
+    }
+
+    let repo = ActivityOperation::new(Arc::new(state.pool.clone()));
+    let loaded = match timeline {
+        Timeline::User => repo.find_recent_by_users(&missing, TIMELINE_LENGTH).await?,
+        Timeline::Group => {
+            repo.find_recent_by_groups(&missing, TIMELINE_LENGTH)
+                .await?
+        }
+    };
+
+    let mut by_owner: HashMap<&str, Vec<CachedTimelineActivity>> =
+        missing.iter().map(|id| (id.as_str(), Vec::new())).collect();
+    for activity in &loaded {
+        if let Some(owner) = timeline.owner_id(activity)
+            && let Some(entries) = by_owner.get_mut(owner)
+        {
+            entries.push(CachedTimelineActivity::from(activity));
+        }
+    }
+    let entries: Vec<(String, Vec<CachedTimelineActivity>)> = by_owner
+        .into_iter()
+        .map(|(id, entries)| (timeline.cache_key(id), entries))
+        .collect();
+
+    if let Err(e) = cache
+        .cache_timelines(&entries, state.config.feed_timeline_cache_ttl_secs)
+        .await
+    {
+        tracing::warn!("写入时间线缓存失败: {}", e);
+    }
+
+    activities.extend(loaded);
+    Ok(activities)
+}
+
+/// 计算信息流排序分数
+///
+/// 新鲜度和距离分数都按半衰期指数衰减到 (0, 1]，按配置的距离权重加权求和；
+/// 未提供当前位置时只按新鲜度排序。
+fn feed_score(state: &AppState, activity: &ActivityEntity, distance: Option<f64>) -> f64 {
+    let age_secs = (Utc::now() - activity.created_at).num_seconds().max(0) as f64;
+    let half_life = state.config.feed_recency_half_life_secs.max(1) as f64;
+    let recency = 0.5_f64.powf(age_secs / half_life);
+
+    match distance {
+        Some(distance) => {
+            let proximity = 0.5_f64.powf(distance / state.config.feed_distance_half_life.max(1.0));
+            let weight = state.config.feed_distance_weight;
+            (1.0 - weight) * recency + weight * proximity
+        }
+        None => recency,
+    }
+}
+
+/// 获取首页信息流
+///
+/// 合并关注用户的活动、所在群组的活动以及当前位置附近的公开签到，
+/// 按新鲜度与距离的加权分数排序。自己的活动不出现在信息流中。
+pub async fn get_home_feed(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<GetHomeFeedRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+
+    let origin = match (params.latitude, params.longitude) {
+        (Some(latitude), Some(longitude))
+            if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) =>
+        {
+            Some((latitude, longitude))
+        }
+        (None, None) => None,
+        _ => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<GetHomeFeedResponse>(
+                    error_codes::VALIDATION_ERROR,
+                    "非法的地理坐标".to_string(),
+                ),
+            );
+        }
+    };
+    let radius = params
+        .radius
+        .map(|r| r as f64)
+        .unwrap_or(state.config.max_search_radius)
+        .min(state.config.max_search_radius);
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_FEED_SIZE)
+        .clamp(1, state.config.activity_max_page_size.max(1)) as usize;
+
+    let result = async {
+        let followee_ids = FollowOperation::new(Arc::new(state.pool.clone()))
+            .find_followee_ids(user_id, MAX_FEED_FOLLOWEES)
+            .await?;
+        let group_ids: Vec<String> = GroupOperation::new(Arc::new(state.pool.clone()))
+            .find_by_user_id(user_id)
+            .await?
+            .into_iter()
+            .map(|g| g.id)
+            .collect();
+
+        // 同一活动可能同时来自多个来源，保留优先级最高的来源
+        let mut merged: HashMap<String, (ActivityEntity, FeedSource)> = HashMap::new();
+        let mut merge = |activities: Vec<ActivityEntity>, source: FeedSource| {
+            for activity in activities {
+                if activity.user_id != *user_id {
+                    merged
+                        .entry(activity.id.clone())
+                        .or_insert((activity, source));
+                }
+            }
+        };
+
+        merge(
+            load_timelines(&state, Timeline::User, &followee_ids).await?,
+            FeedSource::Following,
+        );
+        merge(
+            load_timelines(&state, Timeline::Group, &group_ids).await?,
+            FeedSource::Group,
+        );
+        let repo = ActivityOperation::new(Arc::new(state.pool.clone()));
+        if let Some(origin) = origin {
+            // 与附近活动接口一致，扩大范围查询后再按偏移后的位置过滤
+            let nearby = repo
+                .find_nearby_activities_by_type(
+                    user_id,
+                    origin,
+                    radius + MAX_PRIVACY_MARGIN,
+                    &[ActivityType::UserCheckedIn],
+                    None,
+                    MAX_NEARBY_CANDIDATES,
+                )
+                .await?;
+            merge(nearby.activities, FeedSource::Nearby);
+        }
+
+        // 缓存的时间线在查看者之间共享，按当前用户重新检查可见范围
+        let ids: Vec<String> = merged.keys().cloned().collect();
+        let visible: HashSet<String> = repo
+            .filter_visible(&ids, user_id)
+            .await?
+            .into_iter()
+            .collect();
+        merged.retain(|id, _| visible.contains(id));
+
+        let sources: HashMap<String, FeedSource> = merged
+            .iter()
+            .map(|(id, (_, source))| (id.clone(), *source))
+            .collect();
+        let activities = merged.into_values().map(|(activity, _)| activity).collect();
+        let activities = apply_location_privacy(&state, user_id, activities).await?;
+
+        Ok::<_, sqlx::Error>((activities, sources))
+    }
+    .await;
+
+    match result {
+        Ok((activities, sources)) => {
+            let mut ranked: Vec<(ActivityEntity, FeedSource, Option<f64>, f64)> = activities
+                .into_iter()
+                .filter_map(|activity| {
+                    let source = sources.get(&activity.id).copied()?;
+                    let distance =
+                        origin.map(|o| distance_meters(o, (activity.latitude, activity.longitude)));
+                    if source == FeedSource::Nearby && distance.is_some_and(|d| d > radius) {
+                        return None;
+                    }
+                    let score = feed_score(&state, &activity, distance);
+                    Some((activity, source, distance, score))
+                })
+                .collect();
+            ranked.sort_by(|a, b| {
+                b.3.total_cmp(&a.3)
+                    .then_with(|| b.0.created_at.cmp(&a.0.created_at))
+            });
+            ranked.truncate(limit);
+
+            let (mut details, meta): (Vec<ActivityDetail>, Vec<(FeedSource, f64)>) = ranked
+                .into_iter()
+                .map(|(activity, source, distance, score)| {
+                    (activity_detail(activity, distance), (source, score))
+                })
+                .unzip();
+
+            fill_place_names(&state, &mut details).await;
+
+            fill_interactions(&state, user_id, &mut details).await;
+
+            fill_photos(&state, &mut details).await;
+
+            (
+                StatusCode::OK,
+                success_to_api_response(GetHomeFeedResponse {
+                    items: details
+                        .into_iter()
+                        .zip(meta)
+                        .map(|(activity, (source, score))| FeedItem {
+                            activity,
+                            source,
+                            score,
+                        })
+                        .collect(),
+                }),
+            )
+        }
+        Err(e) => {
+            tracing::error!("获取用户 {} 的首页信息流失败: {}", user_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<GetHomeFeedResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取信息流失败: {}", e),
+                ),
+            )
+        }
+    }
+}
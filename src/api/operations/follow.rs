@@ -0,0 +1,199 @@
+// 用户关注处理器
+// 处理按公开用户ID关注、取消关注以及查询关注列表
+
+use crate::AppState;
+use crate::api::models::follow::*;
+use crate::database::operations::follow::FollowOperation;
+use crate::database::operations::user::UserOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+/// 按公开用户ID查找内部用户ID
+async fn resolve_public_user(
+    state: &AppState,
+    public_user_id: &str,
+) -> Result<String, (i32, String)> {
+    match UserOperation::find_by_public_id(&state.pool, public_user_id).await {
+        Ok(Some(user)) => Ok(user.user_id),
+        Ok(None) => Err((error_codes::NOT_FOUND, "用户不存在".to_string())),
+        Err(e) => {
+            tracing::error!("查询用户 {} 失败: {}", public_user_id, e);
+            Err((error_codes::INTERNAL_ERROR, format!("查询用户失败: {}", e)))
+        }
+    }
+}
+
+/// 关注用户
+pub async fn follow_user(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(public_user_id): Path<String>,
+) -> impl IntoResponse {
+    let followee_id = match resolve_public_user(&state, &public_user_id).await {
+        Ok(user_id) => user_id,
+        Err((code, msg)) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<FollowResponse>(code, msg),
+            );
+        }
+    };
+
+    if followee_id == claims.sub {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<FollowResponse>(
+                error_codes::VALIDATION_ERROR,
+                "不能关注自己".to_string(),
+            ),
+        );
+    }
+
+    let repo = FollowOperation::new(Arc::new(state.pool.clone()));
+    match repo
+        .follow(&claims.sub, &followee_id, state.config.max_following as i64)
+        .await
+    {
+        Ok(true) => (
+            StatusCode::OK,
+            success_to_api_response(FollowResponse {
+                user_id: public_user_id,
+                following: true,
+            }),
+        ),
+        Ok(false) => (
+            StatusCode::OK,
+            error_to_api_response::<FollowResponse>(
+                error_codes::VALIDATION_ERROR,
+                format!("最多关注{}个用户", state.config.max_following),
+            ),
+        ),
+        Err(e) => {
+            tracing::error!("用户 {} 关注 {} 失败: {}", claims.sub, public_user_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<FollowResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("关注失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 取消关注用户，未关注时同样返回成功
+pub async fn unfollow_user(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(public_user_id): Path<String>,
+) -> impl IntoResponse {
+    let followee_id = match resolve_public_user(&state, &public_user_id).await {
+        Ok(user_id) => user_id,
+        Err((code, msg)) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<FollowResponse>(code, msg),
+            );
+        }
+    };
+
+    let repo = FollowOperation::new(Arc::new(state.pool.clone()));
+    match repo.unfollow(&claims.sub, &followee_id).await {
+        Ok(_) => (
+            StatusCode::OK,
+            success_to_api_response(FollowResponse {
+                user_id: public_user_id,
+                following: false,
+            }),
+        ),
+        Err(e) => {
+            tracing::error!(
+                "用户 {} 取消关注 {} 失败: {}",
+                claims.sub,
+                public_user_id,
+                e
+            );
+            (
+                StatusCode::OK,
+                error_to_api_response::<FollowResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("取消关注失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 获取当前用户的关注列表
+pub async fn get_following(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> impl IntoResponse {
+    let repo = FollowOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.find_following(&claims.sub).await {
+        Ok(users) => (
+            StatusCode::OK,
+            success_to_api_response(FollowListResponse {
+                users: users
+                    .into_iter()
+                    .map(|u| FollowUserInfo {
+                        user_id: u.public_user_id,
+                        nickname: u.nickname,
+                        followed_at: u.followed_at,
+                    })
+                    .collect(),
+            }),
+        ),
+        Err(e) => {
+            tracing::error!("获取用户 {} 的关注列表失败: {}", claims.sub, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<FollowListResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取关注列表失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 获取当前用户的粉丝列表
+pub async fn get_followers(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> impl IntoResponse {
+    let repo = FollowOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.find_followers(&claims.sub).await {
+        Ok(users) => (
+            StatusCode::OK,
+            success_to_api_response(FollowListResponse {
+                users: users
+                    .into_iter()
+                    .map(|u| FollowUserInfo {
+                        user_id: u.public_user_id,
+                        nickname: u.nickname,
+                        followed_at: u.followed_at,
+                    })
+                    .collect(),
+            }),
+        ),
+        Err(e) => {
+            tracing::error!("获取用户 {} 的粉丝列表失败: {}", claims.sub, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<FollowListResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取粉丝列表失败: {}", e),
+                ),
+            )
+        }
+    }
+}
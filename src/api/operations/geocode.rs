@@ -0,0 +1,57 @@
+// 逆地理编码处理器
+// 基于离线地名库将坐标解析为行政区名称
+
+use crate::AppState;
+use crate::api::models::geocode::*;
+use crate::database::operations::geocode::GeocodeOperation;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+/// 将坐标解析为国家、省、市、区县
+pub async fn reverse_geocode(
+    State(state): State<AppState>,
+    Query(params): Query<ReverseGeocodeRequest>,
+) -> impl IntoResponse {
+    if !(-90.0..=90.0).contains(&params.latitude) || !(-180.0..=180.0).contains(&params.longitude) {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<ReverseGeocodeResponse>(
+                error_codes::VALIDATION_ERROR,
+                "非法的地理坐标".to_string(),
+            ),
+        );
+    }
+
+    let repo = GeocodeOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.reverse(params.latitude, params.longitude).await {
+        Ok(place) => {
+            let display_name = place.display_name();
+            (
+                StatusCode::OK,
+                success_to_api_response(ReverseGeocodeResponse {
+                    country: place.country,
+                    province: place.province,
+                    city: place.city,
+                    district: place.district,
+                    display_name,
+                }),
+            )
+        }
+        Err(e) => {
+            tracing::error!("逆地理编码失败: {}", e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<ReverseGeocodeResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("逆地理编码失败: {}", e),
+                ),
+            )
+        }
+    }
+}
@@ -2,15 +2,21 @@
 // 处理群组相关的API请求
 
 use crate::AppState;
+use crate::api::models::common::{Location, PaginatedResponse, Pagination};
 use crate::api::models::group::*;
+use crate::database::models::group::{
+    GeofenceCheck, GroupSearchFilter, GroupWithDetails, JoinOutcome, NewGroup,
+};
+use crate::database::operations::geocode::GeocodeOperation;
 use crate::database::operations::group::GroupOperation;
 use crate::utils::Claims;
 use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
 use axum::{
-    extract::{Extension, Json, Path, State},
+    extract::{Extension, Json, Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
+use chrono::Utc;
 use std::sync::Arc;
 
 /// 创建群组
@@ -21,22 +27,125 @@ pub async fn create_group(
 ) -> impl IntoResponse {
     tracing::debug!("用户 {} 正在创建群组: {}", claims.sub, payload.name);
 
+    // 过期时间必须晚于当前时间
+    if let Some(expires_at) = payload.expires_at
+        && expires_at <= Utc::now()
+    {
+        tracing::warn!("用户 {} 创建群组时提供了已过去的过期时间", claims.sub);
+        return (
+            StatusCode::OK,
+            error_to_api_response::<CreateGroupResponse>(
+                error_codes::VALIDATION_ERROR,
+                "过期时间必须晚于当前时间".to_string(),
+            ),
+        );
+    }
+
+    // 人数上限至少为1（创建者本身）
+    if payload.max_members.is_some_and(|max| max < 1) {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<CreateGroupResponse>(
+                error_codes::VALIDATION_ERROR,
+                "人数上限必须大于0".to_string(),
+            ),
+        );
+    }
+
+    // 校验标签数量与长度
+    let tags = normalize_tags(payload.tags.clone());
+    if tags.len() > MAX_GROUP_TAGS || tags.iter().any(|t| t.chars().count() > MAX_GROUP_TAG_LEN) {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<CreateGroupResponse>(
+                error_codes::VALIDATION_ERROR,
+                format!(
+                    "最多{}个标签，每个标签不超过{}个字符",
+                    MAX_GROUP_TAGS, MAX_GROUP_TAG_LEN
+                ),
+            ),
+        );
+    }
+
+    // 校验地理围栏，半径与多边形只能设置一种
+    let geofence_polygon = match (payload.geofence_radius, payload.geofence_polygon.as_deref()) {
+        (Some(_), Some(_)) => Err("地理围栏只能设置半径或多边形其中一种".to_string()),
+        (Some(radius), None) if radius == 0 || radius > MAX_GEOFENCE_RADIUS => {
+            Err(format!("地理围栏半径必须在1-{}米之间", MAX_GEOFENCE_RADIUS))
+        }
+        (None, Some(points)) => geofence_to_wkt(points).map(Some),
+        _ => Ok(None),
+    };
+    let geofence_polygon = match geofence_polygon {
+        Ok(polygon) => polygon,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<CreateGroupResponse>(error_codes::VALIDATION_ERROR, msg),
+            );
+        }
+    };
+
+    // 未提供位置名称时按坐标从离线地名库解析
+    let location_name = match payload
+        .location_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        Some(name) => name.to_string(),
+        None => match GeocodeOperation::new(Arc::new(state.pool.clone()))
+            .reverse(payload.latitude, payload.longitude)
+            .await
+        {
+            Ok(place) => match place.display_name() {
+                Some(name) => name,
+                None => {
+                    return (
+                        StatusCode::OK,
+                        error_to_api_response::<CreateGroupResponse>(
+                            error_codes::VALIDATION_ERROR,
+                            "无法解析该位置的名称，请手动填写位置名称".to_string(),
+                        ),
+                    );
+                }
+            },
+            Err(e) => {
+                tracing::error!("解析群组位置名称失败: {}", e);
+                return (
+                    StatusCode::OK,
+                    error_to_api_response::<CreateGroupResponse>(
+                        error_codes::INTERNAL_ERROR,
+                        format!("解析位置名称失败: {}", e),
+                    ),
+                );
+            }
+        },
+    };
+
     // 创建仓库实例
     let repo = GroupOperation::new(Arc::new(state.pool.clone()));
 
+    let description = payload.description.clone().unwrap_or_default();
+    let new_group = NewGroup {
+        name: &payload.name,
+        location_name: &location_name,
+        latitude: payload.latitude,
+        longitude: payload.longitude,
+        description: &description,
+        password: payload.password.as_deref(),
+        creator_id: &claims.sub, // 使用认证信息中的用户ID
+        expires_at: payload.expires_at,
+        max_members: payload.max_members,
+        category: payload.category.map(|c| c.as_str()),
+        tags: &tags,
+        geofence_radius: payload.geofence_radius.map(|r| r as i32),
+        geofence_polygon: geofence_polygon.as_deref(),
+        requires_approval: payload.requires_approval,
+    };
+
     // 使用仓库方法创建群组
-    match repo
-        .create(
-            &payload.name,
-            &payload.location_name,
-            payload.latitude,
-            payload.longitude,
-            &payload.description.unwrap_or_default(),
-            payload.password.as_deref(),
-            &claims.sub, // 使用认证信息中的用户ID
-        )
-        .await
-    {
+    match repo.create(&new_group).await {
         Ok(group_id) => {
             tracing::info!(
                 "用户 {} 成功创建群组 {}: {}",
@@ -92,6 +201,14 @@ pub async fn get_group_info(
                         distance: 0.0, // 单个群组查询不需要距离
                         location_name: group.location_name,
                         is_password_required: group.password.is_some(),
+                        expires_at: group.expires_at,
+                        max_members: group.max_members,
+                        category: group.category.as_deref().and_then(GroupCategory::from_db),
+                        tags: group.tags,
+                        geofence_radius: group.geofence_radius,
+                        is_geofenced: group.is_geofenced,
+                        requires_approval: group.requires_approval,
+                        score: None,
                     };
 
                     (StatusCode::OK, success_to_api_response(detailed_group))
@@ -344,6 +461,14 @@ pub async fn get_user_groups(
                     distance: 0.0,   // 用户的群组列表不需要距离信息
                     location_name: group.location_name,
                     is_password_required: group.password.is_some(),
+                    expires_at: group.expires_at,
+                    max_members: group.max_members,
+                    category: group.category.as_deref().and_then(GroupCategory::from_db),
+                    tags: group.tags,
+                    geofence_radius: group.geofence_radius,
+                    is_geofenced: group.is_geofenced,
+                    requires_approval: group.requires_approval,
+                    score: None,
                 });
             }
 
@@ -477,42 +602,75 @@ pub async fn search_groups_by_name(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Path(name): Path<String>,
+    Query(params): Query<SearchGroupsByNameParams>,
 ) -> impl IntoResponse {
     // 创建仓库实例
     let repo = GroupOperation::new(Arc::new(state.pool.clone()));
 
     tracing::debug!("用户 {} 正在搜索群组名称: {}", claims.sub, name);
 
-    // 搜索群组
-    match repo.find_by_name_with_creator(&name).await {
-        Ok(groups) => {
-            // 转换为API响应格式
-            let mut result = Vec::new();
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 50 {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<PaginatedResponse<GroupDetail>>(
+                error_codes::VALIDATION_ERROR,
+                "搜索关键词长度必须在1-50个字符之间".to_string(),
+            ),
+        );
+    }
 
-            for (group, creator) in groups {
-                result.push(GroupDetail {
-                    group_id: group.id,
-                    name: group.name,
-                    description: group.description,
-                    public_creator_id: creator.public_user_id,
-                    creator_name: Some(creator.nickname),
-                    avatar_url: None,
-                    created_at: group.created_at,
-                    last_active_at: group.last_active,
-                    latitude: group.latitude,
-                    longitude: group.longitude,
-                    member_count: 0,
-                    distance: 0.0,
-                    location_name: group.location_name,
-                    is_password_required: group.password.is_some(),
-                });
+    // 经纬度需同时提供才用于距离加权
+    let origin = match (params.latitude, params.longitude) {
+        (Some(latitude), Some(longitude)) => {
+            if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+                return (
+                    StatusCode::OK,
+                    error_to_api_response::<PaginatedResponse<GroupDetail>>(
+                        error_codes::VALIDATION_ERROR,
+                        "无效的地理坐标".to_string(),
+                    ),
+                );
             }
-
-            (StatusCode::OK, success_to_api_response(result))
+            Some((latitude, longitude))
         }
+        (None, None) => None,
+        _ => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<PaginatedResponse<GroupDetail>>(
+                    error_codes::VALIDATION_ERROR,
+                    "纬度和经度必须同时提供".to_string(),
+                ),
+            );
+        }
+    };
+
+    let tags = params
+        .tags
+        .as_deref()
+        .map(|tags| tags.split(',').map(str::to_string).collect())
+        .unwrap_or_default();
+    let (page, page_size, filter) = build_search_filter(
+        tags,
+        params.category,
+        params.access,
+        params.min_members,
+        params.active_within_hours,
+        params.sort_by,
+        params.page,
+        params.page_size,
+    );
+
+    // 搜索群组
+    match repo.find_by_name_with_details(name, origin, &filter).await {
+        Ok((groups, total)) => (
+            StatusCode::OK,
+            success_to_api_response(to_paginated_groups(groups, total, page, page_size)),
+        ),
         Err(err) => (
             StatusCode::OK,
-            error_to_api_response::<Vec<GroupDetail>>(
+            error_to_api_response::<PaginatedResponse<GroupDetail>>(
                 error_codes::INTERNAL_ERROR,
                 format!("查询群组失败: {}", err),
             ),
@@ -654,52 +812,36 @@ pub async fn search_groups_by_location(
         payload.radius
     );
 
+    let (page, page_size, filter) = build_search_filter(
+        payload.tags,
+        payload.category,
+        payload.access,
+        payload.min_members,
+        payload.active_within_hours,
+        payload.sort_by,
+        payload.page,
+        payload.page_size,
+    );
+
     // 搜索附近群组
     match repo
-        .find_by_location_with_details(payload.latitude, payload.longitude, payload.radius as f64)
+        .find_by_location_with_details(
+            payload.latitude,
+            payload.longitude,
+            payload.radius as f64,
+            &filter,
+        )
         .await
     {
-        Ok(groups) => {
-            let mut detailed_groups = Vec::new();
-
-            for group in groups {
-                // 计算距离
-                let dx = (group.longitude - payload.longitude).abs() * 111000.0;
-                let dy = (group.latitude - payload.latitude).abs() * 111000.0;
-                let distance = (dx * dx + dy * dy).sqrt();
-
-                // 检查群组是否需要密码
-                let group_info = repo.find_by_id(&group.id).await;
-                let is_password_required = match group_info {
-                    Ok(Some(info)) => info.password.is_some(),
-                    _ => false, // 默认不需要密码
-                };
-
-                detailed_groups.push(GroupDetail {
-                    group_id: group.id,
-                    name: group.name,
-                    description: group.description,
-                    public_creator_id: group.creator_public_id.unwrap_or_default(),
-                    creator_name: group.creator_name,
-                    avatar_url: group.avatar_url,
-                    created_at: group.created_at,
-                    last_active_at: group.last_active_at,
-                    latitude: group.latitude,
-                    longitude: group.longitude,
-                    member_count: group.member_count,
-                    distance,
-                    location_name: group.location_name,
-                    is_password_required,
-                });
-            }
-
-            (StatusCode::OK, success_to_api_response(detailed_groups))
-        }
+        Ok((groups, total)) => (
+            StatusCode::OK,
+            success_to_api_response(to_paginated_groups(groups, total, page, page_size)),
+        ),
         Err(err) => {
             tracing::error!("查找附近群组失败: {}", err);
             (
                 StatusCode::OK,
-                error_to_api_response::<Vec<GroupDetail>>(
+                error_to_api_response::<PaginatedResponse<GroupDetail>>(
                     error_codes::INTERNAL_ERROR,
                     format!("搜索附近群组失败: {}", err),
                 ),
@@ -708,6 +850,139 @@ pub async fn search_groups_by_location(
     }
 }
 
+/// 单页最多返回的群组数量
+const MAX_GROUP_PAGE_SIZE: u32 = 50;
+
+/// 单个群组最多的标签数量
+pub(crate) const MAX_GROUP_TAGS: usize = 10;
+
+/// 单个标签的最大长度（字符）
+pub(crate) const MAX_GROUP_TAG_LEN: usize = 32;
+
+/// 地理围栏的最大半径（米）
+const MAX_GEOFENCE_RADIUS: u32 = 50_000;
+
+/// 地理围栏多边形的最大顶点数
+const MAX_GEOFENCE_VERTICES: usize = 100;
+
+/// 规范化群组标签：去除首尾空白、转为小写并去重
+pub(crate) fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// 将地理围栏顶点转换为 WKT 多边形，自动闭合首尾
+fn geofence_to_wkt(points: &[Location]) -> Result<String, String> {
+    let mut points = points.to_vec();
+    if let (Some(first), Some(last)) = (points.first(), points.last())
+        && first.latitude == last.latitude
+        && first.longitude == last.longitude
+    {
+        points.pop();
+    }
+
+    if points.len() < 3 || points.len() > MAX_GEOFENCE_VERTICES {
+        return Err(format!(
+            "地理围栏多边形需要3-{}个顶点",
+            MAX_GEOFENCE_VERTICES
+        ));
+    }
+    if points
+        .iter()
+        .any(|p| !(-90.0..=90.0).contains(&p.latitude) || !(-180.0..=180.0).contains(&p.longitude))
+    {
+        return Err("地理围栏包含无效的地理坐标".to_string());
+    }
+
+    let ring = points
+        .iter()
+        .chain(points.first())
+        .map(|p| format!("{} {}", p.longitude, p.latitude))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(format!("POLYGON(({}))", ring))
+}
+
+/// 将请求中的搜索条件转换为数据库查询条件，返回规范化后的页码、每页数量和查询条件
+#[allow(clippy::too_many_arguments)]
+fn build_search_filter(
+    tags: Vec<String>,
+    category: Option<GroupCategory>,
+    access: Option<GroupAccess>,
+    min_members: Option<u32>,
+    active_within_hours: Option<u32>,
+    sort_by: GroupSortBy,
+    page: u32,
+    page_size: u32,
+) -> (u32, u32, GroupSearchFilter) {
+    let page = page.max(1);
+    let page_size = page_size.clamp(1, MAX_GROUP_PAGE_SIZE);
+
+    let filter = GroupSearchFilter {
+        tags: normalize_tags(tags),
+        category: category.map(|c| c.as_str().to_string()),
+        access,
+        min_members: min_members.map(i64::from),
+        active_within_hours: active_within_hours.map(|h| h.min(i32::MAX as u32) as i32),
+        sort_by,
+        offset: (page as i64 - 1) * page_size as i64,
+        limit: page_size as i64,
+    };
+
+    (page, page_size, filter)
+}
+
+/// 将数据库查询结果转换为分页的群组列表，`total` 为满足条件的群组总数
+pub(crate) fn to_paginated_groups(
+    groups: Vec<GroupWithDetails>,
+    total: i64,
+    page: u32,
+    page_size: u32,
+) -> PaginatedResponse<GroupDetail> {
+    let items = groups
+        .into_iter()
+        .map(|group| GroupDetail {
+            group_id: group.id,
+            name: group.name,
+            description: group.description,
+            public_creator_id: group.creator_public_id.unwrap_or_default(),
+            creator_name: group.creator_name,
+            avatar_url: group.avatar_url,
+            created_at: group.created_at,
+            last_active_at: group.last_active_at,
+            latitude: group.latitude,
+            longitude: group.longitude,
+            member_count: group.member_count,
+            distance: group.distance.unwrap_or(0.0),
+            location_name: group.location_name,
+            is_password_required: group.is_password_required,
+            expires_at: group.expires_at,
+            max_members: group.max_members,
+            category: group.category.as_deref().and_then(GroupCategory::from_db),
+            tags: group.tags,
+            geofence_radius: group.geofence_radius,
+            is_geofenced: group.is_geofenced,
+            requires_approval: group.requires_approval,
+            score: group.score,
+        })
+        .collect();
+
+    PaginatedResponse {
+        items,
+        pagination: Pagination {
+            page,
+            page_size,
+            total: total as u64,
+        },
+    }
+}
+
 /// 加入群组
 pub async fn join_group(
     State(state): State<AppState>,
@@ -723,16 +998,90 @@ pub async fn join_group(
     // 从认证信息中获取用户ID
     let user_id = &claims.sub;
 
+    // 设置了地理围栏的群组需要在围栏内才能加入
+    match repo
+        .check_geofence(&group_id, payload.latitude.zip(payload.longitude))
+        .await
+    {
+        Ok(GeofenceCheck::Outside) => {
+            tracing::warn!(
+                "用户 {} 加入群组 {} 失败: 不在地理围栏内",
+                user_id,
+                group_id
+            );
+            return (
+                StatusCode::OK,
+                error_to_api_response::<GroupJoinStatusResponse>(
+                    error_codes::OUTSIDE_GEOFENCE,
+                    "需要在群组所在区域内才能加入".to_string(),
+                ),
+            );
+        }
+        Ok(_) => {}
+        Err(err) => {
+            tracing::error!("检查群组 {} 地理围栏失败: {}", group_id, err);
+            return (
+                StatusCode::OK,
+                error_to_api_response::<GroupJoinStatusResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("检查地理围栏失败: {}", err),
+                ),
+            );
+        }
+    }
+
     // 添加用户到群组
     match repo
-        .add_user(&group_id, user_id, payload.password.as_deref())
+        .add_user(
+            &group_id,
+            user_id,
+            payload.password.as_deref(),
+            payload.join_waitlist,
+        )
         .await
     {
+        Ok(JoinOutcome::Waitlisted(position)) => {
+            tracing::info!(
+                "群组 {} 已满，用户 {} 进入候补队列，位置 {}",
+                group_id,
+                user_id,
+                position
+            );
+            (
+                StatusCode::OK,
+                success_to_api_response(GroupJoinStatusResponse {
+                    success: true,
+                    joined: false,
+                    waitlisted: true,
+                    waitlist_position: Some(position),
+                    pending_approval: false,
+                }),
+            )
+        }
+        Ok(JoinOutcome::PendingApproval) => {
+            tracing::info!("用户 {} 已提交加入群组 {} 的申请", user_id, group_id);
+            (
+                StatusCode::OK,
+                success_to_api_response(GroupJoinStatusResponse {
+                    success: true,
+                    joined: false,
+                    waitlisted: false,
+                    waitlist_position: None,
+                    pending_approval: true,
+                }),
+            )
+        }
         Ok(_) => {
             tracing::info!("用户 {} 成功加入群组 {}", user_id, group_id);
             (
                 StatusCode::OK,
-                success_to_api_response(JoinGroupResponse { success: true }),
+                success_to_api_response(GroupJoinStatusResponse {
+                    success: true,
+                    joined: true,
+                    waitlisted: false,
+                    waitlist_position: None,
+                    pending_approval: false,
+                }),
             )
         }
         Err(err) => {
@@ -745,16 +1094,34 @@ pub async fn join_group(
                 );
                 (
                     StatusCode::OK,
-                    error_to_api_response::<JoinGroupResponse>(
+                    error_to_api_response::<GroupJoinStatusResponse>(
                         error_codes::AUTH_FAILED,
                         "密码错误或缺失".to_string(),
                     ),
                 )
+            } else if error_msg.contains("Group is full") {
+                tracing::warn!("用户 {} 加入群组 {} 失败: 群组已满", user_id, group_id);
+                (
+                    StatusCode::OK,
+                    error_to_api_response::<GroupJoinStatusResponse>(
+                        error_codes::GROUP_FULL,
+                        "群组已满，可以加入候补队列".to_string(),
+                    ),
+                )
+            } else if error_msg.contains("Group is archived") {
+                tracing::warn!("用户 {} 加入群组 {} 失败: 群组已归档", user_id, group_id);
+                (
+                    StatusCode::OK,
+                    error_to_api_response::<GroupJoinStatusResponse>(
+                        error_codes::VALIDATION_ERROR,
+                        "群组已归档".to_string(),
+                    ),
+                )
             } else if error_msg.contains("not found") {
                 tracing::warn!("用户 {} 加入群组 {} 失败: 群组不存在", user_id, group_id);
                 (
                     StatusCode::OK,
-                    error_to_api_response::<JoinGroupResponse>(
+                    error_to_api_response::<GroupJoinStatusResponse>(
                         error_codes::NOT_FOUND,
                         "群组不存在".to_string(),
                     ),
@@ -763,7 +1130,7 @@ pub async fn join_group(
                 tracing::error!("用户 {} 加入群组 {} 失败: {}", user_id, group_id, err);
                 (
                     StatusCode::OK,
-                    error_to_api_response::<JoinGroupResponse>(
+                    error_to_api_response::<GroupJoinStatusResponse>(
                         error_codes::INTERNAL_ERROR,
                         format!("加入群组失败: {}", err),
                     ),
@@ -772,3 +1139,277 @@ pub async fn join_group(
         }
     }
 }
+
+/// 退出候补队列
+pub async fn leave_waitlist(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(group_id): Path<String>,
+) -> impl IntoResponse {
+    // 创建仓库实例
+    let repo = GroupOperation::new(Arc::new(state.pool.clone()));
+
+    let user_id = &claims.sub;
+    tracing::debug!("用户 {} 正在退出群组 {} 的候补队列", user_id, group_id);
+
+    match repo.leave_waitlist(&group_id, user_id).await {
+        Ok(true) => {
+            tracing::info!("用户 {} 已退出群组 {} 的候补队列", user_id, group_id);
+            (
+                StatusCode::OK,
+                success_to_api_response(JoinGroupResponse { success: true }),
+            )
+        }
+        Ok(false) => (
+            StatusCode::OK,
+            error_to_api_response::<JoinGroupResponse>(
+                error_codes::NOT_FOUND,
+                "用户不在该群组的候补队列中".to_string(),
+            ),
+        ),
+        Err(err) => {
+            tracing::error!(
+                "用户 {} 退出群组 {} 候补队列失败: {}",
+                user_id,
+                group_id,
+                err
+            );
+            (
+                StatusCode::OK,
+                error_to_api_response::<JoinGroupResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("退出候补队列失败: {}", err),
+                ),
+            )
+        }
+    }
+}
+
+/// 检查用户是否为群组管理员，不是管理员或检查失败时返回错误码和错误信息
+async fn require_group_admin(
+    repo: &GroupOperation,
+    group_id: &str,
+    user_id: &str,
+) -> Result<(), (i32, String)> {
+    match repo.is_admin(group_id, user_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            tracing::warn!(
+                "用户 {} 尝试无权限操作: 处理群组 {} 的加入申请",
+                user_id,
+                group_id
+            );
+            Err((error_codes::PERMISSION_DENIED, "需要管理员权限".to_string()))
+        }
+        Err(err) => {
+            tracing::error!("检查用户 {} 的管理员权限失败: {}", user_id, err);
+            Err((
+                error_codes::INTERNAL_ERROR,
+                format!("检查管理员权限失败: {}", err),
+            ))
+        }
+    }
+}
+
+/// 获取群组待审批的加入申请（需要管理员权限）
+pub async fn get_join_requests(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(group_id): Path<String>,
+) -> impl IntoResponse {
+    let repo = GroupOperation::new(Arc::new(state.pool.clone()));
+
+    if let Err((code, msg)) = require_group_admin(&repo, &group_id, &claims.sub).await {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<Vec<GroupJoinRequestInfo>>(code, msg),
+        );
+    }
+
+    match repo.find_join_requests(&group_id).await {
+        Ok(requests) => {
+            let result: Vec<GroupJoinRequestInfo> = requests
+                .into_iter()
+                .map(|request| GroupJoinRequestInfo {
+                    public_user_id: request.public_user_id,
+                    nickname: request.nickname,
+                    requested_at: request.requested_at,
+                })
+                .collect();
+
+            (StatusCode::OK, success_to_api_response(result))
+        }
+        Err(err) => {
+            tracing::error!("获取群组 {} 的加入申请失败: {}", group_id, err);
+            (
+                StatusCode::OK,
+                error_to_api_response::<Vec<GroupJoinRequestInfo>>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取加入申请失败: {}", err),
+                ),
+            )
+        }
+    }
+}
+
+/// 通过加入申请（需要管理员权限）
+///
+/// 群组已满时申请者进入候补队列，有空位时按顺序自动加入。
+pub async fn approve_join_request(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((group_id, public_user_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let repo = GroupOperation::new(Arc::new(state.pool.clone()));
+
+    if let Err((code, msg)) = require_group_admin(&repo, &group_id, &claims.sub).await {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<GroupJoinStatusResponse>(code, msg),
+        );
+    }
+
+    let (code, msg) = match repo.approve_join_request(&group_id, &public_user_id).await {
+        Ok(Some(outcome)) => {
+            tracing::info!(
+                "用户 {} 通过了用户 {} 加入群组 {} 的申请: {:?}",
+                claims.sub,
+                public_user_id,
+                group_id,
+                outcome
+            );
+            let (joined, waitlist_position) = match outcome {
+                JoinOutcome::Waitlisted(position) => (false, Some(position)),
+                _ => (true, None),
+            };
+            return (
+                StatusCode::OK,
+                success_to_api_response(GroupJoinStatusResponse {
+                    success: true,
+                    joined,
+                    waitlisted: waitlist_position.is_some(),
+                    waitlist_position,
+                    pending_approval: false,
+                }),
+            );
+        }
+        Ok(None) => (error_codes::NOT_FOUND, "加入申请不存在".to_string()),
+        Err(err) if err.to_string().contains("Group is archived") => {
+            (error_codes::VALIDATION_ERROR, "群组已归档".to_string())
+        }
+        Err(err) => {
+            tracing::error!(
+                "通过用户 {} 加入群组 {} 的申请失败: {}",
+                public_user_id,
+                group_id,
+                err
+            );
+            (
+                error_codes::INTERNAL_ERROR,
+                format!("通过加入申请失败: {}", err),
+            )
+        }
+    };
+
+    (
+        StatusCode::OK,
+        error_to_api_response::<GroupJoinStatusResponse>(code, msg),
+    )
+}
+
+/// 拒绝加入申请（需要管理员权限）
+pub async fn reject_join_request(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((group_id, public_user_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let repo = GroupOperation::new(Arc::new(state.pool.clone()));
+
+    if let Err((code, msg)) = require_group_admin(&repo, &group_id, &claims.sub).await {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<JoinGroupResponse>(code, msg),
+        );
+    }
+
+    match repo.reject_join_request(&group_id, &public_user_id).await {
+        Ok(true) => {
+            tracing::info!(
+                "用户 {} 拒绝了用户 {} 加入群组 {} 的申请",
+                claims.sub,
+                public_user_id,
+                group_id
+            );
+            (
+                StatusCode::OK,
+                success_to_api_response(JoinGroupResponse { success: true }),
+            )
+        }
+        Ok(false) => (
+            StatusCode::OK,
+            error_to_api_response::<JoinGroupResponse>(
+                error_codes::NOT_FOUND,
+                "加入申请不存在".to_string(),
+            ),
+        ),
+        Err(err) => {
+            tracing::error!(
+                "拒绝用户 {} 加入群组 {} 的申请失败: {}",
+                public_user_id,
+                group_id,
+                err
+            );
+            (
+                StatusCode::OK,
+                error_to_api_response::<JoinGroupResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("拒绝加入申请失败: {}", err),
+                ),
+            )
+        }
+    }
+}
+
+/// 撤回自己的加入申请
+pub async fn cancel_join_request(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(group_id): Path<String>,
+) -> impl IntoResponse {
+    let repo = GroupOperation::new(Arc::new(state.pool.clone()));
+
+    let user_id = &claims.sub;
+    tracing::debug!("用户 {} 正在撤回加入群组 {} 的申请", user_id, group_id);
+
+    match repo.cancel_join_request(&group_id, user_id).await {
+        Ok(true) => {
+            tracing::info!("用户 {} 已撤回加入群组 {} 的申请", user_id, group_id);
+            (
+                StatusCode::OK,
+                success_to_api_response(JoinGroupResponse { success: true }),
+            )
+        }
+        Ok(false) => (
+            StatusCode::OK,
+            error_to_api_response::<JoinGroupResponse>(
+                error_codes::NOT_FOUND,
+                "没有待审批的加入申请".to_string(),
+            ),
+        ),
+        Err(err) => {
+            tracing::error!(
+                "用户 {} 撤回加入群组 {} 的申请失败: {}",
+                user_id,
+                group_id,
+                err
+            );
+            (
+                StatusCode::OK,
+                error_to_api_response::<JoinGroupResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("撤回加入申请失败: {}", err),
+                ),
+            )
+        }
+    }
+}
@@ -0,0 +1,338 @@
+// 活动互动处理器
+// 处理活动的点赞、评论与删除评论
+
+use crate::AppState;
+use crate::api::models::interaction::*;
+use crate::database::models::activity::ActivityEntity;
+use crate::database::models::interaction::{
+    ActivityCommentEntity, AddCommentOutcome, DeleteCommentOutcome,
+};
+use crate::database::models::privacy::LocationPrivacy;
+use crate::database::operations::activity::ActivityOperation;
+use crate::database::operations::interaction::InteractionOperation;
+use crate::database::operations::privacy::PrivacyOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response, validate_content};
+use axum::{
+    extract::{Extension, Json, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+/// 未指定时评论列表的每页数量
+const DEFAULT_COMMENT_PAGE_SIZE: u32 = 50;
+
+/// 评论列表单页最多返回的数量
+const MAX_COMMENT_PAGE_SIZE: u32 = 200;
+
+/// 查找当前用户可见的活动
+///
+/// 可见性与活动列表一致：活动的可见范围不包含当前用户，或发布者处于隐身模式时，
+/// 其他用户看不到该活动，也不能点赞或评论。
+async fn find_visible_activity(
+    state: &AppState,
+    viewer_id: &str,
+    activity_id: &str,
+) -> Result<ActivityEntity, (i32, String)> {
+    let not_found = || (error_codes::NOT_FOUND, "活动不存在".to_string());
+    let internal = |e: sqlx::Error| {
+        tracing::error!("查询活动 {} 失败: {}", activity_id, e);
+        (error_codes::INTERNAL_ERROR, format!("查询活动失败: {}", e))
+    };
+
+    let activity = ActivityOperation::new(Arc::new(state.pool.clone()))
+        .find_visible_by_id(activity_id, viewer_id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(not_found)?;
+    if activity.user_id == viewer_id {
+        return Ok(activity);
+    }
+
+    let privacy = PrivacyOperation::new(Arc::new(state.pool.clone()))
+        .find_location_privacy(std::slice::from_ref(&activity.user_id))
+        .await
+        .map_err(internal)?
+        .remove(&activity.user_id)
+        .unwrap_or_default();
+    if privacy == LocationPrivacy::Hidden {
+        return Err(not_found());
+    }
+
+    Ok(activity)
+}
+
+/// 将评论实体转换为API响应格式，已删除的评论隐藏作者信息
+fn comment_to_response(
+    comment: ActivityCommentEntity,
+    viewer_id: &str,
+    activity_owner_id: &str,
+) -> ActivityComment {
+    let is_deleted = comment.deleted_at.is_some();
+    ActivityComment {
+        comment_id: comment.comment_id,
+        parent_id: comment.parent_id,
+        can_delete: !is_deleted && (comment.user_id == viewer_id || activity_owner_id == viewer_id),
+        user_id: (!is_deleted).then_some(comment.public_user_id),
+        nickname: (!is_deleted).then_some(comment.nickname),
+        content: comment.content,
+        is_deleted,
+        created_at: comment.created_at,
+    }
+}
+
+/// 设置或取消点赞，返回最新的点赞数
+async fn set_like(
+    state: &AppState,
+    user_id: &str,
+    activity_id: &str,
+    liked: bool,
+) -> Result<ActivityLikeResponse, (i32, String)> {
+    find_visible_activity(state, user_id, activity_id).await?;
+
+    let repo = InteractionOperation::new(Arc::new(state.pool.clone()));
+    let updated = if liked {
+        repo.like(activity_id, user_id).await
+    } else {
+        repo.unlike(activity_id, user_id).await
+    };
+    let result = match updated {
+        Ok(_) => {
+            repo.count_interactions(&[activity_id.to_string()], user_id)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(mut counts) => Ok(ActivityLikeResponse {
+            activity_id: activity_id.to_string(),
+            liked,
+            like_count: counts
+                .remove(activity_id)
+                .map(|c| c.like_count)
+                .unwrap_or_default(),
+        }),
+        Err(e) => {
+            tracing::error!(
+                "用户 {} 更新活动 {} 的点赞失败: {}",
+                user_id,
+                activity_id,
+                e
+            );
+            Err((error_codes::INTERNAL_ERROR, format!("更新点赞失败: {}", e)))
+        }
+    }
+}
+
+/// 点赞活动，已点赞时同样返回成功
+pub async fn like_activity(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(activity_id): Path<String>,
+) -> impl IntoResponse {
+    match set_like(&state, &claims.sub, &activity_id, true).await {
+        Ok(response) => (StatusCode::OK, success_to_api_response(response)),
+        Err((code, msg)) => (
+            StatusCode::OK,
+            error_to_api_response::<ActivityLikeResponse>(code, msg),
+        ),
+    }
+}
+
+/// 取消点赞，未点赞时同样返回成功
+pub async fn unlike_activity(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(activity_id): Path<String>,
+) -> impl IntoResponse {
+    match set_like(&state, &claims.sub, &activity_id, false).await {
+        Ok(response) => (StatusCode::OK, success_to_api_response(response)),
+        Err((code, msg)) => (
+            StatusCode::OK,
+            error_to_api_response::<ActivityLikeResponse>(code, msg),
+        ),
+    }
+}
+
+/// 获取活动的评论
+pub async fn get_activity_comments(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(activity_id): Path<String>,
+    Query(params): Query<ListActivityCommentsRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+
+    let activity = match find_visible_activity(&state, user_id, &activity_id).await {
+        Ok(activity) => activity,
+        Err((code, msg)) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<ListActivityCommentsResponse>(code, msg),
+            );
+        }
+    };
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_COMMENT_PAGE_SIZE)
+        .clamp(1, MAX_COMMENT_PAGE_SIZE);
+    let repo = InteractionOperation::new(Arc::new(state.pool.clone()));
+
+    match repo
+        .find_comments(&activity_id, params.after_id, limit as i64)
+        .await
+    {
+        Ok(comments) => {
+            let next_after_id = if comments.len() == limit as usize {
+                comments.last().map(|c| c.comment_id)
+            } else {
+                None
+            };
+
+            (
+                StatusCode::OK,
+                success_to_api_response(ListActivityCommentsResponse {
+                    comments: comments
+                        .into_iter()
+                        .map(|c| comment_to_response(c, user_id, &activity.user_id))
+                        .collect(),
+                    next_after_id,
+                }),
+            )
+        }
+        Err(e) => {
+            tracing::error!("获取活动 {} 的评论失败: {}", activity_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<ListActivityCommentsResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取评论失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 发表评论或回复评论
+pub async fn create_activity_comment(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(activity_id): Path<String>,
+    Json(payload): Json<CreateActivityCommentRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+
+    let content = match validate_content(&payload.content) {
+        Ok(content) => content,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<ActivityComment>(error_codes::VALIDATION_ERROR, msg),
+            );
+        }
+    };
+
+    let activity = match find_visible_activity(&state, user_id, &activity_id).await {
+        Ok(activity) => activity,
+        Err((code, msg)) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<ActivityComment>(code, msg),
+            );
+        }
+    };
+
+    let repo = InteractionOperation::new(Arc::new(state.pool.clone()));
+    match repo
+        .add_comment(&activity_id, user_id, payload.parent_id, &content)
+        .await
+    {
+        Ok(AddCommentOutcome::Added(comment)) => {
+            tracing::info!(
+                "用户 {} 评论了活动 {}，评论ID: {}",
+                user_id,
+                activity_id,
+                comment.comment_id
+            );
+            (
+                StatusCode::OK,
+                success_to_api_response(comment_to_response(comment, user_id, &activity.user_id)),
+            )
+        }
+        Ok(AddCommentOutcome::ParentNotFound) => (
+            StatusCode::OK,
+            error_to_api_response::<ActivityComment>(
+                error_codes::NOT_FOUND,
+                "回复的评论不存在".to_string(),
+            ),
+        ),
+        Err(e) => {
+            tracing::error!("用户 {} 评论活动 {} 失败: {}", user_id, activity_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<ActivityComment>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("发表评论失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 删除评论，评论作者和活动发布者可以删除
+pub async fn delete_activity_comment(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((activity_id, comment_id)): Path<(String, i64)>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+    let repo = InteractionOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.delete_comment(&activity_id, comment_id, user_id).await {
+        Ok(DeleteCommentOutcome::Deleted) => {
+            tracing::info!(
+                "用户 {} 删除了活动 {} 的评论 {}",
+                user_id,
+                activity_id,
+                comment_id
+            );
+            (
+                StatusCode::OK,
+                success_to_api_response(DeleteActivityCommentResponse { comment_id }),
+            )
+        }
+        Ok(DeleteCommentOutcome::NotFound) => (
+            StatusCode::OK,
+            error_to_api_response::<DeleteActivityCommentResponse>(
+                error_codes::NOT_FOUND,
+                "评论不存在".to_string(),
+            ),
+        ),
+        Ok(DeleteCommentOutcome::Forbidden) => (
+            StatusCode::OK,
+            error_to_api_response::<DeleteActivityCommentResponse>(
+                error_codes::PERMISSION_DENIED,
+                "只有评论作者或活动发布者可以删除评论".to_string(),
+            ),
+        ),
+        Err(e) => {
+            tracing::error!(
+                "用户 {} 删除活动 {} 的评论 {} 失败: {}",
+                user_id,
+                activity_id,
+                comment_id,
+                e
+            );
+            (
+                StatusCode::OK,
+                error_to_api_response::<DeleteActivityCommentResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("删除评论失败: {}", e),
+                ),
+            )
+        }
+    }
+}
@@ -0,0 +1,474 @@
+// 用户位置处理器
+// 处理用户实时位置上报、位置隐私设置与群组位置共享
+
+use crate::AppState;
+use crate::api::models::location::*;
+use crate::cache::operations::activity::ActivityCacheOperations;
+use crate::database::models::privacy::{LocationPrivacy, MAX_FUZZ_RADIUS, MIN_FUZZ_RADIUS};
+use crate::database::operations::group::GroupOperation;
+use crate::database::operations::location::LocationOperation;
+use crate::database::operations::privacy::PrivacyOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    extract::{Extension, Json, Path, State},
+    http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures_util::stream;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+/// 上报用户位置
+///
+/// 误差过大或距上次记录时间过短的定位会被丢弃，并在响应中说明原因。
+pub async fn update_location(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateLocationRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+
+    // 验证坐标是否合法
+    if !(-90.0..=90.0).contains(&payload.latitude) || !(-180.0..=180.0).contains(&payload.longitude)
+    {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<UpdateLocationResponse>(
+                error_codes::VALIDATION_ERROR,
+                "非法的地理坐标".to_string(),
+            ),
+        );
+    }
+
+    if let Some(accuracy) = payload.accuracy {
+        if !accuracy.is_finite() || accuracy < 0.0 {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<UpdateLocationResponse>(
+                    error_codes::VALIDATION_ERROR,
+                    "定位误差必须是非负数".to_string(),
+                ),
+            );
+        }
+        if accuracy > state.config.location_max_accuracy {
+            tracing::debug!("用户 {} 的定位误差 {}m 过大，已丢弃", user_id, accuracy);
+            return (
+                StatusCode::OK,
+                success_to_api_response(UpdateLocationResponse {
+                    accepted: false,
+                    reason: Some(LocationRejectReason::LowAccuracy),
+                    updated_at: None,
+                }),
+            );
+        }
+    }
+
+    let repo = LocationOperation::new(Arc::new(state.pool.clone()));
+    let location = match repo
+        .record_location(
+            user_id,
+            payload.latitude,
+            payload.longitude,
+            payload.accuracy,
+            state.config.location_update_interval().as_secs(),
+        )
+        .await
+    {
+        Ok(Some(location)) => location,
+        Ok(None) => {
+            return (
+                StatusCode::OK,
+                success_to_api_response(UpdateLocationResponse {
+                    accepted: false,
+                    reason: Some(LocationRejectReason::Throttled),
+                    updated_at: None,
+                }),
+            );
+        }
+        Err(e) => {
+            tracing::error!("记录用户 {} 的位置失败: {}", user_id, e);
+            return (
+                StatusCode::OK,
+                error_to_api_response::<UpdateLocationResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("记录位置失败: {}", e),
+                ),
+            );
+        }
+    };
+
+    // 同步更新GEO索引，失败不影响本次上报
+    let cache = ActivityCacheOperations::new(state.redis.clone());
+    if let Err(e) = cache
+        .update_user_location(user_id, location.latitude, location.longitude)
+        .await
+    {
+        tracing::warn!("更新用户 {} 的GEO索引失败: {}", user_id, e);
+    }
+
+    (
+        StatusCode::OK,
+        success_to_api_response(UpdateLocationResponse {
+            accepted: true,
+            reason: None,
+            updated_at: Some(location.updated_at),
+        }),
+    )
+}
+
+/// 获取当前用户的位置隐私设置
+pub async fn get_privacy_settings(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> impl IntoResponse {
+    let repo = PrivacyOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.get_settings(&claims.sub).await {
+        Ok(settings) => {
+            let (privacy, location_history) = settings
+                .map(|settings| (settings.location_privacy(), settings.location_history))
+                .unwrap_or_default();
+            (
+                StatusCode::OK,
+                success_to_api_response(to_privacy_settings(privacy, location_history)),
+            )
+        }
+        Err(e) => {
+            tracing::error!("获取用户 {} 的隐私设置失败: {}", claims.sub, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<PrivacySettings>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取隐私设置失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 更新当前用户的位置隐私设置
+pub async fn update_privacy_settings(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<PrivacySettings>,
+) -> impl IntoResponse {
+    let privacy = match (payload.location_precision, payload.fuzz_radius) {
+        (LocationPrecision::Fuzzed, Some(radius))
+            if (MIN_FUZZ_RADIUS..=MAX_FUZZ_RADIUS).contains(&radius) =>
+        {
+            LocationPrivacy::Fuzzed { radius }
+        }
+        (LocationPrecision::Fuzzed, _) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<PrivacySettings>(
+                    error_codes::VALIDATION_ERROR,
+                    format!(
+                        "模糊半径必须在{}-{}米之间",
+                        MIN_FUZZ_RADIUS, MAX_FUZZ_RADIUS
+                    ),
+                ),
+            );
+        }
+        (LocationPrecision::Exact, _) => LocationPrivacy::Exact,
+        (LocationPrecision::City, _) => LocationPrivacy::City,
+        (LocationPrecision::Hidden, _) => LocationPrivacy::Hidden,
+    };
+
+    let repo = PrivacyOperation::new(Arc::new(state.pool.clone()));
+
+    match repo
+        .update_settings(&claims.sub, privacy, payload.location_history)
+        .await
+    {
+        Ok(settings) => {
+            tracing::info!(
+                "用户 {} 将位置隐私级别设置为 {}，位置历史: {}",
+                claims.sub,
+                settings.location_precision,
+                settings.location_history
+            );
+            (
+                StatusCode::OK,
+                success_to_api_response(to_privacy_settings(
+                    settings.location_privacy(),
+                    settings.location_history,
+                )),
+            )
+        }
+        Err(e) => {
+            tracing::error!("更新用户 {} 的隐私设置失败: {}", claims.sub, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<PrivacySettings>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("更新隐私设置失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 将数据库中的隐私设置转换为API格式
+fn to_privacy_settings(privacy: LocationPrivacy, location_history: bool) -> PrivacySettings {
+    let (location_precision, fuzz_radius) = match privacy {
+        LocationPrivacy::Exact => (LocationPrecision::Exact, None),
+        LocationPrivacy::Fuzzed { radius } => (LocationPrecision::Fuzzed, Some(radius)),
+        LocationPrivacy::City => (LocationPrecision::City, None),
+        LocationPrivacy::Hidden => (LocationPrecision::Hidden, None),
+    };
+    PrivacySettings {
+        location_precision,
+        fuzz_radius,
+        location_history,
+    }
+}
+
+/// 单次共享位置的时长上限（分钟）
+const MAX_SHARING_MINUTES: u32 = 24 * 60;
+
+/// 开始向群组共享自己的实时位置，到期后自动停止
+pub async fn start_group_location_sharing(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(group_id): Path<String>,
+    Json(payload): Json<StartLocationSharingRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+
+    if payload.duration_minutes == 0 || payload.duration_minutes > MAX_SHARING_MINUTES {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<StartLocationSharingResponse>(
+                error_codes::VALIDATION_ERROR,
+                format!("共享时长必须在1-{}分钟之间", MAX_SHARING_MINUTES),
+            ),
+        );
+    }
+
+    if let Err((code, msg)) = ensure_member(&state, &group_id, user_id).await {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<StartLocationSharingResponse>(code, msg),
+        );
+    }
+
+    let repo = LocationOperation::new(Arc::new(state.pool.clone()));
+    match repo
+        .start_group_share(&group_id, user_id, payload.duration_minutes as u64 * 60)
+        .await
+    {
+        Ok(expires_at) => {
+            tracing::info!(
+                "用户 {} 开始向群组 {} 共享位置，至 {}",
+                user_id,
+                group_id,
+                expires_at
+            );
+            (
+                StatusCode::OK,
+                success_to_api_response(StartLocationSharingResponse { expires_at }),
+            )
+        }
+        Err(e) => {
+            tracing::error!("用户 {} 向群组 {} 共享位置失败: {}", user_id, group_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<StartLocationSharingResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("共享位置失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 停止向群组共享自己的实时位置
+pub async fn stop_group_location_sharing(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(group_id): Path<String>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+    let repo = LocationOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.stop_group_share(&group_id, user_id).await {
+        Ok(true) => {
+            tracing::info!("用户 {} 停止向群组 {} 共享位置", user_id, group_id);
+            (StatusCode::OK, success_to_api_response(()))
+        }
+        Ok(false) => (
+            StatusCode::OK,
+            error_to_api_response::<()>(error_codes::NOT_FOUND, "未在共享位置".to_string()),
+        ),
+        Err(e) => {
+            tracing::error!("用户 {} 停止共享位置失败: {}", user_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<()>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("停止共享位置失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 获取群组内正在共享的成员位置，仅群组成员可查看
+pub async fn get_group_shared_locations(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(group_id): Path<String>,
+) -> impl IntoResponse {
+    if let Err((code, msg)) = ensure_member(&state, &group_id, &claims.sub).await {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<GroupSharedLocationsResponse>(code, msg),
+        );
+    }
+
+    match fetch_shared_locations(&state, &group_id).await {
+        Ok(response) => (StatusCode::OK, success_to_api_response(response)),
+        Err(e) => {
+            tracing::error!("获取群组 {} 的共享位置失败: {}", group_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<GroupSharedLocationsResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取共享位置失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 以 Server-Sent Events 推送群组内共享的成员位置
+///
+/// 连接建立时推送一次完整列表，之后每个定位间隔检查一次，有变化时再推送；
+/// 查看者退出群组后结束推送。
+pub async fn stream_group_shared_locations(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(group_id): Path<String>,
+) -> Response {
+    if let Err((code, msg)) = ensure_member(&state, &group_id, &claims.sub).await {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<GroupSharedLocationsResponse>(code, msg),
+        )
+            .into_response();
+    }
+
+    let mut interval = tokio::time::interval(
+        state
+            .config
+            .location_update_interval()
+            .max(Duration::from_secs(1)),
+    );
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let events = stream::unfold(
+        (state, group_id, claims.sub, interval, None),
+        |(state, group_id, user_id, mut interval, mut last)| async move {
+            loop {
+                interval.tick().await;
+
+                match ensure_member(&state, &group_id, &user_id).await {
+                    Ok(()) => {}
+                    Err((_, msg)) => {
+                        tracing::debug!(
+                            "用户 {} 的群组 {} 位置推送结束: {}",
+                            user_id,
+                            group_id,
+                            msg
+                        );
+                        return None;
+                    }
+                }
+
+                let response = match fetch_shared_locations(&state, &group_id).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        tracing::error!("推送群组 {} 的共享位置失败: {}", group_id, e);
+                        continue;
+                    }
+                };
+                if last.as_ref() == Some(&response) {
+                    continue;
+                }
+
+                let event = Event::default()
+                    .event("locations")
+                    .json_data(&response)
+                    .unwrap_or_else(|_| Event::default().event("locations"));
+                last = Some(response);
+                return Some((
+                    Ok::<_, Infallible>(event),
+                    (state, group_id, user_id, interval, last),
+                ));
+            }
+        },
+    );
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// 检查用户是否为群组成员，失败时返回错误码和提示
+async fn ensure_member(
+    state: &AppState,
+    group_id: &str,
+    user_id: &str,
+) -> Result<(), (i32, String)> {
+    let repo = GroupOperation::new(Arc::new(state.pool.clone()));
+    match repo.has_user(group_id, user_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err((
+            error_codes::PERMISSION_DENIED,
+            "只有群组成员才能查看或共享位置".to_string(),
+        )),
+        Err(e) => {
+            tracing::error!(
+                "检查用户 {} 是否为群组 {} 成员失败: {}",
+                user_id,
+                group_id,
+                e
+            );
+            Err((
+                error_codes::INTERNAL_ERROR,
+                format!("检查群组成员失败: {}", e),
+            ))
+        }
+    }
+}
+
+/// 查询群组共享位置并转换为API格式
+async fn fetch_shared_locations(
+    state: &AppState,
+    group_id: &str,
+) -> Result<GroupSharedLocationsResponse, sqlx::Error> {
+    let repo = LocationOperation::new(Arc::new(state.pool.clone()));
+    let locations = repo.find_group_shared_locations(group_id).await?;
+
+    Ok(GroupSharedLocationsResponse {
+        locations: locations
+            .into_iter()
+            .map(|location| SharedLocation {
+                public_user_id: location.public_user_id,
+                nickname: location.nickname,
+                latitude: location.latitude,
+                longitude: location.longitude,
+                accuracy: location.accuracy,
+                updated_at: location.updated_at,
+                sharing_expires_at: location.sharing_expires_at,
+            })
+            .collect(),
+    })
+}
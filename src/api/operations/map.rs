@@ -0,0 +1,690 @@
+// 地图处理器
+// 处理地图视窗（矩形或多边形）内的群组与活动查询
+
+use crate::AppState;
+use crate::api::models::activity::ActivityDetail;
+use crate::api::models::common::{PaginatedResponse, Pagination};
+use crate::api::models::group::GroupDetail;
+use crate::api::models::map::*;
+use crate::api::operations::activity::{
+    activity_detail, fill_interactions, fill_photos, fill_place_names,
+};
+use crate::api::operations::group::to_paginated_groups;
+use crate::cache::models::map::CachedMapCluster;
+use crate::cache::operations::map::MapCacheOperations;
+use crate::database::models::map::{MAX_ZOOM, MapArea, MapLayer as DbMapLayer, tile_bounds};
+use crate::database::operations::activity::ActivityOperation;
+use crate::database::operations::group::GroupOperation;
+use crate::database::operations::map::MapOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    body::Body,
+    extract::{Extension, Json, Path, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// 单页最多返回的结果数量
+const MAX_MAP_PAGE_SIZE: u32 = 200;
+
+/// GeoJSON 多边形的最大顶点数
+const MAX_POLYGON_VERTICES: usize = 1000;
+
+/// 从该缩放级别起不再聚合，直接返回单个点
+const CLUSTER_MAX_ZOOM: u8 = 16;
+
+/// 每个瓦片在经纬方向上划分的聚合网格数量
+const CLUSTER_GRID_CELLS: u32 = 8;
+
+/// 单个瓦片最多返回的单点数量
+const MAX_TILE_POINTS: i64 = 500;
+
+/// 矢量瓦片的 Content-Type
+const MVT_CONTENT_TYPE: &str = "application/vnd.mapbox-vector-tile";
+
+/// 矢量瓦片的客户端缓存时间（秒）
+const MVT_MAX_AGE: u32 = 60;
+
+/// 解析后的地图查询条件
+struct MapQuery {
+    area: MapArea,
+    page: u32,
+    page_size: u32,
+    offset: i64,
+    limit: i64,
+    cap: u32,
+}
+
+/// 查询地图视窗内的群组
+pub async fn get_groups_in_area(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<MapQueryRequest>,
+) -> impl IntoResponse {
+    tracing::debug!("用户 {} 正在查询地图区域内的群组", claims.sub);
+
+    let query = match parse_map_query(&payload) {
+        Ok(query) => query,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<PaginatedResponse<GroupDetail>>(
+                    error_codes::VALIDATION_ERROR,
+                    msg,
+                ),
+            );
+        }
+    };
+
+    let repo = GroupOperation::new(Arc::new(state.pool.clone()));
+
+    match repo
+        .find_in_area_with_details(&query.area, query.offset, query.limit)
+        .await
+    {
+        Ok((groups, total)) => {
+            let mut result = to_paginated_groups(groups, total, query.page, query.page_size);
+            result.pagination.total = result.pagination.total.min(query.cap as u64);
+            (StatusCode::OK, success_to_api_response(result))
+        }
+        Err(err) => (
+            StatusCode::OK,
+            error_to_api_response::<PaginatedResponse<GroupDetail>>(
+                error_codes::INTERNAL_ERROR,
+                format!("查询群组失败: {}", err),
+            ),
+        ),
+    }
+}
+
+/// 查询地图视窗内的活动
+pub async fn get_activities_in_area(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<MapQueryRequest>,
+) -> impl IntoResponse {
+    tracing::debug!("用户 {} 正在查询地图区域内的活动", claims.sub);
+
+    let query = match parse_map_query(&payload) {
+        Ok(query) => query,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<PaginatedResponse<ActivityDetail>>(
+                    error_codes::VALIDATION_ERROR,
+                    msg,
+                ),
+            );
+        }
+    };
+
+    let repo = ActivityOperation::new(Arc::new(state.pool.clone()));
+
+    let viewer = (
+        claims.sub.as_str(),
+        state.config.location_fuzz_secret.as_str(),
+    );
+
+    match repo
+        .find_activities_in_area(viewer, &query.area, query.offset, query.limit)
+        .await
+    {
+        Ok((activities, total)) => {
+            let mut items: Vec<ActivityDetail> = activities
+                .into_iter()
+                .map(|activity| activity_detail(activity, None))
+                .collect();
+            fill_place_names(&state, &mut items).await;
+            fill_interactions(&state, &claims.sub, &mut items).await;
+            fill_photos(&state, &mut items).await;
+
+            (
+                StatusCode::OK,
+                success_to_api_response(PaginatedResponse {
+                    items,
+                    pagination: Pagination {
+                        page: query.page,
+                        page_size: query.page_size,
+                        total: (total as u64).min(query.cap as u64),
+                    },
+                }),
+            )
+        }
+        Err(err) => {
+            tracing::error!("查询地图区域内的活动失败: {}", err);
+            (
+                StatusCode::OK,
+                error_to_api_response::<PaginatedResponse<ActivityDetail>>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("查询活动失败: {}", err),
+                ),
+            )
+        }
+    }
+}
+
+/// 获取地图瓦片内的聚合点
+///
+/// 瓦片使用 Web 墨卡托 XYZ 编号。缩放级别低于 [`CLUSTER_MAX_ZOOM`] 时按网格聚合，
+/// 否则返回瓦片内的单个点。群组图层的结果按瓦片缓存在 Redis 中；
+/// 活动图层只包含请求者能看到的活动，位置按发布者的位置隐私设置处理，
+/// 结果因人而异，因此不使用共享缓存。
+pub async fn get_map_clusters(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((layer, z, x, y)): Path<(MapLayer, u8, u32, u32)>,
+) -> impl IntoResponse {
+    tracing::debug!(
+        "用户 {} 正在获取瓦片 {}/{}/{}/{} 的聚合点",
+        claims.sub,
+        layer.as_str(),
+        z,
+        x,
+        y
+    );
+
+    let Some(bounds) = tile_bounds(z, x, y) else {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<MapClustersResponse>(
+                error_codes::VALIDATION_ERROR,
+                "无效的瓦片坐标".to_string(),
+            ),
+        );
+    };
+
+    // 优先读取缓存，缓存失败不影响查询
+    let cache = MapCacheOperations::new(state.redis.clone());
+    let cacheable = layer == MapLayer::Groups;
+    if cacheable {
+        match cache.get_cached_tile(layer.as_str(), (z, x, y)).await {
+            Ok(Some(clusters)) => {
+                return (
+                    StatusCode::OK,
+                    success_to_api_response(MapClustersResponse {
+                        clusters: clusters.into_iter().map(MapCluster::from).collect(),
+                    }),
+                );
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("读取地图瓦片缓存失败: {}", e),
+        }
+    }
+
+    let db_layer = match layer {
+        MapLayer::Groups => DbMapLayer::Groups,
+        MapLayer::Activities => DbMapLayer::Activities,
+    };
+    let repo = MapOperation::new(Arc::new(state.pool.clone()));
+    let viewer = (
+        claims.sub.as_str(),
+        state.config.location_fuzz_secret.as_str(),
+    );
+    let result = if z >= CLUSTER_MAX_ZOOM {
+        repo.find_points(db_layer, viewer, bounds, MAX_TILE_POINTS)
+            .await
+    } else {
+        repo.find_clusters(db_layer, viewer, bounds, CLUSTER_GRID_CELLS)
+            .await
+    };
+
+    match result {
+        Ok(rows) => {
+            let clusters: Vec<CachedMapCluster> = rows
+                .into_iter()
+                .map(|row| CachedMapCluster {
+                    latitude: row.latitude,
+                    longitude: row.longitude,
+                    count: row.count,
+                    id: row.id,
+                    label: row.label,
+                })
+                .collect();
+
+            if cacheable
+                && let Err(e) = cache
+                    .cache_tile(
+                        layer.as_str(),
+                        (z, x, y),
+                        &clusters,
+                        state.config.map_tile_cache_ttl().as_secs(),
+                    )
+                    .await
+            {
+                tracing::warn!("缓存地图瓦片失败: {}", e);
+            }
+
+            (
+                StatusCode::OK,
+                success_to_api_response(MapClustersResponse {
+                    clusters: clusters.into_iter().map(MapCluster::from).collect(),
+                }),
+            )
+        }
+        Err(err) => {
+            tracing::error!("查询地图瓦片 {}/{}/{} 失败: {}", z, x, y, err);
+            (
+                StatusCode::OK,
+                error_to_api_response::<MapClustersResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("查询地图聚合失败: {}", err),
+                ),
+            )
+        }
+    }
+}
+
+/// 获取 Mapbox 矢量瓦片
+///
+/// 路径最后一段为 `{y}.mvt`。响应带有根据瓦片内容计算的 ETag，
+/// 请求的 `If-None-Match` 与之相同时返回 304。活动图层按请求者生成，
+/// 只包含请求者能看到的活动，位置按发布者的位置隐私设置处理，
+/// 因此只允许客户端私有缓存。
+pub async fn get_vector_tile(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((layer, z, x, tile)): Path<(MapLayer, u8, u32, String)>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(y) = parse_tile_y(z, x, &tile) else {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<()>(
+                error_codes::VALIDATION_ERROR,
+                "无效的瓦片坐标".to_string(),
+            ),
+        )
+            .into_response();
+    };
+
+    tracing::debug!(
+        "用户 {} 正在获取矢量瓦片 {}/{}/{}/{}",
+        claims.sub,
+        layer.as_str(),
+        z,
+        x,
+        y
+    );
+
+    let db_layer = match layer {
+        MapLayer::Groups => DbMapLayer::Groups,
+        MapLayer::Activities => DbMapLayer::Activities,
+    };
+    let repo = MapOperation::new(Arc::new(state.pool.clone()));
+
+    let viewer = (
+        claims.sub.as_str(),
+        state.config.location_fuzz_secret.as_str(),
+    );
+    let tile = match repo.render_tile(db_layer, viewer, (z, x, y)).await {
+        Ok(tile) => tile,
+        Err(err) => {
+            tracing::error!("生成矢量瓦片 {}/{}/{} 失败: {}", z, x, y, err);
+            return (
+                StatusCode::OK,
+                error_to_api_response::<()>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("生成矢量瓦片失败: {}", err),
+                ),
+            )
+                .into_response();
+        }
+    };
+
+    let etag = format!("\"{:x}\"", Sha256::digest(&tile));
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+
+    let builder = Response::builder()
+        .header(header::ETAG, &etag)
+        .header(
+            header::CACHE_CONTROL,
+            format!("private, max-age={}", MVT_MAX_AGE),
+        )
+        .header(header::VARY, header::AUTHORIZATION.as_str());
+
+    let response = if not_modified {
+        builder.status(StatusCode::NOT_MODIFIED).body(Body::empty())
+    } else {
+        builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, MVT_CONTENT_TYPE)
+            .body(Body::from(tile))
+    };
+
+    response.unwrap_or_else(|e| {
+        tracing::error!("构建矢量瓦片响应失败: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    })
+}
+
+impl From<CachedMapCluster> for MapCluster {
+    fn from(cluster: CachedMapCluster) -> Self {
+        MapCluster {
+            latitude: cluster.latitude,
+            longitude: cluster.longitude,
+            count: cluster.count,
+            id: cluster.id,
+            label: cluster.label,
+        }
+    }
+}
+
+/// 解析瓦片路径最后一段 `{y}.mvt`，坐标超出该缩放级别的范围时返回 None
+fn parse_tile_y(z: u8, x: u32, tile: &str) -> Option<u32> {
+    tile.strip_suffix(".mvt")
+        .and_then(|y| y.parse::<u32>().ok())
+        .filter(|&y| tile_bounds(z, x, y).is_some())
+}
+
+/// 校验请求并转换为查询条件
+fn parse_map_query(payload: &MapQueryRequest) -> Result<MapQuery, String> {
+    if payload.zoom > MAX_ZOOM {
+        return Err(format!("缩放级别必须在0-{}之间", MAX_ZOOM));
+    }
+
+    let area = match (&payload.bbox, &payload.polygon) {
+        (Some(bbox), None) => bbox_to_area(bbox)?,
+        (None, Some(polygon)) => MapArea::Polygon(validate_geojson_polygon(polygon)?),
+        _ => return Err("必须提供矩形或多边形其中一种查询区域".to_string()),
+    };
+
+    // 结果上限随缩放级别变化，超出上限的页不再返回
+    let cap = result_cap(payload.zoom);
+    let page = payload.page.max(1);
+    let page_size = payload.page_size.clamp(1, MAX_MAP_PAGE_SIZE);
+    let offset = (page as i64 - 1) * page_size as i64;
+    if offset >= cap as i64 {
+        return Err(format!("当前缩放级别最多返回{}条结果", cap));
+    }
+    let limit = (page_size as i64).min(cap as i64 - offset);
+
+    Ok(MapQuery {
+        area,
+        page,
+        page_size,
+        offset,
+        limit,
+        cap,
+    })
+}
+
+/// 缩放级别对应的最大结果数量，缩放级别越小（视野越大）上限越低
+fn result_cap(zoom: u8) -> u32 {
+    match zoom {
+        0..=5 => 100,
+        6..=10 => 300,
+        11..=14 => 1000,
+        _ => 2000,
+    }
+}
+
+fn is_valid_coordinate(longitude: f64, latitude: f64) -> bool {
+    (-180.0..=180.0).contains(&longitude) && (-90.0..=90.0).contains(&latitude)
+}
+
+/// 校验经纬度矩形
+fn bbox_to_area(bbox: &BoundingBox) -> Result<MapArea, String> {
+    if !is_valid_coordinate(bbox.min_longitude, bbox.min_latitude)
+        || !is_valid_coordinate(bbox.max_longitude, bbox.max_latitude)
+    {
+        return Err("非法的地理坐标".to_string());
+    }
+    if bbox.min_latitude > bbox.max_latitude {
+        return Err("南边界纬度不能大于北边界纬度".to_string());
+    }
+
+    Ok(MapArea::BoundingBox {
+        min_longitude: bbox.min_longitude,
+        min_latitude: bbox.min_latitude,
+        max_longitude: bbox.max_longitude,
+        max_latitude: bbox.max_latitude,
+    })
+}
+
+/// 校验 GeoJSON 多边形，返回序列化后的 GeoJSON 字符串
+fn validate_geojson_polygon(value: &Value) -> Result<String, String> {
+    let invalid = || "无效的GeoJSON多边形".to_string();
+
+    let coordinates = value.get("coordinates").ok_or_else(invalid)?;
+    let polygons: Vec<&Value> = match value.get("type").and_then(Value::as_str) {
+        Some("Polygon") => vec![coordinates],
+        Some("MultiPolygon") => coordinates.as_array().ok_or_else(invalid)?.iter().collect(),
+        _ => return Err("仅支持Polygon或MultiPolygon类型的GeoJSON".to_string()),
+    };
+
+    let mut vertices = 0;
+    for polygon in polygons {
+        let rings = polygon
+            .as_array()
+            .filter(|r| !r.is_empty())
+            .ok_or_else(invalid)?;
+        for ring in rings {
+            let positions = ring.as_array().ok_or_else(invalid)?;
+            // 闭合的线环至少需要4个点，且首尾相同
+            if positions.len() < 4 || positions.first() != positions.last() {
+                return Err(invalid());
+            }
+            for position in positions {
+                let (longitude, latitude) = match position.as_array().map(Vec::as_slice) {
+                    Some([lon, lat, ..]) => (
+                        lon.as_f64().ok_or_else(invalid)?,
+                        lat.as_f64().ok_or_else(invalid)?,
+                    ),
+                    _ => return Err(invalid()),
+                };
+                if !is_valid_coordinate(longitude, latitude) {
+                    return Err("非法的地理坐标".to_string());
+                }
+            }
+            vertices += positions.len();
+        }
+    }
+
+    if vertices > MAX_POLYGON_VERTICES {
+        return Err(format!("多边形顶点数不能超过{}", MAX_POLYGON_VERTICES));
+    }
+
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bbox(west: f64, south: f64, east: f64, north: f64) -> BoundingBox {
+        BoundingBox {
+            min_longitude: west,
+            min_latitude: south,
+            max_longitude: east,
+            max_latitude: north,
+        }
+    }
+
+    fn request(zoom: u8, page: u32, page_size: u32) -> MapQueryRequest {
+        MapQueryRequest {
+            bbox: Some(bbox(0.0, 0.0, 1.0, 1.0)),
+            polygon: None,
+            zoom,
+            page,
+            page_size,
+        }
+    }
+
+    fn square() -> Value {
+        json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]]
+        })
+    }
+
+    #[test]
+    fn result_cap_steps_with_zoom() {
+        assert_eq!(result_cap(0), 100);
+        assert_eq!(result_cap(5), 100);
+        assert_eq!(result_cap(6), 300);
+        assert_eq!(result_cap(10), 300);
+        assert_eq!(result_cap(11), 1000);
+        assert_eq!(result_cap(14), 1000);
+        assert_eq!(result_cap(15), 2000);
+        assert_eq!(result_cap(MAX_ZOOM), 2000);
+    }
+
+    #[test]
+    fn parse_map_query_requires_exactly_one_area() {
+        let mut payload = request(10, 1, 20);
+        payload.bbox = None;
+        assert!(parse_map_query(&payload).is_err());
+
+        payload.bbox = Some(bbox(0.0, 0.0, 1.0, 1.0));
+        payload.polygon = Some(square());
+        assert!(parse_map_query(&payload).is_err());
+
+        payload.bbox = None;
+        assert!(matches!(
+            parse_map_query(&payload).map(|query| query.area),
+            Ok(MapArea::Polygon(_))
+        ));
+    }
+
+    #[test]
+    fn parse_map_query_rejects_zoom_above_max() {
+        assert!(parse_map_query(&request(MAX_ZOOM, 1, 20)).is_ok());
+        assert!(parse_map_query(&request(MAX_ZOOM + 1, 1, 20)).is_err());
+    }
+
+    #[test]
+    fn parse_map_query_normalizes_paging() {
+        let query = parse_map_query(&request(10, 0, 0)).unwrap();
+        assert_eq!((query.page, query.page_size), (1, 1));
+        assert_eq!((query.offset, query.limit), (0, 1));
+
+        let query = parse_map_query(&request(15, 2, 1000)).unwrap();
+        assert_eq!(query.page_size, MAX_MAP_PAGE_SIZE);
+        assert_eq!((query.offset, query.limit), (200, 200));
+        assert_eq!(query.cap, 2000);
+    }
+
+    #[test]
+    fn parse_map_query_truncates_the_last_page_at_the_cap() {
+        // 缩放级别0最多100条：第4页只剩10条，第5页超出上限
+        let query = parse_map_query(&request(0, 4, 30)).unwrap();
+        assert_eq!((query.offset, query.limit, query.cap), (90, 10, 100));
+
+        assert!(parse_map_query(&request(0, 5, 30)).is_err());
+        assert!(parse_map_query(&request(0, 2, 100)).is_err());
+    }
+
+    #[test]
+    fn bbox_to_area_validates_bounds() {
+        assert!(bbox_to_area(&bbox(-181.0, 0.0, 1.0, 1.0)).is_err());
+        assert!(bbox_to_area(&bbox(0.0, 0.0, 1.0, 91.0)).is_err());
+        assert!(bbox_to_area(&bbox(0.0, 10.0, 1.0, -10.0)).is_err());
+        assert!(bbox_to_area(&bbox(0.0, 0.0, 1.0, 1.0)).is_ok());
+    }
+
+    #[test]
+    fn bbox_to_area_accepts_an_antimeridian_box() {
+        let area = bbox_to_area(&bbox(170.0, -5.0, -170.0, 5.0)).unwrap();
+        assert_eq!(area.envelopes().len(), 2);
+    }
+
+    #[test]
+    fn parse_tile_y_reads_the_mvt_segment() {
+        assert_eq!(parse_tile_y(3, 2, "5.mvt"), Some(5));
+        assert_eq!(parse_tile_y(0, 0, "0.mvt"), Some(0));
+        assert_eq!(parse_tile_y(MAX_ZOOM, 0, "4194303.mvt"), Some(4194303));
+    }
+
+    #[test]
+    fn parse_tile_y_rejects_bad_segments() {
+        assert_eq!(parse_tile_y(3, 2, "5"), None);
+        assert_eq!(parse_tile_y(3, 2, "5.png"), None);
+        assert_eq!(parse_tile_y(3, 2, "five.mvt"), None);
+        assert_eq!(parse_tile_y(3, 2, "-1.mvt"), None);
+        assert_eq!(parse_tile_y(3, 2, ".mvt"), None);
+    }
+
+    #[test]
+    fn parse_tile_y_rejects_out_of_range_tiles() {
+        assert_eq!(parse_tile_y(3, 2, "8.mvt"), None);
+        assert_eq!(parse_tile_y(3, 8, "5.mvt"), None);
+        assert_eq!(parse_tile_y(MAX_ZOOM + 1, 0, "0.mvt"), None);
+    }
+
+    #[test]
+    fn validate_geojson_polygon_accepts_polygons() {
+        assert_eq!(
+            validate_geojson_polygon(&square()).unwrap(),
+            square().to_string()
+        );
+
+        let multi = json!({
+            "type": "MultiPolygon",
+            "coordinates": [square()["coordinates"], square()["coordinates"]]
+        });
+        assert!(validate_geojson_polygon(&multi).is_ok());
+    }
+
+    #[test]
+    fn validate_geojson_polygon_rejects_malformed_rings() {
+        let unclosed = json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]]
+        });
+        assert!(validate_geojson_polygon(&unclosed).is_err());
+
+        let too_short = json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]
+        });
+        assert!(validate_geojson_polygon(&too_short).is_err());
+
+        let non_numeric = json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], ["1", 0.0], [1.0, 1.0], [0.0, 0.0]]]
+        });
+        assert!(validate_geojson_polygon(&non_numeric).is_err());
+
+        let no_rings = json!({ "type": "Polygon", "coordinates": [] });
+        assert!(validate_geojson_polygon(&no_rings).is_err());
+
+        let missing = json!({ "type": "Polygon" });
+        assert!(validate_geojson_polygon(&missing).is_err());
+    }
+
+    #[test]
+    fn validate_geojson_polygon_rejects_other_geometries() {
+        let point = json!({ "type": "Point", "coordinates": [0.0, 0.0] });
+        assert!(validate_geojson_polygon(&point).is_err());
+    }
+
+    #[test]
+    fn validate_geojson_polygon_rejects_out_of_range_coordinates() {
+        let polygon = json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [181.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]
+        });
+        assert!(validate_geojson_polygon(&polygon).is_err());
+    }
+
+    #[test]
+    fn validate_geojson_polygon_limits_vertices() {
+        let ring = |count: usize| {
+            let mut positions: Vec<Value> = (0..count - 1)
+                .map(|i| json!([i as f64 * 0.001, (i % 2) as f64 * 0.001]))
+                .collect();
+            positions.push(positions[0].clone());
+            json!({ "type": "Polygon", "coordinates": [positions] })
+        };
+        assert!(validate_geojson_polygon(&ring(MAX_POLYGON_VERTICES)).is_ok());
+        assert!(validate_geojson_polygon(&ring(MAX_POLYGON_VERTICES + 1)).is_err());
+    }
+}
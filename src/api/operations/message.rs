@@ -3,9 +3,12 @@
 
 use crate::AppState;
 use crate::api::models::message::*;
+use crate::api::operations::poll::{fill_polls, validate_poll_request};
+use crate::database::models::message::MessageType as DbMessageType;
+use crate::database::operations::group::GroupOperation;
 use crate::database::operations::message::MessageOperation;
 use crate::utils::Claims;
-use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response, validate_content};
 use axum::{
     extract::{Extension, Json, Path, Query, State},
     http::StatusCode,
@@ -27,15 +30,104 @@ pub async fn send_message(
         payload.group_id
     );
 
+    let content = match validate_content(&payload.content) {
+        Ok(content) => content,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<SendMessageResponse>(error_codes::VALIDATION_ERROR, msg),
+            );
+        }
+    };
+
+    // 投票消息需要附带投票设置，其他消息不能附带
+    let poll = match (&payload.message_type, &payload.poll) {
+        (MessageType::Poll, Some(poll)) => match validate_poll_request(poll) {
+            Ok(poll) => Some(poll),
+            Err(msg) => {
+                return (
+                    StatusCode::OK,
+                    error_to_api_response::<SendMessageResponse>(
+                        error_codes::VALIDATION_ERROR,
+                        msg,
+                    ),
+                );
+            }
+        },
+        (MessageType::Poll, None) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<SendMessageResponse>(
+                    error_codes::VALIDATION_ERROR,
+                    "投票消息需要提供投票设置".to_string(),
+                ),
+            );
+        }
+        (_, Some(_)) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<SendMessageResponse>(
+                    error_codes::VALIDATION_ERROR,
+                    "只有投票消息可以附带投票设置".to_string(),
+                ),
+            );
+        }
+        (_, None) => None,
+    };
+
     // 创建消息仓库实例
     let db_operation = MessageOperation::new(Arc::new(state.pool.clone()));
 
     // 从认证信息中获取用户ID
     let user_id = &claims.sub;
 
+    // 设置了地理围栏的群组需要在围栏内（或离开后的宽限期内）才能发言
+    let group_operation = GroupOperation::new(Arc::new(state.pool.clone()));
+    match group_operation
+        .check_presence(
+            &payload.group_id,
+            user_id,
+            payload.latitude.zip(payload.longitude),
+            state.config.group_geofence_grace().as_secs() as i64,
+        )
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            tracing::warn!(
+                "用户(登录ID) {} 向群组 {} 发送消息失败: 不在地理围栏内",
+                user_id,
+                payload.group_id
+            );
+            return (
+                StatusCode::OK,
+                error_to_api_response::<SendMessageResponse>(
+                    error_codes::OUTSIDE_GEOFENCE,
+                    "需要在群组所在区域内才能发言".to_string(),
+                ),
+            );
+        }
+        Err(e) => {
+            tracing::error!("检查群组 {} 地理围栏失败: {}", payload.group_id, e);
+            return (
+                StatusCode::OK,
+                error_to_api_response::<SendMessageResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("检查地理围栏失败: {}", e),
+                ),
+            );
+        }
+    }
+
     // 发送消息
     match db_operation
-        .save_message(&payload.group_id, user_id, &payload.content)
+        .save_message(
+            &payload.group_id,
+            user_id,
+            &content,
+            poll.as_ref(),
+            state.config.message_activity_interval().as_secs() as i64,
+        )
         .await
     {
         Ok(message_id) => {
@@ -160,20 +252,26 @@ pub async fn get_message_history(
             );
 
             // 转换为API响应格式
-            let message_details: Vec<MessageDetail> = messages
+            let mut message_details: Vec<MessageDetail> = messages
                 .into_iter()
-                .map(|msg| {
-                    MessageDetail {
-                        id: msg.message_id,
-                        group_id: msg.group_id,
-                        sender_id: msg.user_id,
-                        sender_name: msg.nickname,
-                        message_type: MessageType::Text, // 暂时只支持文本消息
-                        content: msg.content,
-                        sent_at: msg.created_at,
-                    }
+                .map(|msg| MessageDetail {
+                    message_type: match msg.get_message_type() {
+                        DbMessageType::Text => MessageType::Text,
+                        DbMessageType::Image => MessageType::Image,
+                        DbMessageType::Voice => MessageType::Voice,
+                        DbMessageType::Poll => MessageType::Poll,
+                        DbMessageType::System => MessageType::System,
+                    },
+                    id: msg.message_id,
+                    group_id: msg.group_id,
+                    sender_id: msg.user_id,
+                    sender_name: msg.nickname,
+                    content: msg.content,
+                    poll: None,
+                    sent_at: msg.created_at,
                 })
                 .collect();
+            fill_polls(&state, user_id, &mut message_details).await;
 
             // 获取下一页游标
             let next_cursor = if !message_details.is_empty() {
@@ -2,14 +2,40 @@
 // 包含所有 API 请求处理逻辑
 
 pub mod activity;
+pub mod admin;
+pub mod event;
+pub mod feed;
+pub mod follow;
+pub mod geocode;
 pub mod group;
+pub mod interaction;
+pub mod location;
+pub mod map;
 pub mod message;
+pub mod notification;
+pub mod photo;
+pub mod place;
+pub mod poll;
 pub mod test;
+pub mod trip;
 pub mod user;
 
 // 重新导出常用处理器
 pub use activity::*;
+pub use admin::*;
+pub use event::*;
+pub use feed::*;
+pub use follow::*;
+pub use geocode::*;
 pub use group::*;
+pub use interaction::*;
+pub use location::*;
+pub use map::*;
 pub use message::*;
+pub use notification::*;
+pub use photo::*;
+pub use place::*;
+pub use poll::*;
 pub use test::*;
+pub use trip::*;
 pub use user::*;
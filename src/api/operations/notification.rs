@@ -0,0 +1,132 @@
+// 通知处理器
+// 处理通知收件箱的查询与已读标记
+
+use crate::AppState;
+use crate::api::models::notification::*;
+use crate::database::operations::notification::NotificationOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+/// 单页最多返回的通知数量
+const MAX_NOTIFICATION_PAGE_SIZE: u32 = 100;
+
+/// 获取当前用户的通知
+pub async fn get_notifications(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<ListNotificationsRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+    let limit = params
+        .limit
+        .unwrap_or(20)
+        .clamp(1, MAX_NOTIFICATION_PAGE_SIZE);
+    let repo = NotificationOperation::new(Arc::new(state.pool.clone()));
+
+    let result = match repo
+        .find_by_user(user_id, params.before_id, limit as i64)
+        .await
+    {
+        Ok(notifications) => repo
+            .count_unread(user_id)
+            .await
+            .map(|unread_count| (notifications, unread_count)),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((notifications, unread_count)) => {
+            let next_before_id = if notifications.len() == limit as usize {
+                notifications.last().map(|n| n.notification_id)
+            } else {
+                None
+            };
+
+            (
+                StatusCode::OK,
+                success_to_api_response(ListNotificationsResponse {
+                    notifications: notifications
+                        .into_iter()
+                        .map(|n| Notification {
+                            notification_id: n.notification_id,
+                            kind: n.kind,
+                            payload: n.payload,
+                            created_at: n.created_at,
+                            is_read: n.read_at.is_some(),
+                        })
+                        .collect(),
+                    unread_count,
+                    next_before_id,
+                }),
+            )
+        }
+        Err(e) => {
+            tracing::error!("获取用户 {} 的通知失败: {}", user_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<ListNotificationsResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取通知失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 将一条通知标记为已读
+pub async fn mark_notification_read(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(notification_id): Path<i64>,
+) -> impl IntoResponse {
+    let repo = NotificationOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.mark_read(&claims.sub, notification_id).await {
+        Ok(true) => (StatusCode::OK, success_to_api_response(())),
+        Ok(false) => (
+            StatusCode::OK,
+            error_to_api_response::<()>(error_codes::NOT_FOUND, "通知不存在".to_string()),
+        ),
+        Err(e) => {
+            tracing::error!("标记通知 {} 已读失败: {}", notification_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<()>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("标记通知已读失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 将当前用户的所有通知标记为已读
+pub async fn mark_all_notifications_read(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> impl IntoResponse {
+    let repo = NotificationOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.mark_all_read(&claims.sub).await {
+        Ok(updated) => (
+            StatusCode::OK,
+            success_to_api_response(MarkAllNotificationsReadResponse { updated }),
+        ),
+        Err(e) => {
+            tracing::error!("标记用户 {} 的通知已读失败: {}", claims.sub, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<MarkAllNotificationsReadResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("标记通知已读失败: {}", e),
+                ),
+            )
+        }
+    }
+}
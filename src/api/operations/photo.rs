@@ -0,0 +1,399 @@
+// 活动照片处理器
+// 处理签到照片的上传、删除以及本地存储后端的文件下载
+
+use crate::AppState;
+use crate::api::models::activity::ActivityType;
+use crate::api::models::photo::*;
+use crate::database::models::photo::{ActivityPhotoEntity, AddPhotoOutcome, NewActivityPhoto};
+use crate::database::operations::activity::ActivityOperation;
+use crate::database::operations::photo::PhotoOperation;
+use crate::media::storage::content_type_for;
+use crate::media::{ProcessedPhoto, process_photo};
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    body::Body,
+    extract::{Extension, Multipart, Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 将照片实体转换为API响应格式，照片和缩略图地址在有效期后失效
+pub(crate) async fn photo_to_response(
+    state: &AppState,
+    photo: ActivityPhotoEntity,
+) -> Result<ActivityPhoto, object_store::Error> {
+    Ok(ActivityPhoto {
+        url: state.media.url(&photo.storage_key).await?,
+        thumbnail_url: state.media.url(&photo.thumbnail_key).await?,
+        photo_id: photo.photo_id,
+        width: photo.width,
+        height: photo.height,
+    })
+}
+
+/// 读取上传表单，返回 (照片内容, 是否保留位置信息)
+///
+/// 表单字段 `photo` 为图片文件，`keep_location` 为 `true` 时保留 EXIF 中的 GPS 信息。
+async fn read_upload_form(
+    state: &AppState,
+    mut multipart: Multipart,
+) -> Result<(Bytes, bool), String> {
+    let mut photo = None;
+    let mut keep_location = false;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| format!("读取上传内容失败: {}", e))?
+    {
+        match field.name() {
+            Some("photo") => {
+                if photo.is_some() {
+                    return Err("每次只能上传一张照片".to_string());
+                }
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| format!("读取上传内容失败: {}", e))?;
+                photo = Some(data);
+            }
+            Some("keep_location") => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| format!("读取上传内容失败: {}", e))?;
+                keep_location = value.trim() == "true";
+            }
+            _ => {}
+        }
+    }
+
+    let photo = photo.ok_or("缺少照片文件")?;
+    if photo.is_empty() {
+        return Err("照片文件为空".to_string());
+    }
+    if photo.len() > state.config.media_max_upload_size {
+        return Err(format!(
+            "照片不能超过{}字节",
+            state.config.media_max_upload_size
+        ));
+    }
+
+    Ok((photo, keep_location))
+}
+
+/// 删除已保存的文件，失败时仅记录日志
+async fn remove_files(state: &AppState, keys: &[&str]) {
+    for key in keys {
+        if let Err(e) = state.media.delete(key).await {
+            tracing::warn!("删除媒体文件 {} 失败: {}", key, e);
+        }
+    }
+}
+
+/// 为签到上传照片
+///
+/// 只有签到的发布者可以上传，每个签到最多附带 `MAX_ACTIVITY_PHOTOS` 张。
+/// 上传使用 multipart 表单，默认去除照片中的 GPS 信息，`keep_location` 为 `true` 时保留。
+pub async fn upload_activity_photo(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(activity_id): Path<String>,
+    multipart: Multipart,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+
+    let activity = match ActivityOperation::new(Arc::new(state.pool.clone()))
+        .find_visible_by_id(&activity_id, user_id)
+        .await
+    {
+        Ok(Some(activity)) if activity.user_id == *user_id => activity,
+        Ok(Some(_)) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<ActivityPhoto>(
+                    error_codes::PERMISSION_DENIED,
+                    "只有签到的发布者可以上传照片".to_string(),
+                ),
+            );
+        }
+        Ok(None) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<ActivityPhoto>(
+                    error_codes::NOT_FOUND,
+                    "活动不存在".to_string(),
+                ),
+            );
+        }
+        Err(e) => {
+            tracing::error!("查询活动 {} 失败: {}", activity_id, e);
+            return (
+                StatusCode::OK,
+                error_to_api_response::<ActivityPhoto>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("查询活动失败: {}", e),
+                ),
+            );
+        }
+    };
+    if activity.activity_type != ActivityType::UserCheckedIn {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<ActivityPhoto>(
+                error_codes::VALIDATION_ERROR,
+                "只有签到可以附带照片".to_string(),
+            ),
+        );
+    }
+
+    // 处理图片之前先检查数量，写入时会在事务中再次检查
+    let repo = PhotoOperation::new(Arc::new(state.pool.clone()));
+    let max_photos = state.config.max_activity_photos as i64;
+    match repo.count_by_activity(&activity_id).await {
+        Ok(count) if count >= max_photos => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<ActivityPhoto>(
+                    error_codes::VALIDATION_ERROR,
+                    format!("每个签到最多附带{}张照片", max_photos),
+                ),
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("统计活动 {} 的照片失败: {}", activity_id, e);
+            return (
+                StatusCode::OK,
+                error_to_api_response::<ActivityPhoto>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("统计照片数量失败: {}", e),
+                ),
+            );
+        }
+    }
+
+    let (data, keep_location) = match read_upload_form(&state, multipart).await {
+        Ok(form) => form,
+        Err(msg) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<ActivityPhoto>(error_codes::VALIDATION_ERROR, msg),
+            );
+        }
+    };
+
+    // 解码和生成缩略图较耗时，放到阻塞线程中执行
+    let processed: ProcessedPhoto =
+        match tokio::task::spawn_blocking(move || process_photo(data, keep_location)).await {
+            Ok(Ok(processed)) => processed,
+            Ok(Err(msg)) => {
+                return (
+                    StatusCode::OK,
+                    error_to_api_response::<ActivityPhoto>(error_codes::VALIDATION_ERROR, msg),
+                );
+            }
+            Err(e) => {
+                tracing::error!("处理活动 {} 的照片失败: {}", activity_id, e);
+                return (
+                    StatusCode::OK,
+                    error_to_api_response::<ActivityPhoto>(
+                        error_codes::INTERNAL_ERROR,
+                        format!("处理照片失败: {}", e),
+                    ),
+                );
+            }
+        };
+
+    let photo_id = Uuid::new_v4().to_string();
+    let storage_key = format!(
+        "activities/{}/{}.{}",
+        activity_id, photo_id, processed.extension
+    );
+    let thumbnail_key = format!("activities/{}/{}_thumb.jpg", activity_id, photo_id);
+    let size_bytes = processed.original.len() as i64;
+
+    let stored = match state
+        .media
+        .put(&storage_key, processed.original, processed.content_type)
+        .await
+    {
+        Ok(()) => {
+            state
+                .media
+                .put(&thumbnail_key, processed.thumbnail, "image/jpeg")
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    if let Err(e) = stored {
+        tracing::error!("保存活动 {} 的照片失败: {}", activity_id, e);
+        remove_files(&state, &[&storage_key, &thumbnail_key]).await;
+        return (
+            StatusCode::OK,
+            error_to_api_response::<ActivityPhoto>(
+                error_codes::INTERNAL_ERROR,
+                format!("保存照片失败: {}", e),
+            ),
+        );
+    }
+
+    let new_photo = NewActivityPhoto {
+        photo_id: &photo_id,
+        activity_id: &activity_id,
+        user_id,
+        storage_key: &storage_key,
+        thumbnail_key: &thumbnail_key,
+        content_type: processed.content_type,
+        width: processed.width as i32,
+        height: processed.height as i32,
+        size_bytes,
+        keeps_location: keep_location,
+    };
+
+    match repo.add_photo(&new_photo, max_photos).await {
+        Ok(AddPhotoOutcome::Added(photo)) => {
+            tracing::info!(
+                "用户 {} 为活动 {} 上传了照片 {}",
+                user_id,
+                activity_id,
+                photo_id
+            );
+            match photo_to_response(&state, photo).await {
+                Ok(photo) => (StatusCode::OK, success_to_api_response(photo)),
+                Err(e) => {
+                    tracing::error!("生成照片 {} 的访问地址失败: {}", photo_id, e);
+                    (
+                        StatusCode::OK,
+                        error_to_api_response::<ActivityPhoto>(
+                            error_codes::INTERNAL_ERROR,
+                            format!("生成照片地址失败: {}", e),
+                        ),
+                    )
+                }
+            }
+        }
+        Ok(AddPhotoOutcome::LimitReached) => {
+            remove_files(&state, &[&storage_key, &thumbnail_key]).await;
+            (
+                StatusCode::OK,
+                error_to_api_response::<ActivityPhoto>(
+                    error_codes::VALIDATION_ERROR,
+                    format!("每个签到最多附带{}张照片", max_photos),
+                ),
+            )
+        }
+        Err(e) => {
+            tracing::error!("保存活动 {} 的照片记录失败: {}", activity_id, e);
+            remove_files(&state, &[&storage_key, &thumbnail_key]).await;
+            (
+                StatusCode::OK,
+                error_to_api_response::<ActivityPhoto>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("保存照片失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 删除签到照片，只有上传者可以删除
+pub async fn delete_activity_photo(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((activity_id, photo_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+    let repo = PhotoOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.delete_photo(&activity_id, &photo_id, user_id).await {
+        Ok(Some(photo)) => {
+            tracing::info!(
+                "用户 {} 删除了活动 {} 的照片 {}",
+                user_id,
+                activity_id,
+                photo_id
+            );
+            remove_files(&state, &[&photo.storage_key, &photo.thumbnail_key]).await;
+            (
+                StatusCode::OK,
+                success_to_api_response(DeleteActivityPhotoResponse { photo_id }),
+            )
+        }
+        Ok(None) => (
+            StatusCode::OK,
+            error_to_api_response::<DeleteActivityPhotoResponse>(
+                error_codes::NOT_FOUND,
+                "照片不存在或您没有权限删除".to_string(),
+            ),
+        ),
+        Err(e) => {
+            tracing::error!(
+                "用户 {} 删除活动 {} 的照片 {} 失败: {}",
+                user_id,
+                activity_id,
+                photo_id,
+                e
+            );
+            (
+                StatusCode::OK,
+                error_to_api_response::<DeleteActivityPhotoResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("删除照片失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 下载媒体文件
+///
+/// 仅在使用本地存储后端时提供，S3 兼容存储的文件由对象存储直接提供。
+/// 地址由照片接口签发，只发给能看到该活动的用户，客户端无需携带令牌即可直接加载图片。
+/// 签名无效或已过期时返回 403，浏览器缓存不超过地址的过期时间。
+pub async fn get_media_file(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Query(params): Query<MediaFileParams>,
+) -> Response {
+    if !state.media.serves_files() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let now = Utc::now().timestamp();
+    let (Some(expires), Some(signature)) = (params.expires, params.signature) else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+    if !state.media.verify_url(&key, expires, &signature, now) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let data = match state.media.get(&key).await {
+        Ok(data) => data,
+        Err(object_store::Error::NotFound { .. } | object_store::Error::InvalidPath { .. }) => {
+            return StatusCode::NOT_FOUND.into_response();
+        }
+        Err(e) => {
+            tracing::error!("读取媒体文件 {} 失败: {}", key, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type_for(&key))
+        .header(
+            header::CACHE_CONTROL,
+            format!("private, max-age={}", expires - now),
+        )
+        .body(Body::from(data))
+        .unwrap_or_else(|e| {
+            tracing::error!("构建媒体文件响应失败: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
@@ -0,0 +1,174 @@
+// 收藏地点处理器
+// 处理收藏地点的创建、查询和删除，地点范围内的新动态会写入通知收件箱
+
+use crate::AppState;
+use crate::api::models::place::*;
+use crate::database::models::place::SavedPlaceEntity;
+use crate::database::operations::place::PlaceOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    extract::{Extension, Json, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+/// 每个用户最多收藏的地点数量
+const MAX_SAVED_PLACES: i64 = 20;
+
+/// 地点名称的最大长度（字符）
+const MAX_PLACE_NAME_LEN: usize = 100;
+
+/// 关注范围半径的取值范围（米）
+const MIN_PLACE_RADIUS: u32 = 50;
+const MAX_PLACE_RADIUS: u32 = 10_000;
+
+/// 收藏地点
+pub async fn create_saved_place(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateSavedPlaceRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+    let name = payload.name.trim();
+
+    let name_len = name.chars().count();
+    if name_len == 0 || name_len > MAX_PLACE_NAME_LEN {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<SavedPlace>(
+                error_codes::VALIDATION_ERROR,
+                format!("地点名称长度必须在1-{}个字符之间", MAX_PLACE_NAME_LEN),
+            ),
+        );
+    }
+
+    if !(-90.0..=90.0).contains(&payload.latitude) || !(-180.0..=180.0).contains(&payload.longitude)
+    {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<SavedPlace>(
+                error_codes::VALIDATION_ERROR,
+                "非法的地理坐标".to_string(),
+            ),
+        );
+    }
+
+    if !(MIN_PLACE_RADIUS..=MAX_PLACE_RADIUS).contains(&payload.radius) {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<SavedPlace>(
+                error_codes::VALIDATION_ERROR,
+                format!(
+                    "关注范围必须在{}-{}米之间",
+                    MIN_PLACE_RADIUS, MAX_PLACE_RADIUS
+                ),
+            ),
+        );
+    }
+
+    let repo = PlaceOperation::new(Arc::new(state.pool.clone()));
+    match repo
+        .create(
+            user_id,
+            name,
+            payload.latitude,
+            payload.longitude,
+            payload.radius as i32,
+            MAX_SAVED_PLACES,
+        )
+        .await
+    {
+        Ok(Some(place)) => {
+            tracing::info!("用户 {} 收藏了地点 {}", user_id, place.place_id);
+            (
+                StatusCode::OK,
+                success_to_api_response(to_saved_place(place)),
+            )
+        }
+        Ok(None) => (
+            StatusCode::OK,
+            error_to_api_response::<SavedPlace>(
+                error_codes::VALIDATION_ERROR,
+                format!("最多收藏{}个地点", MAX_SAVED_PLACES),
+            ),
+        ),
+        Err(e) => {
+            tracing::error!("用户 {} 收藏地点失败: {}", user_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<SavedPlace>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("收藏地点失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 获取当前用户收藏的地点
+pub async fn get_saved_places(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> impl IntoResponse {
+    let repo = PlaceOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.find_by_user(&claims.sub).await {
+        Ok(places) => (
+            StatusCode::OK,
+            success_to_api_response(SavedPlacesResponse {
+                places: places.into_iter().map(to_saved_place).collect(),
+            }),
+        ),
+        Err(e) => {
+            tracing::error!("获取用户 {} 收藏的地点失败: {}", claims.sub, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<SavedPlacesResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取收藏地点失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 删除收藏的地点
+pub async fn delete_saved_place(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(place_id): Path<String>,
+) -> impl IntoResponse {
+    let repo = PlaceOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.delete(&place_id, &claims.sub).await {
+        Ok(true) => (StatusCode::OK, success_to_api_response(())),
+        Ok(false) => (
+            StatusCode::OK,
+            error_to_api_response::<()>(error_codes::NOT_FOUND, "地点不存在".to_string()),
+        ),
+        Err(e) => {
+            tracing::error!("删除地点 {} 失败: {}", place_id, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<()>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("删除地点失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 将数据库实体转换为API格式
+fn to_saved_place(place: SavedPlaceEntity) -> SavedPlace {
+    SavedPlace {
+        place_id: place.place_id,
+        name: place.name,
+        latitude: place.latitude,
+        longitude: place.longitude,
+        radius: place.radius as u32,
+        created_at: place.created_at,
+    }
+}
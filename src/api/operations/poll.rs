@@ -0,0 +1,338 @@
+// 群聊投票处理器
+// 处理投票消息的校验、成员投票以及投票结果的查询与实时推送
+
+use crate::AppState;
+use crate::api::models::message::{MessageDetail, MessageType};
+use crate::api::models::poll::*;
+use crate::database::models::poll::{NewPoll, PollEntity, VoteOutcome};
+use crate::database::operations::group::GroupOperation;
+use crate::database::operations::poll::PollOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    extract::{Extension, Json, Path, State},
+    http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use futures_util::stream;
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::time::MissedTickBehavior;
+
+/// 投票选项数量的取值范围
+const MIN_POLL_OPTIONS: usize = 2;
+const MAX_POLL_OPTIONS: usize = 10;
+
+/// 选项文字的最大长度（字符）
+const MAX_POLL_OPTION_LEN: usize = 100;
+
+/// 投票从发起到截止的最长时间（天）
+const MAX_POLL_DURATION_DAYS: i64 = 30;
+
+/// 将投票实体转换为API响应格式，是否截止按 `now` 计算
+pub(crate) fn poll_to_response(poll: PollEntity, now: DateTime<Utc>) -> PollDetail {
+    let anonymous = poll.anonymous;
+
+    PollDetail {
+        closed: poll.closes_at.is_some_and(|closes_at| closes_at <= now),
+        message_id: poll.message_id,
+        group_id: poll.group_id,
+        question: poll.question,
+        multiple_choice: poll.multiple_choice,
+        anonymous,
+        closes_at: poll.closes_at,
+        options: poll
+            .options
+            .into_iter()
+            .map(|option| PollOptionResult {
+                option_id: option.option_id,
+                text: option.text,
+                vote_count: option.vote_count,
+                voters: (!anonymous).then(|| {
+                    option
+                        .voters
+                        .into_iter()
+                        .map(|voter| PollVoterInfo {
+                            user_id: voter.user_id,
+                            nickname: voter.nickname,
+                        })
+                        .collect()
+                }),
+            })
+            .collect(),
+        total_voters: poll.total_voters,
+        my_votes: poll.my_votes,
+    }
+}
+
+/// 校验发起投票的设置
+pub(crate) fn validate_poll_request(payload: &CreatePollRequest) -> Result<NewPoll<'_>, String> {
+    if !(MIN_POLL_OPTIONS..=MAX_POLL_OPTIONS).contains(&payload.options.len()) {
+        return Err(format!(
+            "投票选项数量必须在{}-{}个之间",
+            MIN_POLL_OPTIONS, MAX_POLL_OPTIONS
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let mut options = Vec::with_capacity(payload.options.len());
+    for option in &payload.options {
+        let option = option.trim();
+        let option_len = option.chars().count();
+        if option_len == 0 || option_len > MAX_POLL_OPTION_LEN {
+            return Err(format!(
+                "投票选项长度必须在1-{}个字符之间",
+                MAX_POLL_OPTION_LEN
+            ));
+        }
+        if option.chars().any(char::is_control) {
+            return Err("投票选项不能包含控制字符".to_string());
+        }
+        if !seen.insert(option) {
+            return Err("投票选项不能重复".to_string());
+        }
+        options.push(option);
+    }
+
+    if let Some(closes_at) = payload.closes_at {
+        let now = Utc::now();
+        if closes_at <= now {
+            return Err("截止时间必须晚于当前时间".to_string());
+        }
+        if closes_at - now > Duration::days(MAX_POLL_DURATION_DAYS) {
+            return Err(format!("截止时间不能晚于{}天后", MAX_POLL_DURATION_DAYS));
+        }
+    }
+
+    Ok(NewPoll {
+        options,
+        multiple_choice: payload.multiple_choice,
+        anonymous: payload.anonymous,
+        closes_at: payload.closes_at,
+    })
+}
+
+/// 为消息列表中的投票消息填充投票结果，查询失败时仅记录日志
+pub(crate) async fn fill_polls(state: &AppState, viewer_id: &str, messages: &mut [MessageDetail]) {
+    let message_ids: Vec<String> = messages
+        .iter()
+        .filter(|message| message.message_type == MessageType::Poll)
+        .map(|message| message.id.clone())
+        .collect();
+
+    match PollOperation::new(Arc::new(state.pool.clone()))
+        .find_by_messages(&message_ids, viewer_id)
+        .await
+    {
+        Ok(mut polls) => {
+            let now = Utc::now();
+            for message in messages.iter_mut() {
+                message.poll = polls
+                    .remove(&message.id)
+                    .map(|poll| poll_to_response(poll, now));
+            }
+        }
+        Err(e) => tracing::warn!("获取消息中的投票失败: {}", e),
+    }
+}
+
+/// 查询投票，并检查查看者是否为投票所在群组的成员
+async fn find_member_poll(
+    state: &AppState,
+    message_id: &str,
+    user_id: &str,
+) -> Result<PollEntity, (i32, String)> {
+    let internal = |e: sqlx::Error| {
+        tracing::error!("查询投票 {} 失败: {}", message_id, e);
+        (error_codes::INTERNAL_ERROR, format!("查询投票失败: {}", e))
+    };
+
+    let poll = PollOperation::new(Arc::new(state.pool.clone()))
+        .find_by_message(message_id, user_id)
+        .await
+        .map_err(internal)?
+        .ok_or((error_codes::NOT_FOUND, "投票不存在".to_string()))?;
+
+    let is_member = GroupOperation::new(Arc::new(state.pool.clone()))
+        .has_user(&poll.group_id, user_id)
+        .await
+        .map_err(internal)?;
+    if !is_member {
+        return Err((
+            error_codes::PERMISSION_DENIED,
+            "只有群组成员才能查看投票".to_string(),
+        ));
+    }
+
+    Ok(poll)
+}
+
+/// 获取投票及其当前结果，仅群组成员可查看
+pub async fn get_poll(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(message_id): Path<String>,
+) -> impl IntoResponse {
+    match find_member_poll(&state, &message_id, &claims.sub).await {
+        Ok(poll) => (
+            StatusCode::OK,
+            success_to_api_response(poll_to_response(poll, Utc::now())),
+        ),
+        Err((code, msg)) => (
+            StatusCode::OK,
+            error_to_api_response::<PollDetail>(code, msg),
+        ),
+    }
+}
+
+/// 投票
+///
+/// 只有群组成员可以投票，再次投票会覆盖之前的选择，选项为空时撤回投票。
+/// 单选投票只能选择一个选项，投票截止后不能再投票或修改。
+pub async fn vote_poll(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(message_id): Path<String>,
+    Json(payload): Json<VotePollRequest>,
+) -> impl IntoResponse {
+    let user_id = &claims.sub;
+    let repo = PollOperation::new(Arc::new(state.pool.clone()));
+
+    let (code, msg) = match repo.vote(&message_id, user_id, &payload.option_ids).await {
+        Ok(VoteOutcome::Voted) => {
+            tracing::info!(
+                "用户(登录ID) {} 在投票 {} 中选择了 {:?}",
+                user_id,
+                message_id,
+                payload.option_ids
+            );
+            return match repo.find_by_message(&message_id, user_id).await {
+                Ok(Some(poll)) => (
+                    StatusCode::OK,
+                    success_to_api_response(poll_to_response(poll, Utc::now())),
+                ),
+                Ok(None) => (
+                    StatusCode::OK,
+                    error_to_api_response::<PollDetail>(
+                        error_codes::NOT_FOUND,
+                        "投票不存在".to_string(),
+                    ),
+                ),
+                Err(e) => {
+                    tracing::error!("获取投票 {} 结果失败: {}", message_id, e);
+                    (
+                        StatusCode::OK,
+                        error_to_api_response::<PollDetail>(
+                            error_codes::INTERNAL_ERROR,
+                            format!("获取投票结果失败: {}", e),
+                        ),
+                    )
+                }
+            };
+        }
+        Ok(VoteOutcome::NotFound) => (error_codes::NOT_FOUND, "投票不存在".to_string()),
+        Ok(VoteOutcome::NotMember) => {
+            tracing::warn!(
+                "用户(登录ID) {} 在投票 {} 中投票失败: 不是群组成员",
+                user_id,
+                message_id
+            );
+            (
+                error_codes::PERMISSION_DENIED,
+                "用户不是该群组成员".to_string(),
+            )
+        }
+        Ok(VoteOutcome::Closed) => (error_codes::VALIDATION_ERROR, "投票已截止".to_string()),
+        Ok(VoteOutcome::InvalidOption) => (
+            error_codes::VALIDATION_ERROR,
+            "选项不存在、重复，或单选投票选择了多个选项".to_string(),
+        ),
+        Err(e) => {
+            tracing::error!(
+                "用户(登录ID) {} 在投票 {} 中投票失败: {}",
+                user_id,
+                message_id,
+                e
+            );
+            (error_codes::INTERNAL_ERROR, format!("投票失败: {}", e))
+        }
+    };
+
+    (
+        StatusCode::OK,
+        error_to_api_response::<PollDetail>(code, msg),
+    )
+}
+
+/// 以 Server-Sent Events 推送投票结果
+///
+/// 连接建立时推送一次当前结果，之后每个推送间隔检查一次，有变化时再推送；
+/// 投票截止并推送最终结果、投票被删除或查看者退出群组后结束推送。
+pub async fn stream_poll_results(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(message_id): Path<String>,
+) -> Response {
+    if let Err((code, msg)) = find_member_poll(&state, &message_id, &claims.sub).await {
+        return (
+            StatusCode::OK,
+            error_to_api_response::<PollDetail>(code, msg),
+        )
+            .into_response();
+    }
+
+    let mut interval = tokio::time::interval(state.config.poll_stream_interval());
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let events = stream::unfold(
+        (state, message_id, claims.sub, interval, None::<PollDetail>),
+        |(state, message_id, user_id, mut interval, mut last)| async move {
+            loop {
+                if last.as_ref().is_some_and(|poll| poll.closed) {
+                    return None;
+                }
+
+                interval.tick().await;
+
+                let poll = match find_member_poll(&state, &message_id, &user_id).await {
+                    Ok(poll) => poll_to_response(poll, Utc::now()),
+                    Err((error_codes::INTERNAL_ERROR, msg)) => {
+                        tracing::error!("推送投票 {} 的结果失败: {}", message_id, msg);
+                        continue;
+                    }
+                    Err((_, msg)) => {
+                        tracing::debug!(
+                            "用户 {} 的投票 {} 结果推送结束: {}",
+                            user_id,
+                            message_id,
+                            msg
+                        );
+                        return None;
+                    }
+                };
+                if last.as_ref() == Some(&poll) {
+                    continue;
+                }
+
+                let event = Event::default()
+                    .event("poll")
+                    .json_data(&poll)
+                    .unwrap_or_else(|_| Event::default().event("poll"));
+                last = Some(poll);
+                return Some((
+                    Ok::<_, Infallible>(event),
+                    (state, message_id, user_id, interval, last),
+                ));
+            }
+        },
+    );
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
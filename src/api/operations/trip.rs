@@ -0,0 +1,242 @@
+// 行程轨迹处理器
+// 基于用户位置历史切分行程，并支持以 GPX 或 GeoJSON 导出简化后的轨迹
+
+use crate::AppState;
+use crate::api::models::trip::*;
+use crate::database::models::location::TripTrack;
+use crate::database::operations::location::LocationOperation;
+use crate::utils::Claims;
+use crate::utils::{error_codes, error_to_api_response, success_to_api_response};
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{Value, json};
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// 单次最多返回的行程数量
+const MAX_TRIPS: u32 = 100;
+
+/// 简化后的顶点与原始定位点匹配时的容差（度）
+const VERTEX_MATCH_TOLERANCE: f64 = 1e-9;
+
+/// 列出当前用户的行程
+pub async fn get_trips(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<ListTripsRequest>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(20).clamp(1, MAX_TRIPS);
+    let repo = LocationOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.find_trips(&claims.sub, limit as i64).await {
+        Ok(trips) => (
+            StatusCode::OK,
+            success_to_api_response(ListTripsResponse {
+                trips: trips
+                    .into_iter()
+                    .map(|trip| TripInfo {
+                        trip_id: trip.trip_id,
+                        started_at: trip.started_at,
+                        ended_at: trip.ended_at,
+                        duration_secs: (trip.ended_at - trip.started_at).num_seconds(),
+                        distance: trip.distance,
+                        point_count: trip.point_count,
+                    })
+                    .collect(),
+            }),
+        ),
+        Err(e) => {
+            tracing::error!("获取用户 {} 的行程失败: {}", claims.sub, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<ListTripsResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取行程失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 导出行程轨迹
+///
+/// 轨迹经过 Douglas-Peucker 简化，保留下来的顶点带有原始定位时间。
+pub async fn export_trip(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(trip_id): Path<i32>,
+    Query(params): Query<ExportTripRequest>,
+) -> Response {
+    let repo = LocationOperation::new(Arc::new(state.pool.clone()));
+
+    let track = match repo.find_trip_track(&claims.sub, trip_id).await {
+        Ok(Some(track)) => track,
+        Ok(None) => {
+            return (
+                StatusCode::OK,
+                error_to_api_response::<()>(error_codes::NOT_FOUND, "行程不存在".to_string()),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            tracing::error!("获取用户 {} 的行程 {} 失败: {}", claims.sub, trip_id, e);
+            return (
+                StatusCode::OK,
+                error_to_api_response::<()>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("获取行程失败: {}", e),
+                ),
+            )
+                .into_response();
+        }
+    };
+
+    let points = simplified_points(&track);
+    match params.format {
+        TripExportFormat::Geojson => attachment_response(
+            trip_geojson(trip_id, &points).to_string(),
+            "application/geo+json",
+            &format!("trip-{}.geojson", trip_id),
+        ),
+        TripExportFormat::Gpx => attachment_response(
+            trip_gpx(trip_id, &points),
+            "application/gpx+xml",
+            &format!("trip-{}.gpx", trip_id),
+        ),
+    }
+}
+
+/// 删除当前用户的全部位置历史
+pub async fn delete_location_history(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> impl IntoResponse {
+    let repo = LocationOperation::new(Arc::new(state.pool.clone()));
+
+    match repo.delete_history(&claims.sub).await {
+        Ok(deleted) => {
+            tracing::info!("用户 {} 删除了 {} 条位置记录", claims.sub, deleted);
+            (
+                StatusCode::OK,
+                success_to_api_response(DeleteLocationHistoryResponse { deleted }),
+            )
+        }
+        Err(e) => {
+            tracing::error!("删除用户 {} 的位置历史失败: {}", claims.sub, e);
+            (
+                StatusCode::OK,
+                error_to_api_response::<DeleteLocationHistoryResponse>(
+                    error_codes::INTERNAL_ERROR,
+                    format!("删除位置历史失败: {}", e),
+                ),
+            )
+        }
+    }
+}
+
+/// 取出简化后路径的顶点 (纬度, 经度, 定位时间)
+///
+/// 简化结果的顶点是原始定位点的有序子集，按顺序向后匹配即可找回每个顶点的定位时间。
+fn simplified_points(track: &TripTrack) -> Vec<(f64, f64, DateTime<Utc>)> {
+    let coordinates = serde_json::from_str::<Value>(&track.simplified)
+        .ok()
+        .and_then(|geojson| geojson.get("coordinates").cloned())
+        .and_then(|coordinates| serde_json::from_value::<Vec<[f64; 2]>>(coordinates).ok())
+        .unwrap_or_default();
+
+    let mut points = Vec::with_capacity(coordinates.len());
+    let mut next = 0;
+    for [longitude, latitude] in coordinates {
+        while next < track.recorded_at.len() {
+            let index = next;
+            next += 1;
+            if (track.latitudes[index] - latitude).abs() <= VERTEX_MATCH_TOLERANCE
+                && (track.longitudes[index] - longitude).abs() <= VERTEX_MATCH_TOLERANCE
+            {
+                points.push((
+                    track.latitudes[index],
+                    track.longitudes[index],
+                    track.recorded_at[index],
+                ));
+                break;
+            }
+        }
+    }
+
+    // 无法解析简化结果时退回原始定位点
+    if points.len() < 2 {
+        return (0..track.recorded_at.len())
+            .map(|i| {
+                (
+                    track.latitudes[i],
+                    track.longitudes[i],
+                    track.recorded_at[i],
+                )
+            })
+            .collect();
+    }
+    points
+}
+
+/// 生成行程的 GeoJSON Feature
+fn trip_geojson(trip_id: i32, points: &[(f64, f64, DateTime<Utc>)]) -> Value {
+    json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "LineString",
+            "coordinates": points
+                .iter()
+                .map(|(latitude, longitude, _)| [*longitude, *latitude])
+                .collect::<Vec<_>>(),
+        },
+        "properties": {
+            "trip_id": trip_id,
+            "coordinate_times": points
+                .iter()
+                .map(|(_, _, recorded_at)| recorded_at.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .collect::<Vec<_>>(),
+        },
+    })
+}
+
+/// 生成行程的 GPX 文档
+fn trip_gpx(trip_id: i32, points: &[(f64, f64, DateTime<Utc>)]) -> String {
+    let mut gpx = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        "\n",
+        r#"<gpx version="1.1" creator="gudong" xmlns="http://www.topografix.com/GPX/1/1">"#,
+        "\n",
+    ));
+    let _ = writeln!(gpx, "<trk><name>trip-{}</name><trkseg>", trip_id);
+    for (latitude, longitude, recorded_at) in points {
+        let _ = writeln!(
+            gpx,
+            r#"<trkpt lat="{}" lon="{}"><time>{}</time></trkpt>"#,
+            latitude,
+            longitude,
+            recorded_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+        );
+    }
+    gpx.push_str("</trkseg></trk>\n</gpx>\n");
+    gpx
+}
+
+/// 构建以附件形式下载的响应
+fn attachment_response(body: String, content_type: &str, filename: &str) -> Response {
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,15 @@
+/// 用户动态时间线缓存键前缀
+const USER_TIMELINE_PREFIX: &str = "feed:timeline:user:";
+
+/// 群组动态时间线缓存键前缀
+const GROUP_TIMELINE_PREFIX: &str = "feed:timeline:group:";
+
+/// 生成用户动态时间线缓存键
+pub fn user_timeline_key(user_id: &str) -> String {
+    format!("{}{}", USER_TIMELINE_PREFIX, user_id)
+}
+
+/// 生成群组动态时间线缓存键
+pub fn group_timeline_key(group_id: &str) -> String {
+    format!("{}{}", GROUP_TIMELINE_PREFIX, group_id)
+}
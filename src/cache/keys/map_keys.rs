@@ -0,0 +1,7 @@
+/// 地图瓦片聚合缓存键前缀
+const MAP_TILE_PREFIX: &str = "map:tile:";
+
+/// 生成地图瓦片聚合缓存键
+pub fn map_tile_key(layer: &str, z: u8, x: u32, y: u32) -> String {
+    format!("{}{}:{}:{}:{}", MAP_TILE_PREFIX, layer, z, x, y)
+}
@@ -9,12 +9,20 @@ pub mod activity_keys;
 // 群组缓存键模块
 pub mod group_keys;
 
+// 地图缓存键模块
+pub mod map_keys;
+
+// 动态缓存键模块
+pub mod feed_keys;
+
 // 重新导出常用的键生成函数
 pub use activity_keys::{
     ACTIVITY_GEO_KEY, USER_GEO_KEY, activity_cache_key, nearby_activities_key,
     nearby_users_key as nearby_users_location_key, user_activities_key, user_cache_key,
 };
+pub use feed_keys::{group_timeline_key, user_timeline_key};
 pub use group_keys::{
     GROUP_GEO_KEY, group_id_key, group_members_key, group_name_key, nearby_groups_key,
 };
+pub use map_keys::map_tile_key;
 pub use user_keys::{nearby_users_key, user_info_key, user_status_key};
@@ -78,7 +78,7 @@ impl From<CachedNearbyUser> for NearbyUser {
             user_id: cached.user_id,
             nickname: cached.nickname,
             last_active: DateTime::from_timestamp(cached.last_active, 0)
-                .unwrap_or_else(|| Utc::now()),
+                .unwrap_or_else(Utc::now),
             latitude: cached.latitude,
             longitude: cached.longitude,
             distance: cached.distance,
@@ -116,7 +116,7 @@ impl From<CachedUserActivity> for UserActivity {
             latitude: cached.latitude,
             longitude: cached.longitude,
             created_at: DateTime::from_timestamp(cached.created_at, 0)
-                .unwrap_or_else(|| Utc::now()),
+                .unwrap_or_else(Utc::now),
             distance: cached.distance,
             avatar: cached.avatar,
         }
@@ -0,0 +1,55 @@
+use crate::database::models::activity::{ActivityEntity, ActivityType, ActivityVisibility};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 时间线活动缓存模型
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedTimelineActivity {
+    pub id: String,
+    pub activity_type: String, // 数据库中的类型名称
+    pub user_id: String,
+    pub group_id: Option<String>,
+    pub event_id: Option<String>,
+    pub content: Option<String>,
+    pub description: String,
+    pub longitude: f64,
+    pub latitude: f64,
+    pub created_at: i64, // Unix timestamp（微秒）
+    pub visibility: String,
+}
+
+impl From<&ActivityEntity> for CachedTimelineActivity {
+    fn from(activity: &ActivityEntity) -> Self {
+        Self {
+            id: activity.id.clone(),
+            activity_type: activity.activity_type.as_db_str().to_string(),
+            user_id: activity.user_id.clone(),
+            group_id: activity.group_id.clone(),
+            event_id: activity.event_id.clone(),
+            content: activity.content.clone(),
+            description: activity.description.clone(),
+            longitude: activity.longitude,
+            latitude: activity.latitude,
+            created_at: activity.created_at.timestamp_micros(),
+            visibility: activity.visibility.as_db_str().to_string(),
+        }
+    }
+}
+
+impl From<CachedTimelineActivity> for ActivityEntity {
+    fn from(cached: CachedTimelineActivity) -> Self {
+        Self {
+            id: cached.id,
+            activity_type: ActivityType::from_db(&cached.activity_type),
+            user_id: cached.user_id,
+            group_id: cached.group_id,
+            event_id: cached.event_id,
+            content: cached.content,
+            description: cached.description,
+            longitude: cached.longitude,
+            latitude: cached.latitude,
+            created_at: DateTime::from_timestamp_micros(cached.created_at).unwrap_or_else(Utc::now),
+            visibility: ActivityVisibility::from_db(&cached.visibility),
+        }
+    }
+}
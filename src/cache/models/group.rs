@@ -81,7 +81,7 @@ impl From<CachedGroup> for Group {
             password_hash: cached.password_hash,
             creator_id: cached.creator_id,
             created_at: DateTime::from_timestamp(cached.created_at, 0)
-                .unwrap_or_else(|| Utc::now()),
+                .unwrap_or_else(Utc::now),
             member_count: cached.member_count,
         }
     }
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// 地图聚合点缓存模型
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedMapCluster {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub count: i64,
+    pub id: Option<String>,
+    pub label: Option<String>,
+}
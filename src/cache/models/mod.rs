@@ -9,13 +9,21 @@ pub mod activity;
 // 群组缓存模型
 pub mod group;
 
+// 地图缓存模型
+pub mod map;
+
+// 动态缓存模型
+pub mod feed;
+
 pub mod rate_limit;
 pub mod session;
 pub mod token;
 
 // 重新导出常用类型
 pub use activity::{CachedNearbyUser, CachedUserActivity};
+pub use feed::CachedTimelineActivity;
 pub use group::{CachedGroup, CachedGroupMember, CachedNearbyGroup};
+pub use map::CachedMapCluster;
 pub use rate_limit::*;
 pub use session::*;
 pub use token::*;
@@ -80,16 +80,15 @@ impl ActivityCacheOperations {
                     let user_data: redis::RedisResult<String> =
                         conn.get(user_cache_key(&user_id)).await;
 
-                    if let Ok(user_data) = user_data {
-                        if let Ok(mut cached_user) =
+                    if let Ok(user_data) = user_data
+                        && let Ok(mut cached_user) =
                             serde_json::from_str::<CachedNearbyUser>(&user_data)
-                        {
-                            // 更新距离
-                            cached_user.distance = distance;
+                    {
+                        // 更新距离
+                        cached_user.distance = distance;
 
-                            // 转换为API模型
-                            nearby_users.push(NearbyUser::from(cached_user));
-                        }
+                        // 转换为API模型
+                        nearby_users.push(NearbyUser::from(cached_user));
                     }
                 }
             }
@@ -169,16 +168,15 @@ impl ActivityCacheOperations {
                     let activity_data: redis::RedisResult<String> =
                         conn.get(activity_cache_key(&activity_id)).await;
 
-                    if let Ok(activity_data) = activity_data {
-                        if let Ok(mut cached_activity) =
+                    if let Ok(activity_data) = activity_data
+                        && let Ok(mut cached_activity) =
                             serde_json::from_str::<CachedUserActivity>(&activity_data)
-                        {
-                            // 更新距离
-                            cached_activity.distance = distance;
+                    {
+                        // 更新距离
+                        cached_activity.distance = distance;
 
-                            // 转换为API模型
-                            nearby_activities.push(UserActivity::from(cached_activity));
-                        }
+                        // 转换为API模型
+                        nearby_activities.push(UserActivity::from(cached_activity));
                     }
                 }
             }
@@ -0,0 +1,63 @@
+use crate::cache::models::feed::CachedTimelineActivity;
+use redis::{AsyncCommands, Client as RedisClient};
+use std::sync::Arc;
+
+/// 动态时间线缓存操作
+///
+/// 首页信息流在读取时合并各个来源的时间线（fan-out-on-read），
+/// 这里缓存每个用户、每个群组最近的活动，避免每次请求都逐个查询数据库。
+pub struct FeedCacheOperations {
+    redis_client: Arc<RedisClient>,
+}
+
+impl FeedCacheOperations {
+    /// 创建新的动态缓存操作实例
+    pub fn new(redis_client: Arc<RedisClient>) -> Self {
+        Self { redis_client }
+    }
+
+    /// 批量获取缓存的时间线，结果与 `keys` 一一对应，未命中的为 `None`
+    pub async fn get_timelines(
+        &self,
+        keys: &[String],
+    ) -> Result<Vec<Option<Vec<CachedTimelineActivity>>>, redis::RedisError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+
+        let results: Vec<Option<String>> = conn.mget(keys).await?;
+
+        // 无法解析的缓存按未命中处理，由调用方重新加载并覆盖
+        Ok(results
+            .into_iter()
+            .map(|json| json.and_then(|json| serde_json::from_str(&json).ok()))
+            .collect())
+    }
+
+    /// 批量缓存时间线
+    pub async fn cache_timelines(
+        &self,
+        timelines: &[(String, Vec<CachedTimelineActivity>)],
+        ttl: u64,
+    ) -> Result<(), redis::RedisError> {
+        if timelines.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+
+        let mut pipe = redis::pipe();
+        for (key, activities) in timelines {
+            let json = serde_json::to_string(activities).map_err(|e| {
+                redis::RedisError::from((redis::ErrorKind::IoError, "序列化错误", e.to_string()))
+            })?;
+            pipe.set_ex(key, json, ttl).ignore();
+        }
+
+        let _: () = pipe.query_async(&mut conn).await?;
+
+        Ok(())
+    }
+}
@@ -120,24 +120,22 @@ impl GroupCacheOperations {
             let group_key = group_id_key(&group_id);
             let group_json: Option<String> = conn.get(&group_key).await?;
 
-            if let Some(json) = group_json {
-                if let Ok(cached_group) = serde_json::from_str::<CachedGroup>(&json) {
-                    // 延长缓存过期时间
-                    let _: () = conn.expire(&group_key, GROUP_CACHE_EXPIRE as i64).await?;
-
-                    // 转换为Group并添加到结果集
-                    let mut group = Group::from(cached_group);
-
-                    // 如果坐标有变化，更新为GEO索引中的精确坐标
-                    if (group.latitude - lat).abs() > 0.0001
-                        || (group.longitude - lon).abs() > 0.0001
-                    {
-                        group.latitude = lat;
-                        group.longitude = lon;
-                    }
-
-                    cached_groups.push(group);
+            if let Some(json) = group_json
+                && let Ok(cached_group) = serde_json::from_str::<CachedGroup>(&json)
+            {
+                // 延长缓存过期时间
+                let _: () = conn.expire(&group_key, GROUP_CACHE_EXPIRE as i64).await?;
+
+                // 转换为Group并添加到结果集
+                let mut group = Group::from(cached_group);
+
+                // 如果坐标有变化，更新为GEO索引中的精确坐标
+                if (group.latitude - lat).abs() > 0.0001 || (group.longitude - lon).abs() > 0.0001 {
+                    group.latitude = lat;
+                    group.longitude = lon;
                 }
+
+                cached_groups.push(group);
             }
         }
 
@@ -173,18 +171,18 @@ impl GroupCacheOperations {
         let group_key = group_id_key(group_id);
         let group_json: Option<String> = conn.get(&group_key).await?;
 
-        if let Some(json) = group_json {
-            if let Ok(mut cached_group) = serde_json::from_str::<CachedGroup>(&json) {
-                // 更新坐标
-                cached_group.latitude = latitude;
-                cached_group.longitude = longitude;
-
-                // 保存回Redis
-                if let Ok(updated_json) = serde_json::to_string(&cached_group) {
-                    let _: () = conn
-                        .set_ex(group_key, updated_json, GROUP_CACHE_EXPIRE)
-                        .await?;
-                }
+        if let Some(json) = group_json
+            && let Ok(mut cached_group) = serde_json::from_str::<CachedGroup>(&json)
+        {
+            // 更新坐标
+            cached_group.latitude = latitude;
+            cached_group.longitude = longitude;
+
+            // 保存回Redis
+            if let Ok(updated_json) = serde_json::to_string(&cached_group) {
+                let _: () = conn
+                    .set_ex(group_key, updated_json, GROUP_CACHE_EXPIRE)
+                    .await?;
             }
         }
 
@@ -0,0 +1,60 @@
+use crate::cache::keys::map_tile_key;
+use crate::cache::models::map::CachedMapCluster;
+use redis::{AsyncCommands, Client as RedisClient};
+use std::sync::Arc;
+
+/// 地图瓦片缓存操作
+pub struct MapCacheOperations {
+    redis_client: Arc<RedisClient>,
+}
+
+impl MapCacheOperations {
+    /// 创建新的地图缓存操作实例
+    pub fn new(redis_client: Arc<RedisClient>) -> Self {
+        Self { redis_client }
+    }
+
+    /// 缓存瓦片的聚合结果
+    pub async fn cache_tile(
+        &self,
+        layer: &str,
+        (z, x, y): (u8, u32, u32),
+        clusters: &[CachedMapCluster],
+        ttl: u64,
+    ) -> Result<(), redis::RedisError> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+
+        let json = serde_json::to_string(clusters).map_err(|e| {
+            redis::RedisError::from((redis::ErrorKind::IoError, "序列化错误", e.to_string()))
+        })?;
+
+        let _: () = conn.set_ex(map_tile_key(layer, z, x, y), json, ttl).await?;
+
+        Ok(())
+    }
+
+    /// 获取缓存的瓦片聚合结果
+    pub async fn get_cached_tile(
+        &self,
+        layer: &str,
+        (z, x, y): (u8, u32, u32),
+    ) -> Result<Option<Vec<CachedMapCluster>>, redis::RedisError> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+
+        let result: Option<String> = conn.get(map_tile_key(layer, z, x, y)).await?;
+
+        match result {
+            Some(json) => {
+                let clusters = serde_json::from_str(&json).map_err(|e| {
+                    redis::RedisError::from((
+                        redis::ErrorKind::IoError,
+                        "反序列化错误",
+                        e.to_string(),
+                    ))
+                })?;
+                Ok(Some(clusters))
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -9,13 +9,21 @@ pub mod activity;
 // 群组缓存操作
 pub mod group;
 
+// 地图缓存操作
+pub mod map;
+
+// 动态缓存操作
+pub mod feed;
+
 pub mod rate_limit;
 pub mod session;
 pub mod token;
 
 // 重新导出常用操作
 pub use activity::ActivityCacheOperations;
+pub use feed::FeedCacheOperations;
 pub use group::GroupCacheOperations;
+pub use map::MapCacheOperations;
 pub use rate_limit::*;
 pub use session::*;
 pub use token::*;
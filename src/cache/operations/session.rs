@@ -75,12 +75,11 @@ impl SessionCacheOperations {
 
         for key in keys {
             let result: Option<String> = conn.get(&key).await?;
-            if let Some(json) = result {
-                if let Ok(session) = serde_json::from_str::<CachedSession>(&json) {
-                    if session.user_id == user_id {
-                        sessions.push(session);
-                    }
-                }
+            if let Some(json) = result
+                && let Ok(session) = serde_json::from_str::<CachedSession>(&json)
+                && session.user_id == user_id
+            {
+                sessions.push(session);
             }
         }
 
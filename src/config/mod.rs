@@ -1,6 +1,9 @@
 use std::env;
 use std::time::Duration;
 
+/// 定时任务和推送流的最短间隔，`tokio::time::interval` 不接受零间隔
+const MIN_TASK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct Config {
     pub database_url: String,
@@ -14,6 +17,41 @@ pub struct Config {
     pub server_port: u16,
     pub api_base_uri: String,
     pub max_search_radius: f64,
+    pub group_inactive_ttl_secs: u64,
+    pub group_archive_interval_secs: u64,
+    pub group_geofence_grace_secs: u64,
+    pub map_tile_cache_ttl_secs: u64,
+    pub admin_user_ids: Vec<String>,
+    pub activity_heatmap_interval_secs: u64,
+    pub activity_heatmap_retention_secs: u64,
+    pub location_update_interval_secs: u64,
+    pub location_max_accuracy: f64,
+    pub location_stale_after_secs: u64,
+    pub location_fuzz_secret: String,
+    pub location_history_retention_secs: u64,
+    pub location_prune_interval_secs: u64,
+    pub message_activity_interval_secs: u64,
+    pub activity_max_page_size: u32,
+    pub feed_timeline_cache_ttl_secs: u64,
+    pub feed_recency_half_life_secs: u64,
+    pub feed_distance_half_life: f64,
+    pub feed_distance_weight: f64,
+    pub max_following: u32,
+    pub media_storage: String,
+    pub media_local_dir: String,
+    pub media_public_base_url: String,
+    pub media_s3_bucket: String,
+    pub media_s3_endpoint: String,
+    pub media_s3_region: String,
+    pub media_s3_access_key_id: String,
+    pub media_s3_secret_access_key: String,
+    pub media_max_upload_size: usize,
+    pub media_url_secret: String,
+    pub media_url_ttl_secs: u64,
+    pub max_activity_photos: u32,
+    pub event_reminder_lead_secs: u64,
+    pub event_reminder_interval_secs: u64,
+    pub poll_stream_interval_secs: u64,
 }
 
 /// 解析带单位的时间字符串为秒数
@@ -23,7 +61,7 @@ fn parse_time_to_seconds(time_str: &str) -> Result<u64, String> {
     // 匹配最后一个字符作为单位，其前面的都是数值
     let (value_str, unit) = match time_str.chars().last() {
         Some(c) => {
-            if c.is_digit(10) {
+            if c.is_ascii_digit() {
                 // 没有单位，直接作为秒处理
                 (time_str, 's')
             } else {
@@ -61,8 +99,8 @@ impl Config {
 
         // 解析临时令牌过期时间
         let temp_token_expiration_secs = match env::var("TEMP_TOKEN_EXPIRATION") {
-            Ok(val) => parse_time_to_seconds(&val).unwrap_or(1 * 3600), // 默认1小时
-            Err(_) => 1 * 3600,
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(3600), // 默认1小时
+            Err(_) => 3600,
         };
 
         // 解析速率限制窗口时间
@@ -71,6 +109,185 @@ impl Config {
             Err(_) => 60,
         };
 
+        // 解析群组不活跃归档时间
+        let group_inactive_ttl_secs = match env::var("GROUP_INACTIVE_TTL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(7 * 86400), // 默认7天
+            Err(_) => 7 * 86400,
+        };
+
+        // 解析群组归档任务执行间隔
+        let group_archive_interval_secs = match env::var("GROUP_ARCHIVE_INTERVAL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(600), // 默认10分钟
+            Err(_) => 600,
+        };
+
+        // 解析离开群组地理围栏后的发言宽限期
+        let group_geofence_grace_secs = match env::var("GROUP_GEOFENCE_GRACE") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(1800), // 默认30分钟
+            Err(_) => 1800,
+        };
+
+        // 解析地图瓦片聚合结果的缓存时间
+        let map_tile_cache_ttl_secs = match env::var("MAP_TILE_CACHE_TTL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(60), // 默认1分钟
+            Err(_) => 60,
+        };
+
+        // 解析活动热力图聚合任务执行间隔
+        let activity_heatmap_interval_secs = match env::var("ACTIVITY_HEATMAP_INTERVAL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(60), // 默认1分钟
+            Err(_) => 60,
+        };
+
+        // 解析活动热力图数据保留时间
+        let activity_heatmap_retention_secs = match env::var("ACTIVITY_HEATMAP_RETENTION") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(7 * 86400), // 默认7天
+            Err(_) => 7 * 86400,
+        };
+
+        // 解析用户定位写入的最小间隔
+        let location_update_interval_secs = match env::var("LOCATION_UPDATE_INTERVAL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(5), // 默认5秒
+            Err(_) => 5,
+        };
+
+        // 解析可接受的最大定位误差（米）
+        let location_max_accuracy = match env::var("LOCATION_MAX_ACCURACY") {
+            Ok(val) => val.parse().unwrap_or(100.0), // 默认100米
+            Err(_) => 100.0,
+        };
+
+        // 解析用户位置多久未更新后视为过期
+        let location_stale_after_secs = match env::var("LOCATION_STALE_AFTER") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(600), // 默认10分钟
+            Err(_) => 600,
+        };
+
+        // 位置模糊化使用的密钥，未配置时使用JWT密钥
+        let location_fuzz_secret = match env::var("LOCATION_FUZZ_SECRET") {
+            Ok(val) if !val.is_empty() => val,
+            _ => env::var("JWT_SECRET")?,
+        };
+
+        // 解析位置历史（轨迹）保留时间
+        let location_history_retention_secs = match env::var("LOCATION_HISTORY_RETENTION") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(30 * 86400), // 默认30天
+            Err(_) => 30 * 86400,
+        };
+
+        // 解析位置记录清理任务执行间隔
+        let location_prune_interval_secs = match env::var("LOCATION_PRUNE_INTERVAL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(600), // 默认10分钟
+            Err(_) => 600,
+        };
+
+        // 解析同一用户在同一群组记录发送消息活动的最小间隔
+        let message_activity_interval_secs = match env::var("MESSAGE_ACTIVITY_INTERVAL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(600), // 默认10分钟
+            Err(_) => 600,
+        };
+
+        // 解析活动列表单页最大条数
+        let activity_max_page_size = match env::var("ACTIVITY_MAX_PAGE_SIZE") {
+            Ok(val) => val.parse().unwrap_or(100), // 默认100条
+            Err(_) => 100,
+        };
+
+        // 解析首页信息流中单个来源时间线的缓存时间
+        let feed_timeline_cache_ttl_secs = match env::var("FEED_TIMELINE_CACHE_TTL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(60), // 默认1分钟
+            Err(_) => 60,
+        };
+
+        // 解析首页信息流排序中新鲜度的半衰期
+        let feed_recency_half_life_secs = match env::var("FEED_RECENCY_HALF_LIFE") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(6 * 3600), // 默认6小时
+            Err(_) => 6 * 3600,
+        };
+
+        // 解析首页信息流排序中距离的半衰距离（米）
+        let feed_distance_half_life = match env::var("FEED_DISTANCE_HALF_LIFE") {
+            Ok(val) => val.parse().unwrap_or(2000.0), // 默认2公里
+            Err(_) => 2000.0,
+        };
+
+        // 解析首页信息流排序中距离所占权重（0~1，其余为新鲜度）
+        let feed_distance_weight = match env::var("FEED_DISTANCE_WEIGHT") {
+            Ok(val) => val.parse::<f64>().unwrap_or(0.3).clamp(0.0, 1.0), // 默认0.3
+            Err(_) => 0.3,
+        };
+
+        // 解析单个用户最多关注的人数
+        let max_following = match env::var("MAX_FOLLOWING") {
+            Ok(val) => val.parse().unwrap_or(1000), // 默认1000人
+            Err(_) => 1000,
+        };
+
+        // 媒体文件存储后端（local 或 s3）
+        let media_storage = env::var("MEDIA_STORAGE").unwrap_or_else(|_| "local".to_string());
+
+        // 本地存储后端保存文件的目录
+        let media_local_dir = env::var("MEDIA_LOCAL_DIR").unwrap_or_else(|_| "media".to_string());
+
+        // 媒体文件对外访问的基础URL，本地存储未配置时由服务自身提供下载
+        let media_public_base_url = match env::var("MEDIA_PUBLIC_BASE_URL") {
+            Ok(val) if !val.is_empty() => val.trim_end_matches('/').to_string(),
+            _ => format!("{}/media", env::var("API_BASE_URI")?.trim_end_matches('/')),
+        };
+
+        // 本地存储后端签名媒体文件地址使用的密钥，未配置时使用JWT密钥
+        let media_url_secret = match env::var("MEDIA_URL_SECRET") {
+            Ok(val) if !val.is_empty() => val,
+            _ => env::var("JWT_SECRET")?,
+        };
+
+        // 解析媒体文件地址（本地签名地址或 S3 预签名地址）的有效期
+        let media_url_ttl_secs = match env::var("MEDIA_URL_TTL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(86400), // 默认1天
+            Err(_) => 86400,
+        };
+
+        // 解析单张照片上传的大小上限（字节）
+        let media_max_upload_size = match env::var("MEDIA_MAX_UPLOAD_SIZE") {
+            Ok(val) => val.parse().unwrap_or(10 * 1024 * 1024), // 默认10MB
+            Err(_) => 10 * 1024 * 1024,
+        };
+
+        // 解析单个签到最多附带的照片数量
+        let max_activity_photos = match env::var("MAX_ACTIVITY_PHOTOS") {
+            Ok(val) => val.parse().unwrap_or(9), // 默认9张
+            Err(_) => 9,
+        };
+
+        // 解析群组聚会开始前多久发送提醒
+        let event_reminder_lead_secs = match env::var("EVENT_REMINDER_LEAD") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(3600), // 默认1小时
+            Err(_) => 3600,
+        };
+
+        // 解析聚会提醒任务执行间隔
+        let event_reminder_interval_secs = match env::var("EVENT_REMINDER_INTERVAL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(60), // 默认1分钟
+            Err(_) => 60,
+        };
+
+        // 解析投票结果实时推送的检查间隔
+        let poll_stream_interval_secs = match env::var("POLL_STREAM_INTERVAL") {
+            Ok(val) => parse_time_to_seconds(&val).unwrap_or(2), // 默认2秒
+            Err(_) => 2,
+        };
+
+        // 解析管理员用户ID列表（以逗号分隔的登录ID）
+        let admin_user_ids = env::var("ADMIN_USER_IDS")
+            .map(|val| {
+                val.split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
         Ok(Config {
             database_url: env::var("DATABASE_URL")?,
             redis_url: env::var("REDIS_URL")?,
@@ -83,6 +300,41 @@ impl Config {
             rate_limit_window_secs,
             rate_limit_requests: env::var("RATE_LIMIT_REQUESTS")?.parse().unwrap_or(100),
             max_search_radius: env::var("MAX_SEARCH_RADIUS")?.parse().unwrap_or(5000.0),
+            group_inactive_ttl_secs,
+            group_archive_interval_secs,
+            group_geofence_grace_secs,
+            map_tile_cache_ttl_secs,
+            admin_user_ids,
+            activity_heatmap_interval_secs,
+            activity_heatmap_retention_secs,
+            location_update_interval_secs,
+            location_max_accuracy,
+            location_stale_after_secs,
+            location_fuzz_secret,
+            location_history_retention_secs,
+            location_prune_interval_secs,
+            message_activity_interval_secs,
+            activity_max_page_size,
+            feed_timeline_cache_ttl_secs,
+            feed_recency_half_life_secs,
+            feed_distance_half_life,
+            feed_distance_weight,
+            max_following,
+            media_storage,
+            media_local_dir,
+            media_public_base_url,
+            media_s3_bucket: env::var("MEDIA_S3_BUCKET").unwrap_or_default(),
+            media_s3_endpoint: env::var("MEDIA_S3_ENDPOINT").unwrap_or_default(),
+            media_s3_region: env::var("MEDIA_S3_REGION").unwrap_or_default(),
+            media_s3_access_key_id: env::var("MEDIA_S3_ACCESS_KEY_ID").unwrap_or_default(),
+            media_s3_secret_access_key: env::var("MEDIA_S3_SECRET_ACCESS_KEY").unwrap_or_default(),
+            media_max_upload_size,
+            media_url_secret,
+            media_url_ttl_secs,
+            max_activity_photos,
+            event_reminder_lead_secs,
+            event_reminder_interval_secs,
+            poll_stream_interval_secs,
         })
     }
 
@@ -97,4 +349,77 @@ impl Config {
     pub fn rate_limit_window(&self) -> Duration {
         Duration::from_secs(self.rate_limit_window_secs)
     }
+
+    pub fn group_inactive_ttl(&self) -> Duration {
+        Duration::from_secs(self.group_inactive_ttl_secs)
+    }
+
+    pub fn group_archive_interval(&self) -> Duration {
+        Duration::from_secs(self.group_archive_interval_secs).max(MIN_TASK_INTERVAL)
+    }
+
+    pub fn group_geofence_grace(&self) -> Duration {
+        Duration::from_secs(self.group_geofence_grace_secs)
+    }
+
+    pub fn map_tile_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.map_tile_cache_ttl_secs)
+    }
+
+    pub fn activity_heatmap_interval(&self) -> Duration {
+        Duration::from_secs(self.activity_heatmap_interval_secs).max(MIN_TASK_INTERVAL)
+    }
+
+    pub fn activity_heatmap_retention(&self) -> Duration {
+        Duration::from_secs(self.activity_heatmap_retention_secs)
+    }
+
+    pub fn location_update_interval(&self) -> Duration {
+        Duration::from_secs(self.location_update_interval_secs)
+    }
+
+    pub fn location_stale_after(&self) -> Duration {
+        Duration::from_secs(self.location_stale_after_secs)
+    }
+
+    pub fn location_history_retention(&self) -> Duration {
+        Duration::from_secs(self.location_history_retention_secs)
+    }
+
+    pub fn location_prune_interval(&self) -> Duration {
+        Duration::from_secs(self.location_prune_interval_secs).max(MIN_TASK_INTERVAL)
+    }
+
+    pub fn message_activity_interval(&self) -> Duration {
+        Duration::from_secs(self.message_activity_interval_secs)
+    }
+
+    pub fn feed_timeline_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.feed_timeline_cache_ttl_secs)
+    }
+
+    pub fn feed_recency_half_life(&self) -> Duration {
+        Duration::from_secs(self.feed_recency_half_life_secs)
+    }
+
+    pub fn event_reminder_lead(&self) -> Duration {
+        Duration::from_secs(self.event_reminder_lead_secs)
+    }
+
+    pub fn event_reminder_interval(&self) -> Duration {
+        Duration::from_secs(self.event_reminder_interval_secs).max(MIN_TASK_INTERVAL)
+    }
+
+    pub fn poll_stream_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_stream_interval_secs).max(MIN_TASK_INTERVAL)
+    }
+
+    pub fn media_url_ttl(&self) -> Duration {
+        Duration::from_secs(self.media_url_ttl_secs)
+    }
+
+    /// 用户是否为系统管理员
+    pub fn is_admin(&self, user_id: &str) -> bool {
+        self.admin_user_ids.iter().any(|id| id == user_id)
+    }
 }
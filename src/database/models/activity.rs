@@ -2,39 +2,201 @@
 // 定义活动相关的数据库实体
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, FromRow, Postgres, Type};
 
-/// 活动类型枚举
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[repr(i32)]
+/// 活动类型
+///
+/// 数据库中以类型名称（如 `USER_CHECKIN`）保存，API 中使用 snake_case 名称。
+/// 未建模的类型保存在 [`ActivityType::Other`] 中，读写时原样保留。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ActivityType {
     /// 用户登录
-    UserLogin = 1,
+    UserLogin,
     /// 用户签到
-    UserCheckIn = 2,
+    UserCheckedIn,
     /// 创建群组
-    GroupCreated = 10,
+    GroupCreated,
     /// 加入群组
-    UserJoined = 11,
+    UserJoined,
     /// 离开群组
-    UserLeft = 12,
+    UserLeft,
     /// 发送消息
-    MessageSent = 20,
+    MessageSent,
+    /// 创建群组聚会
+    EventCreated,
+    /// 未建模的活动类型，保存数据库中的原始名称
+    Other(String),
 }
 
-/// 活动实体，对应数据库中的活动表
+impl ActivityType {
+    /// 从数据库中的类型名称解析
+    pub fn from_db(name: &str) -> Self {
+        match name {
+            "USER_LOGIN" => ActivityType::UserLogin,
+            "USER_CHECKIN" => ActivityType::UserCheckedIn,
+            "GROUP_CREATE" => ActivityType::GroupCreated,
+            "USER_JOINED" => ActivityType::UserJoined,
+            "USER_LEFT" => ActivityType::UserLeft,
+            "MESSAGE_SENT" => ActivityType::MessageSent,
+            "EVENT_CREATE" => ActivityType::EventCreated,
+            other => ActivityType::Other(other.to_string()),
+        }
+    }
+
+    /// 数据库中保存的类型名称
+    pub fn as_db_str(&self) -> &str {
+        match self {
+            ActivityType::UserLogin => "USER_LOGIN",
+            ActivityType::UserCheckedIn => "USER_CHECKIN",
+            ActivityType::GroupCreated => "GROUP_CREATE",
+            ActivityType::UserJoined => "USER_JOINED",
+            ActivityType::UserLeft => "USER_LEFT",
+            ActivityType::MessageSent => "MESSAGE_SENT",
+            ActivityType::EventCreated => "EVENT_CREATE",
+            ActivityType::Other(name) => name,
+        }
+    }
+
+    /// 从 API 中的类型名称解析，同时接受数据库中的类型名称
+    pub fn from_api(name: &str) -> Self {
+        match name {
+            "user_login" => ActivityType::UserLogin,
+            "user_checked_in" => ActivityType::UserCheckedIn,
+            "group_created" => ActivityType::GroupCreated,
+            "user_joined" => ActivityType::UserJoined,
+            "user_left" => ActivityType::UserLeft,
+            "message_sent" => ActivityType::MessageSent,
+            "event_created" => ActivityType::EventCreated,
+            other => Self::from_db(other),
+        }
+    }
+
+    /// API 中使用的类型名称，未建模的类型使用数据库中的原始名称
+    pub fn as_api_str(&self) -> &str {
+        match self {
+            ActivityType::UserLogin => "user_login",
+            ActivityType::UserCheckedIn => "user_checked_in",
+            ActivityType::GroupCreated => "group_created",
+            ActivityType::UserJoined => "user_joined",
+            ActivityType::UserLeft => "user_left",
+            ActivityType::MessageSent => "message_sent",
+            ActivityType::EventCreated => "event_created",
+            ActivityType::Other(name) => name,
+        }
+    }
+}
+
+impl Serialize for ActivityType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_api_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ActivityType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Self::from_api(&name))
+    }
+}
+
+impl Type<Postgres> for ActivityType {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for ActivityType {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self::from_db(<&str as Decode<Postgres>>::decode(value)?))
+    }
+}
+
+impl Encode<'_, Postgres> for ActivityType {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(self.as_db_str(), buf)
+    }
+}
+
+/// 活动可见范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityVisibility {
+    /// 所有人可见
+    #[default]
+    Public,
+    /// 关注发布者的用户可见
+    Followers,
+    /// 关联群组的成员可见
+    Group,
+    /// 仅发布者自己可见
+    Private,
+}
+
+impl ActivityVisibility {
+    /// 从数据库字段还原可见范围，无法识别的值按仅自己可见处理
+    pub fn from_db(name: &str) -> Self {
+        match name {
+            "public" => ActivityVisibility::Public,
+            "followers" => ActivityVisibility::Followers,
+            "group" => ActivityVisibility::Group,
+            _ => ActivityVisibility::Private,
+        }
+    }
+
+    /// 数据库中保存的可见范围名称
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            ActivityVisibility::Public => "public",
+            ActivityVisibility::Followers => "followers",
+            ActivityVisibility::Group => "group",
+            ActivityVisibility::Private => "private",
+        }
+    }
+}
+
+impl Type<Postgres> for ActivityVisibility {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for ActivityVisibility {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self::from_db(<&str as Decode<Postgres>>::decode(value)?))
+    }
+}
+
+impl Encode<'_, Postgres> for ActivityVisibility {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(self.as_db_str(), buf)
+    }
+}
+
+/// 活动实体，对应数据库中的 user_activities 表
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ActivityEntity {
     /// 活动ID
     pub id: String,
     /// 活动类型
-    #[sqlx(rename = "activity_type")]
-    pub activity_type: i32,
+    pub activity_type: ActivityType,
     /// 用户ID
     pub user_id: String,
     /// 相关群组ID（可选）
     pub group_id: Option<String>,
+    /// 相关群组聚会ID（可选）
+    pub event_id: Option<String>,
     /// 活动内容（扩展数据，通常为JSON格式）
     pub content: Option<String>,
     /// 活动描述
@@ -45,35 +207,177 @@ pub struct ActivityEntity {
     pub latitude: f64,
     /// 活动发生时间
     pub created_at: DateTime<Utc>,
+    /// 可见范围
+    pub visibility: ActivityVisibility,
 }
 
-impl ActivityEntity {
-    /// 获取活动类型
-    pub fn get_activity_type(&self) -> ActivityType {
-        match self.activity_type {
-            1 => ActivityType::UserLogin,
-            2 => ActivityType::UserCheckIn,
-            10 => ActivityType::GroupCreated,
-            11 => ActivityType::UserJoined,
-            12 => ActivityType::UserLeft,
-            20 => ActivityType::MessageSent,
-            _ => ActivityType::UserCheckIn, // 默认为签到
+/// 活动列表的分页游标，按 (created_at, activity_id) 倒序分页
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivityCursor {
+    /// 上一页最后一条活动的发生时间
+    pub created_at: DateTime<Utc>,
+    /// 上一页最后一条活动的ID
+    pub activity_id: String,
+}
+
+impl ActivityCursor {
+    /// 以活动作为下一页的起点
+    pub fn after(activity: &ActivityEntity) -> Self {
+        Self {
+            created_at: activity.created_at,
+            activity_id: activity.id.clone(),
+        }
+    }
+
+    /// 编码为不透明的游标字符串
+    pub fn encode(&self) -> String {
+        format!(
+            "{}:{}",
+            self.created_at.timestamp_micros(),
+            self.activity_id
+        )
+        .bytes()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+    }
+
+    /// 解析游标字符串，格式不正确时返回 `None`
+    pub fn decode(cursor: &str) -> Option<Self> {
+        // from_str_radix 接受前导 '+'，需要先确认全部为十六进制字符
+        if !cursor.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
         }
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(cursor.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let raw = String::from_utf8(bytes).ok()?;
+        let (micros, activity_id) = raw.split_once(':')?;
+
+        Some(Self {
+            created_at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            activity_id: activity_id.to_string(),
+        })
     }
+}
 
-    /// 设置活动类型
-    pub fn set_activity_type(&mut self, activity_type: ActivityType) {
-        self.activity_type = activity_type as i32;
+/// 一页活动
+#[derive(Debug, Clone)]
+pub struct ActivityPage {
+    /// 当前页的活动，按时间倒序
+    pub activities: Vec<ActivityEntity>,
+    /// 下一页游标，没有更多时为空
+    pub next_cursor: Option<ActivityCursor>,
+}
+
+impl ActivityPage {
+    /// 由多取一条的查询结果构造分页，多出的一条表示还有下一页
+    pub fn from_rows(mut rows: Vec<ActivityEntity>, limit: i64) -> Self {
+        let has_more = rows.len() as i64 > limit;
+        rows.truncate(limit.max(0) as usize);
+        let next_cursor = if has_more {
+            rows.last().map(ActivityCursor::after)
+        } else {
+            None
+        };
+
+        Self {
+            activities: rows,
+            next_cursor,
+        }
     }
 }
 
-/// 近期用户活动信息
-pub struct NearbyUserActivity {
-    pub user_id: String,
-    pub nickname: String,
-    pub last_activity_id: Option<String>,
-    pub last_activity_type: Option<String>,
-    pub last_activity_description: Option<String>,
-    pub last_activity_time: Option<chrono::DateTime<chrono::Utc>>,
-    pub distance: Option<f64>,
+/// 热力图网格
+#[derive(Debug, Clone, FromRow)]
+pub struct HeatmapCell {
+    /// 网格的 geohash
+    pub cell: String,
+    /// 网格中心纬度
+    pub latitude: f64,
+    /// 网格中心经度
+    pub longitude: f64,
+    /// 时间窗口内的活动数量
+    pub weight: i64,
+}
+
+/// 热力图查询条件
+#[derive(Debug, Clone)]
+pub struct HeatmapFilter<'a> {
+    /// geohash 精度
+    pub precision: i16,
+    /// 统计最近多少小时
+    pub hours: i32,
+    /// 活动类型（数据库中的类型名称），为空时统计所有类型
+    pub activity_type: Option<&'a str>,
+    /// 视窗范围 (west, south, east, north)，west 大于 east 表示跨越180度经线
+    pub bounds: (f64, f64, f64, f64),
+    /// 最多返回的网格数量
+    pub limit: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按字节编码为十六进制，用于构造格式不正确的游标
+    fn hex(raw: &[u8]) -> String {
+        raw.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let cursor = ActivityCursor {
+            created_at: DateTime::from_timestamp_micros(1_712_345_678_901_234).unwrap(),
+            activity_id: "a1b2:c3".to_string(),
+        };
+
+        let encoded = cursor.encode();
+        assert!(encoded.bytes().all(|b| b.is_ascii_hexdigit()));
+
+        let decoded = ActivityCursor::decode(&encoded).unwrap();
+        assert_eq!(decoded.created_at, cursor.created_at);
+        assert_eq!(decoded.activity_id, cursor.activity_id);
+    }
+
+    #[test]
+    fn cursor_round_trip_before_epoch() {
+        let cursor = ActivityCursor {
+            created_at: DateTime::from_timestamp_micros(-1).unwrap(),
+            activity_id: "old".to_string(),
+        };
+
+        let decoded = ActivityCursor::decode(&cursor.encode()).unwrap();
+        assert_eq!(decoded.created_at, cursor.created_at);
+    }
+
+    #[test]
+    fn cursor_decode_rejects_odd_length() {
+        let encoded = ActivityCursor {
+            created_at: Utc::now(),
+            activity_id: "a1".to_string(),
+        }
+        .encode();
+
+        assert!(ActivityCursor::decode(&encoded[..encoded.len() - 1]).is_none());
+        assert!(ActivityCursor::decode("3").is_none());
+    }
+
+    #[test]
+    fn cursor_decode_rejects_non_hex() {
+        assert!(ActivityCursor::decode("zz").is_none());
+        assert!(ActivityCursor::decode(&format!("{}+1", hex(b"1:a"))).is_none());
+        assert!(ActivityCursor::decode("31:a").is_none());
+        assert!(ActivityCursor::decode("中文").is_none());
+    }
+
+    #[test]
+    fn cursor_decode_rejects_malformed_content() {
+        // 空游标、缺少分隔符、时间戳不是数字、非 UTF-8 内容
+        assert!(ActivityCursor::decode("").is_none());
+        assert!(ActivityCursor::decode(&hex(b"1712345678")).is_none());
+        assert!(ActivityCursor::decode(&hex(b"abc:a1")).is_none());
+        assert!(ActivityCursor::decode(&hex(&[0xff, b':', b'a'])).is_none());
+        assert!(ActivityCursor::decode(&hex(b"99999999999999999999:a1")).is_none());
+    }
 }
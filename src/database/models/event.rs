@@ -0,0 +1,108 @@
+// 群组聚会实体
+// 定义与 group_events、event_rsvps 表对应的数据结构
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
+
+/// 成员对聚会的回复
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RsvpStatus {
+    /// 参加
+    Going,
+    /// 可能参加
+    Maybe,
+    /// 不参加
+    NotGoing,
+}
+
+impl RsvpStatus {
+    /// 从数据库字段还原回复，无法识别的值按不参加处理
+    pub fn from_db(name: &str) -> Self {
+        match name {
+            "going" => RsvpStatus::Going,
+            "maybe" => RsvpStatus::Maybe,
+            _ => RsvpStatus::NotGoing,
+        }
+    }
+
+    /// 数据库中保存的回复名称
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            RsvpStatus::Going => "going",
+            RsvpStatus::Maybe => "maybe",
+            RsvpStatus::NotGoing => "not_going",
+        }
+    }
+}
+
+impl Type<Postgres> for RsvpStatus {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for RsvpStatus {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self::from_db(<&str as Decode<Postgres>>::decode(value)?))
+    }
+}
+
+impl Encode<'_, Postgres> for RsvpStatus {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(self.as_db_str(), buf)
+    }
+}
+
+/// 群组聚会，包含回复统计和查看者自己的回复
+#[derive(Debug, Clone)]
+pub struct EventEntity {
+    pub event_id: String,
+    pub group_id: String,
+    pub group_name: String,
+    pub creator_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub location_name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub created_at: DateTime<Utc>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub going_count: i64,
+    pub maybe_count: i64,
+    /// 查看者的回复，未回复时为空
+    pub my_rsvp: Option<RsvpStatus>,
+    /// 与查询位置的距离（米），仅附近搜索有值
+    pub distance: Option<f64>,
+}
+
+/// 新建聚会所需的参数
+#[derive(Debug, Clone)]
+pub struct NewEvent<'a> {
+    /// 所属群组ID
+    pub group_id: &'a str,
+    /// 创建者ID
+    pub creator_id: &'a str,
+    /// 标题
+    pub title: &'a str,
+    /// 描述（可选）
+    pub description: Option<String>,
+    /// 开始时间
+    pub starts_at: DateTime<Utc>,
+    /// 结束时间
+    pub ends_at: DateTime<Utc>,
+    /// 聚会地点 (纬度, 经度)，为空时使用群组位置
+    pub location: Option<(f64, f64)>,
+    /// 地点名称，为空时使用群组的位置名称
+    pub location_name: Option<&'a str>,
+}
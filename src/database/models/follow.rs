@@ -0,0 +1,12 @@
+// 关注关系实体
+// 定义与 user_follows 表对应的数据结构
+
+use chrono::{DateTime, Utc};
+
+/// 关注列表或粉丝列表中的用户
+#[derive(Debug, Clone)]
+pub struct FollowUserEntity {
+    pub public_user_id: String,
+    pub nickname: String,
+    pub followed_at: DateTime<Utc>,
+}
@@ -0,0 +1,60 @@
+// 逆地理编码实体
+// 定义与 admin_areas 表对应的数据结构
+
+/// 行政区划级别，与 admin_areas.level 对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminLevel {
+    Country = 1,
+    Province = 2,
+    City = 3,
+    District = 4,
+}
+
+impl AdminLevel {
+    /// 数据库中的级别值
+    pub fn as_db_value(self) -> i16 {
+        self as i16
+    }
+
+    /// 只有点位数据时，坐标与代表点的最大匹配距离（米）
+    pub fn max_point_distance(self) -> f64 {
+        match self {
+            AdminLevel::Country => 500_000.0,
+            AdminLevel::Province => 150_000.0,
+            AdminLevel::City => 30_000.0,
+            AdminLevel::District => 8_000.0,
+        }
+    }
+}
+
+/// 坐标解析出的各级行政区名称，地名库中没有对应数据的级别为 `None`
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedPlace {
+    pub country: Option<String>,
+    pub province: Option<String>,
+    pub city: Option<String>,
+    pub district: Option<String>,
+}
+
+impl ResolvedPlace {
+    /// 用于展示的地名，由省、市、区县从大到小组成
+    ///
+    /// 直辖市等省市同名的情况只保留一个；省市区县都缺失时退回国家名。
+    pub fn display_name(&self) -> Option<String> {
+        let mut parts: Vec<&str> = Vec::new();
+        for name in [&self.province, &self.city, &self.district]
+            .into_iter()
+            .flatten()
+        {
+            if parts.last() != Some(&name.as_str()) {
+                parts.push(name);
+            }
+        }
+
+        if parts.is_empty() {
+            self.country.clone()
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+}
@@ -28,6 +28,88 @@ pub struct GroupEntity {
     pub created_at: DateTime<Utc>,
     /// 最后活跃时间
     pub last_active: DateTime<Utc>,
+    /// 过期时间（可选，到期后自动归档）
+    pub expires_at: Option<DateTime<Utc>>,
+    /// 归档时间，归档后的群组不再出现在搜索结果中
+    pub archived_at: Option<DateTime<Utc>>,
+    /// 人数上限（可选）
+    pub max_members: Option<i32>,
+    /// 群组分类
+    pub category: Option<String>,
+    /// 群组标签
+    pub tags: Vec<String>,
+    /// 地理围栏半径（米，可选）
+    pub geofence_radius: Option<i32>,
+    /// 是否设置了地理围栏（半径或多边形）
+    pub is_geofenced: bool,
+    /// 是否需要管理员审批才能加入
+    pub requires_approval: bool,
+}
+
+/// 新建群组所需的参数
+#[derive(Debug, Clone)]
+pub struct NewGroup<'a> {
+    /// 群组名称
+    pub name: &'a str,
+    /// 群组位置名称
+    pub location_name: &'a str,
+    /// 群组位置纬度
+    pub latitude: f64,
+    /// 群组位置经度
+    pub longitude: f64,
+    /// 群组描述
+    pub description: &'a str,
+    /// 群组密码（可选）
+    pub password: Option<&'a str>,
+    /// 创建者ID
+    pub creator_id: &'a str,
+    /// 过期时间（可选）
+    pub expires_at: Option<DateTime<Utc>>,
+    /// 人数上限（可选）
+    pub max_members: Option<i32>,
+    /// 群组分类（可选）
+    pub category: Option<&'a str>,
+    /// 群组标签
+    pub tags: &'a [String],
+    /// 地理围栏半径（米，可选）
+    pub geofence_radius: Option<i32>,
+    /// 地理围栏多边形（WKT格式，可选），设置后优先于半径
+    pub geofence_polygon: Option<&'a str>,
+    /// 是否需要管理员审批才能加入
+    pub requires_approval: bool,
+}
+
+/// 加入群组的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinOutcome {
+    /// 成功加入
+    Joined,
+    /// 已经是群组成员
+    AlreadyMember,
+    /// 群组已满，已进入候补队列（队列中的位置，从1开始）
+    Waitlisted(i64),
+    /// 群组需要审批，已提交加入申请
+    PendingApproval,
+}
+
+/// 待审批的加入申请
+#[derive(Debug, Clone)]
+pub struct GroupJoinRequestEntity {
+    /// 申请者的公开ID
+    pub public_user_id: String,
+    pub nickname: String,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// 地理围栏检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeofenceCheck {
+    /// 群组未设置地理围栏
+    Unfenced,
+    /// 位置在围栏内
+    Inside,
+    /// 位置在围栏外或未提供位置
+    Outside,
 }
 
 /// 群组成员实体，对应数据库中的群组成员表
@@ -63,6 +145,68 @@ pub struct GroupWithDetails {
     pub last_active_at: chrono::DateTime<chrono::Utc>,
     pub member_count: i64,
     pub creator_public_id: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub max_members: Option<i32>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub is_password_required: bool,
+    pub geofence_radius: Option<i32>,
+    pub is_geofenced: bool,
+    pub requires_approval: bool,
+    /// 与查询位置的距离（米），没有查询位置时为空
+    pub distance: Option<f64>,
+    /// 名称搜索的匹配得分，非名称搜索时为空
+    pub score: Option<f64>,
+    /// 满足条件的群组总数（分页前）
+    pub total_count: i64,
+}
+
+/// 群组加入方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupAccess {
+    /// 无需密码和审批即可加入
+    Open,
+    /// 需要密码才能加入
+    Password,
+    /// 需要管理员审批才能加入
+    Approval,
+}
+
+/// 群组搜索排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupSortBy {
+    /// 按距离由近到远，没有查询位置时按最后活跃时间排序
+    #[default]
+    Distance,
+    /// 按最后活跃时间由近到远
+    Activity,
+    /// 按成员数量由多到少
+    MemberCount,
+    /// 按名称匹配度由高到低，非名称搜索时按距离排序
+    Relevance,
+}
+
+/// 群组搜索的过滤、排序与分页条件
+#[derive(Debug, Clone, Default)]
+pub struct GroupSearchFilter {
+    /// 必须同时包含的标签
+    pub tags: Vec<String>,
+    /// 分类
+    pub category: Option<String>,
+    /// 加入方式
+    pub access: Option<GroupAccess>,
+    /// 最少成员数
+    pub min_members: Option<i64>,
+    /// 最近多少小时内有活跃
+    pub active_within_hours: Option<i32>,
+    /// 排序方式
+    pub sort_by: GroupSortBy,
+    /// 跳过的记录数
+    pub offset: i64,
+    /// 返回的最大记录数
+    pub limit: i64,
 }
 
 /// 创建者基本信息
@@ -0,0 +1,47 @@
+// 活动互动实体
+// 定义与 activity_likes、activity_comments 表对应的数据结构
+
+use chrono::{DateTime, Utc};
+
+/// 活动评论
+#[derive(Debug, Clone)]
+pub struct ActivityCommentEntity {
+    pub comment_id: i64,
+    pub activity_id: String,
+    pub user_id: String,
+    pub public_user_id: String,
+    pub nickname: String,
+    pub parent_id: Option<i64>,
+    /// 已删除的评论为空
+    pub content: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// 活动的点赞数、评论数以及查看者是否已点赞
+#[derive(Debug, Clone, Default)]
+pub struct ActivityInteractionCounts {
+    pub like_count: i64,
+    pub comment_count: i64,
+    pub liked: bool,
+}
+
+/// 新增评论的结果
+#[derive(Debug)]
+pub enum AddCommentOutcome {
+    /// 评论成功
+    Added(ActivityCommentEntity),
+    /// 回复的评论不存在、已删除或不属于该活动
+    ParentNotFound,
+}
+
+/// 删除评论的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteCommentOutcome {
+    /// 删除成功
+    Deleted,
+    /// 评论不存在或已删除
+    NotFound,
+    /// 既不是评论作者也不是活动发布者
+    Forbidden,
+}
@@ -0,0 +1,72 @@
+// 用户位置实体
+// 定义与 user_locations 表对应的数据结构
+
+use crate::database::models::activity::ActivityType;
+use chrono::{DateTime, Utc};
+
+/// 用户位置记录
+#[derive(Debug, Clone)]
+pub struct UserLocationEntity {
+    pub location_id: i32,
+    pub user_id: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub accuracy: Option<f64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 附近用户按位置隐私设置处理后的位置、距离及最近一次活动
+#[derive(Debug, Clone)]
+pub struct NearbyUserLocation {
+    pub user_id: String,
+    pub nickname: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub location_updated_at: DateTime<Utc>,
+    pub last_activity_id: Option<String>,
+    pub last_activity_type: Option<ActivityType>,
+    pub last_activity_description: Option<String>,
+    pub last_activity_time: Option<DateTime<Utc>>,
+    pub distance: Option<f64>,
+}
+
+/// 群组内共享的成员位置
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupSharedLocation {
+    pub user_id: String,
+    pub public_user_id: String,
+    pub nickname: String,
+    pub sharing_expires_at: DateTime<Utc>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub accuracy: Option<f64>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 相邻两次定位间隔超过该时长（秒）时视为新的一段行程
+pub const TRIP_GAP_SECS: u64 = 600;
+
+/// 轨迹简化（Douglas-Peucker）的容差（度），约10米
+pub const TRIP_SIMPLIFY_TOLERANCE: f64 = 0.0001;
+
+/// 行程摘要
+///
+/// 行程ID为行程第一条定位的ID。
+#[derive(Debug, Clone)]
+pub struct TripSummary {
+    pub trip_id: i32,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub point_count: i64,
+    pub distance: f64,
+}
+
+/// 行程中的原始定位点与简化后的路径
+#[derive(Debug, Clone)]
+pub struct TripTrack {
+    pub latitudes: Vec<f64>,
+    pub longitudes: Vec<f64>,
+    pub recorded_at: Vec<DateTime<Utc>>,
+    /// 简化后路径的 GeoJSON LineString
+    pub simplified: String,
+}
@@ -0,0 +1,264 @@
+// 地图区域
+// 定义地图视窗查询使用的区域
+
+use crate::database::models::privacy::METERS_PER_DEGREE;
+use std::f64::consts::PI;
+
+/// 支持的最大缩放级别
+pub const MAX_ZOOM: u8 = 22;
+
+/// 地图查询区域
+#[derive(Debug, Clone)]
+pub enum MapArea {
+    /// 经纬度矩形，`min_longitude > max_longitude` 表示跨越180度经线
+    BoundingBox {
+        min_longitude: f64,
+        min_latitude: f64,
+        max_longitude: f64,
+        max_latitude: f64,
+    },
+    /// GeoJSON 格式的多边形（Polygon 或 MultiPolygon）
+    Polygon(String),
+}
+
+impl MapArea {
+    /// 将矩形拆分为不跨越180度经线的矩形，
+    /// 返回 (min_longitude, min_latitude, max_longitude, max_latitude) 列表；多边形返回空列表
+    pub fn envelopes(&self) -> Vec<(f64, f64, f64, f64)> {
+        let MapArea::BoundingBox {
+            min_longitude,
+            min_latitude,
+            max_longitude,
+            max_latitude,
+        } = *self
+        else {
+            return Vec::new();
+        };
+
+        if min_longitude <= max_longitude {
+            vec![(min_longitude, min_latitude, max_longitude, max_latitude)]
+        } else {
+            vec![
+                (min_longitude, min_latitude, 180.0, max_latitude),
+                (-180.0, min_latitude, max_longitude, max_latitude),
+            ]
+        }
+    }
+}
+
+/// 计算 Web 墨卡托 XYZ 瓦片的经纬度范围 (west, south, east, north)，瓦片坐标无效时返回 None
+pub fn tile_bounds(z: u8, x: u32, y: u32) -> Option<(f64, f64, f64, f64)> {
+    if z > MAX_ZOOM {
+        return None;
+    }
+    let tiles = 1u32 << z;
+    if x >= tiles || y >= tiles {
+        return None;
+    }
+
+    let n = tiles as f64;
+    let longitude = |x: f64| x / n * 360.0 - 180.0;
+    let latitude = |y: f64| (PI * (1.0 - 2.0 * y / n)).sinh().atan().to_degrees();
+
+    Some((
+        longitude(x as f64),
+        latitude(y as f64 + 1.0),
+        longitude(x as f64 + 1.0),
+        latitude(y as f64),
+    ))
+}
+
+/// 将矩形 (west, south, east, north) 向四周扩大 `meters` 米，跨越180度经线的矩形以 `east + 360` 作为东边界传入
+///
+/// 经度方向按矩形内离赤道最远处计算，保证扩大后的矩形包含所有与原矩形距离不超过 `meters` 的点。
+pub fn expand_bounds((west, south, east, north): (f64, f64, f64, f64), meters: f64) -> MapArea {
+    let delta_latitude = meters / METERS_PER_DEGREE;
+    let min_latitude = (south - delta_latitude).max(-90.0);
+    let max_latitude = (north + delta_latitude).min(90.0);
+
+    let max_abs_latitude = min_latitude.abs().max(max_latitude.abs());
+    let delta_longitude =
+        meters / (METERS_PER_DEGREE * max_abs_latitude.to_radians().cos()).max(1.0);
+
+    let (min_longitude, max_longitude) = if east - west + 2.0 * delta_longitude >= 360.0 {
+        (-180.0, 180.0)
+    } else {
+        let wrap = |longitude: f64| {
+            if longitude < -180.0 {
+                longitude + 360.0
+            } else if longitude > 180.0 {
+                longitude - 360.0
+            } else {
+                longitude
+            }
+        };
+        (wrap(west - delta_longitude), wrap(east + delta_longitude))
+    };
+
+    MapArea::BoundingBox {
+        min_longitude,
+        min_latitude,
+        max_longitude,
+        max_latitude,
+    }
+}
+
+/// 地图图层
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapLayer {
+    /// 群组
+    Groups,
+    /// 用户活动
+    Activities,
+}
+
+/// 地图聚合点，只包含一个点时带有该点的ID和名称
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MapCluster {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub count: i64,
+    pub id: Option<String>,
+    pub label: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(area: &MapArea) -> (f64, f64, f64, f64) {
+        match *area {
+            MapArea::BoundingBox {
+                min_longitude,
+                min_latitude,
+                max_longitude,
+                max_latitude,
+            } => (min_longitude, min_latitude, max_longitude, max_latitude),
+            MapArea::Polygon(_) => panic!("expected a bounding box"),
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn envelopes_keep_a_regular_box() {
+        let area = MapArea::BoundingBox {
+            min_longitude: 10.0,
+            min_latitude: -5.0,
+            max_longitude: 20.0,
+            max_latitude: 5.0,
+        };
+        assert_eq!(area.envelopes(), vec![(10.0, -5.0, 20.0, 5.0)]);
+    }
+
+    #[test]
+    fn envelopes_split_at_the_antimeridian() {
+        let area = MapArea::BoundingBox {
+            min_longitude: 170.0,
+            min_latitude: -5.0,
+            max_longitude: -170.0,
+            max_latitude: 5.0,
+        };
+        assert_eq!(
+            area.envelopes(),
+            vec![(170.0, -5.0, 180.0, 5.0), (-180.0, -5.0, -170.0, 5.0)]
+        );
+    }
+
+    #[test]
+    fn envelopes_of_a_polygon_are_empty() {
+        assert!(MapArea::Polygon("{}".to_string()).envelopes().is_empty());
+    }
+
+    #[test]
+    fn tile_bounds_at_zoom_zero_cover_the_world() {
+        let (west, south, east, north) = tile_bounds(0, 0, 0).unwrap();
+        assert_close(west, -180.0);
+        assert_close(east, 180.0);
+        // Web Mercator 的纬度上限约为 ±85.0511
+        assert_close(north, 85.0511);
+        assert_close(south, -85.0511);
+        assert!(tile_bounds(0, 1, 0).is_none());
+        assert!(tile_bounds(0, 0, 1).is_none());
+    }
+
+    #[test]
+    fn tile_bounds_split_the_world_into_quadrants() {
+        let (west, south, east, north) = tile_bounds(1, 1, 1).unwrap();
+        assert_close(west, 0.0);
+        assert_close(east, 180.0);
+        assert_close(north, 0.0);
+        assert_close(south, -85.0511);
+    }
+
+    #[test]
+    fn tile_bounds_reject_out_of_range_tiles() {
+        assert!(tile_bounds(3, 7, 7).is_some());
+        assert!(tile_bounds(3, 8, 0).is_none());
+        assert!(tile_bounds(3, 0, 8).is_none());
+    }
+
+    #[test]
+    fn tile_bounds_reject_zoom_above_max() {
+        let last = (1u32 << MAX_ZOOM) - 1;
+        assert!(tile_bounds(MAX_ZOOM, last, last).is_some());
+        assert!(tile_bounds(MAX_ZOOM, last + 1, 0).is_none());
+        assert!(tile_bounds(MAX_ZOOM + 1, 0, 0).is_none());
+    }
+
+    #[test]
+    fn expand_bounds_grows_every_side() {
+        let (west, south, east, north) = bounds(&expand_bounds((10.0, 0.0, 11.0, 1.0), 0.0));
+        assert_eq!((west, south, east, north), (10.0, 0.0, 11.0, 1.0));
+
+        let delta = 1000.0 / METERS_PER_DEGREE;
+        let (west, south, east, north) = bounds(&expand_bounds((10.0, 0.0, 11.0, 1.0), 1000.0));
+        assert_close(south, -delta);
+        assert_close(north, 1.0 + delta);
+        assert!(west < 10.0 - delta && east > 11.0 + delta);
+    }
+
+    #[test]
+    fn expand_bounds_clamps_latitude_at_the_poles() {
+        let (west, south, east, north) =
+            bounds(&expand_bounds((0.0, 89.99, 1.0, 89.999), 10_000.0));
+        assert_eq!(north, 90.0);
+        assert!(south < 89.99);
+        // 极点附近经度方向覆盖整个纬圈
+        assert_eq!((west, east), (-180.0, 180.0));
+
+        let (_, south, _, _) = bounds(&expand_bounds((0.0, -89.999, 1.0, -89.99), 10_000.0));
+        assert_eq!(south, -90.0);
+    }
+
+    #[test]
+    fn expand_bounds_wraps_across_the_antimeridian() {
+        let (west, _, east, _) = bounds(&expand_bounds((179.9, 0.0, 179.95, 1.0), 50_000.0));
+        assert!(west > 179.0 && west < 179.9);
+        assert!(east > -180.0 && east < -179.0);
+
+        let (west, _, east, _) = bounds(&expand_bounds((-179.95, 0.0, -179.9, 1.0), 50_000.0));
+        assert!(west > 179.0 && west < 180.0);
+        assert!(east > -179.9 && east < -179.0);
+    }
+
+    #[test]
+    fn expand_bounds_accepts_an_antimeridian_box_as_east_plus_360() {
+        let (west, _, east, _) = bounds(&expand_bounds((170.0, 0.0, -170.0 + 360.0, 1.0), 1000.0));
+        assert!(west < 170.0 && west > 169.9);
+        assert!(east > -170.0 && east < -169.9);
+    }
+
+    #[test]
+    fn expand_bounds_covers_the_world_when_wider_than_360_degrees() {
+        let (west, _, east, _) = bounds(&expand_bounds((-179.0, 0.0, 179.0, 1.0), 500_000.0));
+        assert_eq!((west, east), (-180.0, 180.0));
+    }
+}
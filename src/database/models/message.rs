@@ -15,10 +15,26 @@ pub enum MessageType {
     Image = 1,
     /// 语音消息
     Voice = 2,
+    /// 投票消息
+    Poll = 3,
     /// 系统消息
     System = 10,
 }
 
+impl MessageType {
+    /// 从数据库字段还原消息类型，无法识别的值按文本消息处理
+    pub fn from_db(value: i32) -> Self {
+        match value {
+            0 => MessageType::Text,
+            1 => MessageType::Image,
+            2 => MessageType::Voice,
+            3 => MessageType::Poll,
+            10 => MessageType::System,
+            _ => MessageType::Text, // 默认为文本消息
+        }
+    }
+}
+
 /// 消息实体，对应数据库中的消息表
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct MessageEntity {
@@ -46,13 +62,7 @@ pub struct MessageEntity {
 impl MessageEntity {
     /// 获取消息类型
     pub fn get_message_type(&self) -> MessageType {
-        match self.message_type {
-            0 => MessageType::Text,
-            1 => MessageType::Image,
-            2 => MessageType::Voice,
-            10 => MessageType::System,
-            _ => MessageType::Text, // 默认为文本消息
-        }
+        MessageType::from_db(self.message_type)
     }
 
     /// 设置消息类型
@@ -68,6 +78,14 @@ pub struct MessageWithUser {
     /// 用户的公开ID，而非登录ID
     pub user_id: String,
     pub nickname: String,
+    pub message_type: i32,
     pub content: String,
     pub created_at: DateTime<Utc>,
 }
+
+impl MessageWithUser {
+    /// 获取消息类型
+    pub fn get_message_type(&self) -> MessageType {
+        MessageType::from_db(self.message_type)
+    }
+}
@@ -2,6 +2,17 @@
 // 包含所有数据库表对应的实体结构
 
 pub mod activity;
+pub mod event;
+pub mod follow;
+pub mod geocode;
 pub mod group;
+pub mod interaction;
+pub mod location;
+pub mod map;
 pub mod message;
+pub mod notification;
+pub mod photo;
+pub mod place;
+pub mod poll;
+pub mod privacy;
 pub mod user;
@@ -0,0 +1,16 @@
+// 通知实体
+// 定义与 notifications 表对应的数据结构
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// 用户通知
+#[derive(Debug, Clone)]
+pub struct NotificationEntity {
+    pub notification_id: i64,
+    pub user_id: String,
+    pub kind: String,
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,54 @@
+// 活动照片实体
+// 定义与 activity_photos 表对应的数据结构
+
+use chrono::{DateTime, Utc};
+
+/// 活动照片
+#[derive(Debug, Clone)]
+pub struct ActivityPhotoEntity {
+    pub photo_id: String,
+    pub activity_id: String,
+    pub user_id: String,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size_bytes: i64,
+    pub keeps_location: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 新增照片所需的参数
+#[derive(Debug, Clone)]
+pub struct NewActivityPhoto<'a> {
+    /// 照片ID，与存储中的对象键对应
+    pub photo_id: &'a str,
+    /// 所属活动ID
+    pub activity_id: &'a str,
+    /// 上传者ID
+    pub user_id: &'a str,
+    /// 原图的对象键
+    pub storage_key: &'a str,
+    /// 缩略图的对象键
+    pub thumbnail_key: &'a str,
+    /// 原图的内容类型
+    pub content_type: &'a str,
+    /// 宽度（像素）
+    pub width: i32,
+    /// 高度（像素）
+    pub height: i32,
+    /// 原图大小（字节）
+    pub size_bytes: i64,
+    /// 是否保留了 EXIF 中的 GPS 信息
+    pub keeps_location: bool,
+}
+
+/// 新增照片的结果
+#[derive(Debug)]
+pub enum AddPhotoOutcome {
+    /// 保存成功
+    Added(ActivityPhotoEntity),
+    /// 活动的照片数量已达上限
+    LimitReached,
+}
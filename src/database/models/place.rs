@@ -0,0 +1,16 @@
+// 收藏地点实体
+// 定义与 saved_places 表对应的数据结构
+
+use chrono::{DateTime, Utc};
+
+/// 用户收藏的地点
+#[derive(Debug, Clone)]
+pub struct SavedPlaceEntity {
+    pub place_id: String,
+    pub user_id: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius: i32,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,69 @@
+// 群聊投票实体
+// 定义与 message_polls、message_poll_options、message_poll_votes 表对应的数据结构
+
+use chrono::{DateTime, Utc};
+
+/// 群聊投票，包含各选项的结果和查看者自己的选择
+#[derive(Debug, Clone)]
+pub struct PollEntity {
+    /// 投票消息ID
+    pub message_id: String,
+    pub group_id: String,
+    /// 投票的问题，即消息内容
+    pub question: String,
+    pub multiple_choice: bool,
+    pub anonymous: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+    /// 按序号排列的选项
+    pub options: Vec<PollOptionEntity>,
+    /// 参与投票的人数
+    pub total_voters: i64,
+    /// 查看者选择的选项序号
+    pub my_votes: Vec<i32>,
+}
+
+/// 投票选项及其结果
+#[derive(Debug, Clone)]
+pub struct PollOptionEntity {
+    pub option_id: i32,
+    pub text: String,
+    pub vote_count: i64,
+    /// 选择该选项的成员，匿名投票时为空
+    pub voters: Vec<PollVoter>,
+}
+
+/// 投票者
+#[derive(Debug, Clone)]
+pub struct PollVoter {
+    /// 用户的公开ID，而非登录ID
+    pub user_id: String,
+    pub nickname: String,
+}
+
+/// 新建投票所需的参数，问题保存在消息内容中
+#[derive(Debug, Clone)]
+pub struct NewPoll<'a> {
+    /// 选项文字，序号即选项ID
+    pub options: Vec<&'a str>,
+    /// 是否允许多选
+    pub multiple_choice: bool,
+    /// 是否匿名，匿名投票不公开投票者
+    pub anonymous: bool,
+    /// 截止时间，为空时一直开放
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+/// 投票的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteOutcome {
+    /// 投票成功
+    Voted,
+    /// 投票不存在
+    NotFound,
+    /// 不是投票所在群组的成员
+    NotMember,
+    /// 投票已截止
+    Closed,
+    /// 选项不存在、重复，或单选投票选择了多个选项
+    InvalidOption,
+}
@@ -0,0 +1,309 @@
+// 用户隐私设置实体
+// 定义位置隐私级别以及对外展示位置的模糊化规则
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::f64::consts::PI;
+
+/// 模糊化偏移按时间分桶的长度（秒），同一桶内同一用户的偏移保持不变
+const FUZZ_BUCKET_SECS: i64 = 3600;
+
+/// 城市级位置使用的网格大小（度），约11公里
+const CITY_GRID_DEGREES: f64 = 0.1;
+
+/// 城市级位置与真实位置的最大偏差（米），即网格对角线的一半
+pub const CITY_MARGIN: f64 = 8_000.0;
+
+/// 每度纬度对应的距离（米）
+pub const METERS_PER_DEGREE: f64 = 111_320.0;
+
+/// 地球平均半径（米）
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// 模糊半径的取值范围（米）
+pub const MIN_FUZZ_RADIUS: u32 = 200;
+pub const MAX_FUZZ_RADIUS: u32 = 10_000;
+
+/// 对外展示位置与真实位置的最大偏差（米）
+///
+/// 按距离查询时需要把搜索范围扩大这么多，再按模糊后的位置过滤。
+pub const MAX_PRIVACY_MARGIN: f64 = if CITY_MARGIN > MAX_FUZZ_RADIUS as f64 {
+    CITY_MARGIN
+} else {
+    MAX_FUZZ_RADIUS as f64
+};
+
+/// 位置隐私级别
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LocationPrivacy {
+    /// 展示精确位置
+    #[default]
+    Exact,
+    /// 在指定半径（米）内随机偏移
+    Fuzzed { radius: u32 },
+    /// 只展示所在城市级网格的中心
+    City,
+    /// 不展示位置（隐身模式）
+    Hidden,
+}
+
+impl LocationPrivacy {
+    /// 从数据库字段还原隐私级别，无法识别的值按隐身处理
+    pub fn from_db(precision: &str, fuzz_radius: Option<i32>) -> Self {
+        match (precision, fuzz_radius) {
+            ("exact", _) => LocationPrivacy::Exact,
+            ("fuzzed", Some(radius)) if radius > 0 => LocationPrivacy::Fuzzed {
+                radius: radius as u32,
+            },
+            ("city", _) => LocationPrivacy::City,
+            _ => LocationPrivacy::Hidden,
+        }
+    }
+
+    /// 数据库中保存的隐私级别名称
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            LocationPrivacy::Exact => "exact",
+            LocationPrivacy::Fuzzed { .. } => "fuzzed",
+            LocationPrivacy::City => "city",
+            LocationPrivacy::Hidden => "hidden",
+        }
+    }
+
+    /// 模糊半径，仅 `Fuzzed` 有值
+    pub fn fuzz_radius(&self) -> Option<i32> {
+        match self {
+            LocationPrivacy::Fuzzed { radius } => Some(*radius as i32),
+            _ => None,
+        }
+    }
+
+    /// 计算对外展示的位置 (纬度, 经度)，隐身时返回 `None`
+    ///
+    /// 模糊偏移由密钥、用户ID和 `at` 所在的时间桶决定，同一时间桶内重复查询得到相同的结果，
+    /// 无法通过多次查询取平均或三角定位还原真实位置。
+    /// 地图查询使用 SQL 函数 `obscure_location` 完成相同的计算，修改时需同步修改。
+    pub fn apply(
+        &self,
+        secret: &str,
+        user_id: &str,
+        (latitude, longitude): (f64, f64),
+        at: DateTime<Utc>,
+    ) -> Option<(f64, f64)> {
+        match self {
+            LocationPrivacy::Exact => Some((latitude, longitude)),
+            LocationPrivacy::Fuzzed { radius } => {
+                let bucket = at.timestamp().div_euclid(FUZZ_BUCKET_SECS);
+                let digest = Sha256::new()
+                    .chain_update(secret.as_bytes())
+                    .chain_update(b":")
+                    .chain_update(user_id.as_bytes())
+                    .chain_update(b":")
+                    .chain_update(bucket.to_be_bytes())
+                    .finalize();
+                let unit = |bytes: &[u8]| {
+                    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64
+                        / (u32::MAX as f64 + 1.0)
+                };
+
+                // 在圆内均匀取点
+                let angle = 2.0 * PI * unit(&digest[0..4]);
+                let distance = *radius as f64 * unit(&digest[4..8]).sqrt();
+
+                let fuzzed_latitude =
+                    (latitude + distance * angle.cos() / METERS_PER_DEGREE).clamp(-90.0, 90.0);
+                let meters_per_longitude =
+                    (METERS_PER_DEGREE * latitude.to_radians().cos()).max(1.0);
+                let fuzzed_longitude =
+                    wrap_longitude(longitude + distance * angle.sin() / meters_per_longitude);
+
+                Some((fuzzed_latitude, fuzzed_longitude))
+            }
+            LocationPrivacy::City => {
+                let snap =
+                    |value: f64| ((value / CITY_GRID_DEGREES).floor() + 0.5) * CITY_GRID_DEGREES;
+                Some((
+                    snap(latitude).clamp(-90.0, 90.0),
+                    wrap_longitude(snap(longitude)),
+                ))
+            }
+            LocationPrivacy::Hidden => None,
+        }
+    }
+}
+
+/// 用户隐私设置记录
+#[derive(Debug, Clone)]
+pub struct PrivacySettingsEntity {
+    pub user_id: String,
+    pub location_precision: String,
+    pub fuzz_radius: Option<i32>,
+    pub location_history: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PrivacySettingsEntity {
+    /// 位置隐私级别
+    pub fn location_privacy(&self) -> LocationPrivacy {
+        LocationPrivacy::from_db(&self.location_precision, self.fuzz_radius)
+    }
+}
+
+/// 两点间的球面距离（米）
+pub fn distance_meters((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_METERS * a.sqrt().asin()
+}
+
+/// 将经度规范到 [-180, 180]
+fn wrap_longitude(longitude: f64) -> f64 {
+    if (-180.0..=180.0).contains(&longitude) {
+        longitude
+    } else {
+        (longitude + 180.0).rem_euclid(360.0) - 180.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "test-secret";
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn exact_keeps_position() {
+        let position = (31.2304, 121.4737);
+        assert_eq!(
+            LocationPrivacy::Exact.apply(SECRET, "u1", position, at(0)),
+            Some(position)
+        );
+    }
+
+    #[test]
+    fn hidden_returns_none() {
+        assert_eq!(
+            LocationPrivacy::Hidden.apply(SECRET, "u1", (31.2304, 121.4737), at(0)),
+            None
+        );
+    }
+
+    #[test]
+    fn fuzzed_stays_within_radius() {
+        let privacy = LocationPrivacy::Fuzzed { radius: 1000 };
+        for (i, position) in [
+            (31.2304, 121.4737),
+            (-33.8688, 151.2093),
+            (64.1466, -21.9426),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            for hour in 0..50 {
+                let user_id = format!("user-{}", i);
+                let fuzzed = privacy
+                    .apply(SECRET, &user_id, position, at(hour * 3600))
+                    .unwrap();
+                assert!(distance_meters(position, fuzzed) <= 1000.0 + 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn fuzzed_is_stable_within_bucket_and_varies_across_buckets() {
+        let privacy = LocationPrivacy::Fuzzed { radius: 5000 };
+        let position = (31.2304, 121.4737);
+
+        let first = privacy.apply(SECRET, "u1", position, at(7200)).unwrap();
+        let same_hour = privacy
+            .apply(SECRET, "u1", position, at(7200 + 3599))
+            .unwrap();
+        let next_hour = privacy
+            .apply(SECRET, "u1", position, at(7200 + 3600))
+            .unwrap();
+        let other_user = privacy.apply(SECRET, "u2", position, at(7200)).unwrap();
+        let other_secret = privacy.apply("other", "u1", position, at(7200)).unwrap();
+
+        assert_eq!(first, same_hour);
+        assert_ne!(first, next_hour);
+        assert_ne!(first, other_user);
+        assert_ne!(first, other_secret);
+    }
+
+    #[test]
+    fn fuzzed_matches_sql_port() {
+        // 迁移 20250422_map_location_privacy.sql 中的 obscure_location 对同一输入返回相同的结果
+        let fuzzed = LocationPrivacy::Fuzzed { radius: 5000 }
+            .apply("sek", "u1", (31.2304, 121.4737), at(1_700_000_000))
+            .unwrap();
+        assert_eq!(fuzzed, (31.20871926974963, 121.45984103307454));
+    }
+
+    #[test]
+    fn fuzzed_wraps_longitude_at_antimeridian() {
+        let privacy = LocationPrivacy::Fuzzed { radius: 10_000 };
+        for hour in 0..50 {
+            let (latitude, longitude) = privacy
+                .apply(SECRET, "u1", (0.0, 179.999), at(hour * 3600))
+                .unwrap();
+            assert!((-180.0..=180.0).contains(&longitude));
+            assert!(distance_meters((0.0, 179.999), (latitude, longitude)) <= 10_000.0 + 1.0);
+        }
+    }
+
+    #[test]
+    fn city_snaps_to_grid_center() {
+        let a = LocationPrivacy::City
+            .apply(SECRET, "u1", (31.2304, 121.4737), at(0))
+            .unwrap();
+        let b = LocationPrivacy::City
+            .apply(SECRET, "u2", (31.2999, 121.4001), at(86_400))
+            .unwrap();
+
+        assert_eq!(a, b);
+        assert!((a.0 - 31.25).abs() < 1e-9 && (a.1 - 121.45).abs() < 1e-9);
+        assert!(distance_meters((31.2304, 121.4737), a) <= CITY_MARGIN);
+    }
+
+    #[test]
+    fn city_wraps_longitude() {
+        let (_, longitude) = LocationPrivacy::City
+            .apply(SECRET, "u1", (10.0, 179.99), at(0))
+            .unwrap();
+        assert!((-180.0..=180.0).contains(&longitude));
+    }
+
+    #[test]
+    fn from_db_treats_unknown_settings_as_hidden() {
+        assert_eq!(
+            LocationPrivacy::from_db("exact", None),
+            LocationPrivacy::Exact
+        );
+        assert_eq!(
+            LocationPrivacy::from_db("fuzzed", Some(500)),
+            LocationPrivacy::Fuzzed { radius: 500 }
+        );
+        assert_eq!(
+            LocationPrivacy::from_db("city", None),
+            LocationPrivacy::City
+        );
+        assert_eq!(
+            LocationPrivacy::from_db("fuzzed", None),
+            LocationPrivacy::Hidden
+        );
+        assert_eq!(
+            LocationPrivacy::from_db("fuzzed", Some(0)),
+            LocationPrivacy::Hidden
+        );
+        assert_eq!(
+            LocationPrivacy::from_db("unknown", None),
+            LocationPrivacy::Hidden
+        );
+    }
+}
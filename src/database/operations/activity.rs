@@ -1,25 +1,24 @@
 // 活动存储库
 // 包含活动相关的数据库操作
 
-use crate::database::models::activity::{ActivityEntity, NearbyUserActivity};
-use sqlx::{Error as SqlxError, PgPool, FromRow};
+use crate::database::models::activity::{
+    ActivityCursor, ActivityEntity, ActivityPage, ActivityType, ActivityVisibility, HeatmapCell,
+    HeatmapFilter,
+};
+use crate::database::models::map::MapArea;
+use crate::database::operations::map::push_obscured_intersects;
+use sqlx::{Error as SqlxError, PgPool, FromRow, Postgres, QueryBuilder, Transaction};
 use std::sync::Arc;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use futures_util::stream::BoxStream;
 
-/// 带距离的活动实体，用于 SQL 查询
+/// 带总数的活动实体，用于分页查询
 #[derive(Debug, FromRow)]
-pub struct ActivityEntityWithDistance {
-    pub id: String,
-    pub activity_type: i32,
-    pub user_id: String,
-    pub group_id: Option<String>,
-    pub content: Option<String>,
-    pub description: String,
-    pub longitude: f64,
-    pub latitude: f64,
-    pub created_at: DateTime<Utc>,
-    pub distance: Option<f64>,
+struct ActivityEntityWithTotal {
+    #[sqlx(flatten)]
+    activity: ActivityEntity,
+    total_count: i64,
 }
 
 /// 活动存储库，处理所有与活动相关的数据库操作
@@ -34,13 +33,16 @@ impl ActivityOperation {
     }
 
     /// 创建活动
+    ///
+    /// 可见范围为 [`ActivityVisibility::Group`] 时，`group_id` 为可以看到该活动的群组。
     pub async fn create_activity(
         &self,
         user_id: &str,
-        activity_type: &str,
+        activity_type: &ActivityType,
         activity_details: Option<&str>,
-        latitude: f64,
-        longitude: f64,
+        (latitude, longitude): (f64, f64),
+        visibility: ActivityVisibility,
+        group_id: Option<&str>,
     ) -> Result<String, SqlxError> {
         let activity_id = Uuid::new_v4().to_string();
 
@@ -48,15 +50,18 @@ impl ActivityOperation {
         sqlx::query!(
             r#"
             INSERT INTO user_activities (
-                activity_id, user_id, activity_type, activity_details, latitude, longitude
-            ) VALUES ($1, $2, $3, $4, $5, $6)
+                activity_id, user_id, activity_type, activity_details, latitude, longitude,
+                visibility, group_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             "#,
             activity_id,
             user_id,
-            activity_type,
+            activity_type.as_db_str(),
             activity_details,
             latitude,
-            longitude
+            longitude,
+            visibility.as_db_str(),
+            group_id
         )
         .execute(&*self.db)
         .await?;
@@ -64,355 +69,641 @@ impl ActivityOperation {
         Ok(activity_id)
     }
 
-    /// 查找附近活动
-    pub async fn find_nearby_activities(
+    /// 在事务中记录群组相关的活动
+    ///
+    /// 活动位置为群组坐标，活动描述为群组名称，只对群组成员可见。`min_interval_secs` 大于0时，
+    /// 同一用户在同一群组的同类活动在该时间内只记录一次。返回是否写入了新活动。
+    pub async fn record_group_activity(
+        tx: &mut Transaction<'_, Postgres>,
+        group_id: &str,
+        user_id: &str,
+        activity_type: &ActivityType,
+        min_interval_secs: i64,
+    ) -> Result<bool, SqlxError> {
+        let activity_id = Uuid::new_v4().to_string();
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO user_activities (
+                activity_id, user_id, group_id, activity_type, activity_details, latitude, longitude,
+                visibility
+            )
+            SELECT $1, $2::varchar, g.group_id, $4::varchar, g.name, g.latitude, g.longitude, 'group'
+            FROM groups g
+            WHERE g.group_id = $3
+              AND (
+                  $5::float8 <= 0
+                  OR NOT EXISTS (
+                      SELECT 1
+                      FROM user_activities a
+                      WHERE a.group_id = $3
+                        AND a.user_id = $2
+                        AND a.activity_type = $4
+                        AND a.created_at > NOW() - make_interval(secs => $5::float8)
+                  )
+              )
+            "#,
+            activity_id,
+            user_id,
+            group_id,
+            activity_type.as_db_str(),
+            min_interval_secs as f64
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        Ok(inserted > 0)
+    }
+
+    /// 在事务中记录群组聚会相关的活动
+    ///
+    /// 活动关联到聚会和所属群组，位置为聚会地点，活动描述为聚会标题，只对群组成员可见。
+    pub async fn record_event_activity(
+        tx: &mut Transaction<'_, Postgres>,
+        event_id: &str,
+        user_id: &str,
+        activity_type: &ActivityType,
+    ) -> Result<(), SqlxError> {
+        let activity_id = Uuid::new_v4().to_string();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO user_activities (
+                activity_id, user_id, group_id, event_id, activity_type, activity_details,
+                latitude, longitude, visibility
+            )
+            SELECT $1, $2, e.group_id, e.event_id, $4, e.title, e.latitude, e.longitude, 'group'
+            FROM group_events e
+            WHERE e.event_id = $3
+            "#,
+            activity_id,
+            user_id,
+            event_id,
+            activity_type.as_db_str()
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 按时间倒序分页获取 `viewer_id` 可见的全部活动
+    pub async fn find_recent_activities(
+        &self,
+        viewer_id: &str,
+        cursor: Option<&ActivityCursor>,
+        limit: i64,
+    ) -> Result<ActivityPage, SqlxError> {
+        let (before_time, before_id) = cursor_bounds(cursor);
+
+        let rows = sqlx::query_as!(
+            ActivityEntity,
+            r#"
+            SELECT
+                a.activity_id as "id!",
+                a.activity_type as "activity_type: ActivityType",
+                a.user_id as "user_id!",
+                a.group_id as "group_id",
+                a.event_id as "event_id",
+                a.activity_details as "content",
+                COALESCE(a.activity_details, a.activity_type) as "description!",
+                a.longitude as "longitude!",
+                a.latitude as "latitude!",
+                a.created_at as "created_at!",
+                a.visibility as "visibility: ActivityVisibility"
+            FROM user_activities a
+            WHERE ($1::timestamptz IS NULL OR (a.created_at, a.activity_id) < ($1, $2::varchar))
+              AND activity_visible_to(a.user_id, a.visibility, a.group_id, $4)
+            ORDER BY a.created_at DESC, a.activity_id DESC
+            LIMIT $3
+            "#,
+            before_time,
+            before_id,
+            limit + 1,
+            viewer_id
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(ActivityPage::from_rows(rows, limit))
+    }
+
+    /// 按类型分页查找 `viewer_id` 可见的附近活动，`activity_types` 为空时不按类型过滤
+    pub async fn find_nearby_activities_by_type(
         &self,
-        latitude: f64,
-        longitude: f64,
+        viewer_id: &str,
+        (latitude, longitude): (f64, f64),
         radius: f64,
+        activity_types: &[ActivityType],
+        cursor: Option<&ActivityCursor>,
         limit: i64,
-    ) -> Result<Vec<ActivityEntity>, SqlxError> {
-        let actual_limit = if limit <= 0 { 20 } else { limit };
+    ) -> Result<ActivityPage, SqlxError> {
+        let types: Vec<String> = activity_types
+            .iter()
+            .map(|t| t.as_db_str().to_string())
+            .collect();
+        let (before_time, before_id) = cursor_bounds(cursor);
 
-        // 使用PostGIS的ST_DWithin和ST_Distance函数进行精确的地理空间查询
-        // 将经纬度转换为地理坐标点，并使用球面计算距离
-        let activities = sqlx::query!(
+        // 使用 geom 列上的 GIST 索引进行球面距离过滤
+        let rows = sqlx::query_as!(
+            ActivityEntity,
             r#"
-            SELECT 
+            SELECT
                 a.activity_id as "id!",
-                CASE 
-                    WHEN a.activity_type = 'USER_CHECKIN' THEN 2
-                    WHEN a.activity_type = 'GROUP_CREATE' THEN 10
-                    WHEN a.activity_type = 'USER_JOINED' THEN 11
-                    WHEN a.activity_type = 'MESSAGE_SENT' THEN 20
-                    ELSE 1
-                END as "activity_type!",
+                a.activity_type as "activity_type: ActivityType",
                 a.user_id as "user_id!",
-                NULL as "group_id",
+                a.group_id as "group_id",
+                a.event_id as "event_id",
                 a.activity_details as "content",
                 COALESCE(a.activity_details, a.activity_type) as "description!",
                 a.longitude as "longitude!",
                 a.latitude as "latitude!",
                 a.created_at as "created_at!",
-                -- 使用PostGIS计算精确的球面距离（米）
-                ST_Distance(
-                    ST_SetSRID(ST_MakePoint(a.longitude, a.latitude), 4326)::geography,
-                    ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography
-                ) as "distance"
+                a.visibility as "visibility: ActivityVisibility"
             FROM user_activities a
             WHERE ST_DWithin(
-                ST_SetSRID(ST_MakePoint(a.longitude, a.latitude), 4326)::geography,
+                a.geom,
                 ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography,
                 $3
             )
-            ORDER BY a.created_at DESC
-            LIMIT $4
+            AND (cardinality($4::varchar[]) = 0 OR a.activity_type = ANY($4))
+            AND ($5::timestamptz IS NULL OR (a.created_at, a.activity_id) < ($5, $6::varchar))
+            AND activity_visible_to(a.user_id, a.visibility, a.group_id, $8)
+            ORDER BY a.created_at DESC, a.activity_id DESC
+            LIMIT $7
             "#,
             latitude,
             longitude,
-            radius,  // 以米为单位的半径
-            actual_limit
+            radius,
+            &types,
+            before_time,
+            before_id,
+            limit + 1,
+            viewer_id
         )
         .fetch_all(&*self.db)
         .await?;
 
-        // 转换为ActivityEntity结构
-        let entities = activities
-            .into_iter()
-            .map(|a| ActivityEntity {
-                id: a.id,
-                activity_type: a.activity_type,
-                user_id: a.user_id,
-                group_id: a.group_id,
-                content: a.content,
-                description: a.description,
-                longitude: a.longitude,
-                latitude: a.latitude,
-                created_at: a.created_at,
-            })
-            .collect();
-
-        Ok(entities)
+        Ok(ActivityPage::from_rows(rows, limit))
     }
 
-    /// 按类型查找附近活动
-    pub async fn find_nearby_activities_by_type(
+    /// 查找地图区域内 `viewer_id` 可见的活动，按时间倒序，返回当前页的活动和满足条件的总数
+    ///
+    /// 活动位置为 `obscure_location` 按发布者的位置隐私设置处理后的位置，隐身用户的活动被排除；
+    /// 区域过滤、总数和分页都基于处理后的位置，不会泄露真实位置是否在区域内。
+    pub async fn find_activities_in_area(
         &self,
-        latitude: f64,
-        longitude: f64,
-        radius: f64,
+        (viewer_id, fuzz_secret): (&str, &str),
+        area: &MapArea,
+        offset: i64,
         limit: i64,
-        activity_types: &[&str],
-    ) -> Result<Vec<ActivityEntity>, SqlxError> {
-        let actual_limit = if limit <= 0 { 20 } else { limit };
-
-        // 构建查询条件，按活动类型过滤
-        let types_condition = if activity_types.is_empty() {
-            "".to_string()
-        } else {
-            let types = activity_types
-                .iter()
-                .map(|t| format!("'{}'", t))
-                .collect::<Vec<_>>()
-                .join(", ");
-            format!("AND a.activity_type IN ({})", types)
-        };
-
-        // 动态构建SQL查询
-        let query = format!(
+    ) -> Result<(Vec<ActivityEntity>, i64), SqlxError> {
+        let mut query = QueryBuilder::new(
             r#"
             SELECT 
                 a.activity_id as "id",
-                CASE 
-                    WHEN a.activity_type = 'USER_CHECKIN' THEN 2
-                    WHEN a.activity_type = 'GROUP_CREATE' THEN 10
-                    WHEN a.activity_type = 'USER_JOINED' THEN 11
-                    WHEN a.activity_type = 'MESSAGE_SENT' THEN 20
-                    ELSE 1
-                END as "activity_type",
+                a.activity_type as "activity_type",
                 a.user_id as "user_id",
-                NULL as "group_id",
+                a.group_id as "group_id",
+                a.event_id as "event_id",
                 a.activity_details as "content",
                 COALESCE(a.activity_details, a.activity_type) as "description",
-                a.longitude as "longitude",
-                a.latitude as "latitude",
+                o.longitude as "longitude",
+                o.latitude as "latitude",
                 a.created_at as "created_at",
-                -- 使用PostGIS计算精确的球面距离（米）
-                ST_Distance(
-                    ST_SetSRID(ST_MakePoint(a.longitude, a.latitude), 4326)::geography,
-                    ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography
-                ) as "distance"
+                a.visibility as "visibility",
+                COUNT(*) OVER () as "total_count"
             FROM user_activities a
-            WHERE ST_DWithin(
-                ST_SetSRID(ST_MakePoint(a.longitude, a.latitude), 4326)::geography,
-                ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography,
-                $3
-            )
-            {}
-            ORDER BY a.created_at DESC
-            LIMIT $4
-            "#,
-            types_condition
+            CROSS JOIN LATERAL obscure_location(a.user_id, "#,
         );
+        query
+            .push_bind(viewer_id.to_string())
+            .push(", ")
+            .push_bind(fuzz_secret.to_string())
+            .push(
+                ", a.latitude, a.longitude, a.created_at) o
+            WHERE activity_visible_to(a.user_id, a.visibility, a.group_id, ",
+            )
+            .push_bind(viewer_id.to_string())
+            .push(")");
+        push_obscured_intersects(&mut query, "a.geom", ("o.latitude", "o.longitude"), area);
+        query
+            .push(" ORDER BY a.created_at DESC, a.activity_id LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
 
-        // 执行动态SQL查询
-        let activities = sqlx::query_as::<_, ActivityEntityWithDistance>(&query)
-            .bind(latitude)
-            .bind(longitude)
-            .bind(radius)
-            .bind(actual_limit)
+        let rows = query
+            .build_query_as::<ActivityEntityWithTotal>()
             .fetch_all(&*self.db)
             .await?;
 
-        // 转换为ActivityEntity结构
-        let entities = activities
-            .into_iter()
-            .map(|a| ActivityEntity {
-                id: a.id,
-                activity_type: a.activity_type,
-                user_id: a.user_id,
-                group_id: a.group_id,
-                content: a.content,
-                description: a.description,
-                longitude: a.longitude,
-                latitude: a.latitude,
-                created_at: a.created_at,
-            })
-            .collect();
+        let total = rows.first().map(|r| r.total_count).unwrap_or(0);
+        let activities = rows.into_iter().map(|r| r.activity).collect();
+
+        Ok((activities, total))
+    }
+
+    /// 按时间顺序逐行读取用户的全部活动，用于导出
+    pub fn stream_user_activities<'a>(
+        &'a self,
+        user_id: &'a str,
+    ) -> BoxStream<'a, Result<ActivityEntity, SqlxError>> {
+        sqlx::query_as!(
+            ActivityEntity,
+            r#"
+            SELECT 
+                a.activity_id as "id!",
+                a.activity_type as "activity_type: ActivityType",
+                a.user_id as "user_id!",
+                a.group_id as "group_id",
+                a.event_id as "event_id",
+                a.activity_details as "content",
+                COALESCE(a.activity_details, a.activity_type) as "description!",
+                a.longitude as "longitude!",
+                a.latitude as "latitude!",
+                a.created_at as "created_at!",
+                a.visibility as "visibility: ActivityVisibility"
+            FROM user_activities a
+            WHERE a.user_id = $1
+            ORDER BY a.created_at, a.activity_id
+            "#,
+            user_id
+        )
+        .fetch(&*self.db)
+    }
+
+    /// 增量聚合活动热力图
+    ///
+    /// 只处理上次聚合之后、`lag_secs` 秒之前创建的公开活动，按小时、geohash 网格
+    /// 和活动类型累加到 `activity_heatmap_cells`，同时删除超过保留期的数据。
+    /// 活动位置按发布者的位置隐私设置处理后再聚合，隐身用户的活动不计入。
+    /// 返回本次更新的网格数量。
+    pub async fn aggregate_heatmap(
+        &self,
+        fuzz_secret: &str,
+        precisions: &[i32],
+        lag_secs: i64,
+        retention_secs: i64,
+    ) -> Result<u64, SqlxError> {
+        let mut tx = self.db.begin().await?;
+
+        // 锁定进度行，避免多个实例重复聚合；超出保留时间的活动不再聚合
+        let window = sqlx::query!(
+            r#"
+            SELECT
+                GREATEST(
+                    aggregated_until,
+                    date_trunc('hour', NOW() - make_interval(secs => $2::float8))
+                ) as "since!",
+                NOW() - make_interval(secs => $1::float8) as "until!"
+            FROM activity_heatmap_state
+            WHERE id
+            FOR UPDATE
+            "#,
+            lag_secs as f64,
+            retention_secs as f64
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if window.until <= window.since {
+            return Ok(0);
+        }
+
+        let updated = sqlx::query!(
+            r#"
+            INSERT INTO activity_heatmap_cells (
+                precision, bucket_start, activity_type, cell, latitude, longitude, weight
+            )
+            SELECT
+                h.precision,
+                h.bucket_start,
+                h.activity_type,
+                h.cell,
+                ST_Y(ST_PointFromGeoHash(h.cell)),
+                ST_X(ST_PointFromGeoHash(h.cell)),
+                h.weight
+            FROM (
+                SELECT
+                    p.precision::smallint as precision,
+                    date_trunc('hour', a.created_at) as bucket_start,
+                    a.activity_type,
+                    ST_GeoHash(ST_SetSRID(ST_MakePoint(o.longitude, o.latitude), 4326), p.precision) as cell,
+                    COUNT(*) as weight
+                FROM user_activities a
+                CROSS JOIN LATERAL obscure_location(
+                    a.user_id, NULL::varchar, $4, a.latitude, a.longitude, a.created_at
+                ) o
+                CROSS JOIN unnest($3::int[]) as p(precision)
+                WHERE a.created_at > $1 AND a.created_at <= $2
+                  AND a.visibility = 'public'
+                GROUP BY 1, 2, 3, 4
+            ) h
+            ON CONFLICT (precision, bucket_start, activity_type, cell)
+            DO UPDATE SET weight = activity_heatmap_cells.weight + EXCLUDED.weight
+            "#,
+            window.since,
+            window.until,
+            precisions,
+            fuzz_secret
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query!(
+            r#"
+            UPDATE activity_heatmap_state
+            SET aggregated_until = $1
+            WHERE id
+            "#,
+            window.until
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM activity_heatmap_cells
+            WHERE bucket_start < NOW() - make_interval(secs => $1::float8)
+            "#,
+            retention_secs as f64
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
 
-        Ok(entities)
+        Ok(updated)
     }
 
-    /// 获取用户活动
+    /// 查询视窗内的热力图网格，按活动数量从高到低排序
+    ///
+    /// 时间窗口按整小时对齐，包含当前小时。
+    pub async fn find_heatmap_cells(
+        &self,
+        filter: &HeatmapFilter<'_>,
+    ) -> Result<Vec<HeatmapCell>, SqlxError> {
+        let (west, south, east, north) = filter.bounds;
+
+        sqlx::query_as!(
+            HeatmapCell,
+            r#"
+            SELECT
+                cell,
+                MIN(latitude) as "latitude!",
+                MIN(longitude) as "longitude!",
+                SUM(weight)::bigint as "weight!"
+            FROM activity_heatmap_cells
+            WHERE precision = $1
+              AND bucket_start >= date_trunc('hour', NOW() - make_interval(hours => $2))
+              AND ($3::varchar IS NULL OR activity_type = $3)
+              AND latitude BETWEEN $4::float8 AND $5::float8
+              AND CASE
+                    WHEN $6::float8 <= $7::float8 THEN longitude BETWEEN $6 AND $7
+                    ELSE longitude >= $6 OR longitude <= $7
+                  END
+            GROUP BY cell
+            ORDER BY 4 DESC, cell
+            LIMIT $8
+            "#,
+            filter.precision,
+            filter.hours,
+            filter.activity_type,
+            south,
+            north,
+            west,
+            east,
+            filter.limit
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 按时间倒序分页获取用户对 `viewer_id` 可见的活动
     pub async fn find_user_activities(
         &self,
+        viewer_id: &str,
         user_id: &str,
+        cursor: Option<&ActivityCursor>,
         limit: i64,
-    ) -> Result<Vec<ActivityEntity>, SqlxError> {
-        let actual_limit = if limit <= 0 { 20 } else { limit };
+    ) -> Result<ActivityPage, SqlxError> {
+        let (before_time, before_id) = cursor_bounds(cursor);
 
-        // 查询用户的活动记录
-        let activities = sqlx::query!(
+        let rows = sqlx::query_as!(
+            ActivityEntity,
             r#"
-            SELECT 
+            SELECT
                 a.activity_id as "id!",
-                CASE 
-                    WHEN a.activity_type = 'USER_CHECKIN' THEN 2
-                    WHEN a.activity_type = 'GROUP_CREATE' THEN 10
-                    WHEN a.activity_type = 'USER_JOINED' THEN 11
-                    WHEN a.activity_type = 'MESSAGE_SENT' THEN 20
-                    ELSE 1
-                END as "activity_type!",
+                a.activity_type as "activity_type: ActivityType",
                 a.user_id as "user_id!",
-                NULL as "group_id",
+                a.group_id as "group_id",
+                a.event_id as "event_id",
                 a.activity_details as "content",
                 COALESCE(a.activity_details, a.activity_type) as "description!",
                 a.longitude as "longitude!",
                 a.latitude as "latitude!",
-                a.created_at as "created_at!"
+                a.created_at as "created_at!",
+                a.visibility as "visibility: ActivityVisibility"
             FROM user_activities a
             WHERE a.user_id = $1
-            ORDER BY a.created_at DESC
-            LIMIT $2
+              AND ($2::timestamptz IS NULL OR (a.created_at, a.activity_id) < ($2, $3::varchar))
+              AND activity_visible_to(a.user_id, a.visibility, a.group_id, $5)
+            ORDER BY a.created_at DESC, a.activity_id DESC
+            LIMIT $4
             "#,
             user_id,
-            actual_limit
+            before_time,
+            before_id,
+            limit + 1,
+            viewer_id
         )
         .fetch_all(&*self.db)
         .await?;
 
-        // 转换为ActivityEntity结构
-        let entities = activities
-            .into_iter()
-            .map(|a| ActivityEntity {
-                id: a.id,
-                activity_type: a.activity_type,
-                user_id: a.user_id,
-                group_id: a.group_id,
-                content: a.content,
-                description: a.description,
-                longitude: a.longitude,
-                latitude: a.latitude,
-                created_at: a.created_at,
-            })
-            .collect();
-
-        Ok(entities)
+        Ok(ActivityPage::from_rows(rows, limit))
     }
 
-    /// 获取群组活动
+    /// 按时间倒序分页获取关联到群组、对 `viewer_id` 可见的活动
     pub async fn find_group_activities(
         &self,
+        viewer_id: &str,
         group_id: &str,
+        cursor: Option<&ActivityCursor>,
         limit: i64,
-    ) -> Result<Vec<ActivityEntity>, SqlxError> {
-        let actual_limit = if limit <= 0 { 20 } else { limit };
+    ) -> Result<ActivityPage, SqlxError> {
+        let (before_time, before_id) = cursor_bounds(cursor);
 
-        // 查询与群组相关的活动
-        // 注意：user_activities表中没有直接的group_id字段
-        // 但我们可以查询group相关的活动类型
-        let activities = sqlx::query!(
+        let rows = sqlx::query_as!(
+            ActivityEntity,
             r#"
-            SELECT 
+            SELECT
                 a.activity_id as "id!",
-                CASE 
-                    WHEN a.activity_type = 'USER_CHECKIN' THEN 2
-                    WHEN a.activity_type = 'GROUP_CREATE' THEN 10
-                    WHEN a.activity_type = 'USER_JOINED' THEN 11
-                    WHEN a.activity_type = 'MESSAGE_SENT' THEN 20
-                    ELSE 1
-                END as "activity_type!",
+                a.activity_type as "activity_type: ActivityType",
                 a.user_id as "user_id!",
-                $1::varchar as "group_id",
+                a.group_id as "group_id",
+                a.event_id as "event_id",
                 a.activity_details as "content",
                 COALESCE(a.activity_details, a.activity_type) as "description!",
                 a.longitude as "longitude!",
                 a.latitude as "latitude!",
-                a.created_at as "created_at!"
+                a.created_at as "created_at!",
+                a.visibility as "visibility: ActivityVisibility"
             FROM user_activities a
-            JOIN group_members gm ON a.user_id = gm.user_id
-            WHERE gm.group_id = $1
-              AND a.activity_type IN ('GROUP_CREATE', 'USER_JOINED', 'MESSAGE_SENT')
-            ORDER BY a.created_at DESC
-            LIMIT $2
+            WHERE a.group_id = $1
+              AND ($2::timestamptz IS NULL OR (a.created_at, a.activity_id) < ($2, $3::varchar))
+              AND activity_visible_to(a.user_id, a.visibility, a.group_id, $5)
+            ORDER BY a.created_at DESC, a.activity_id DESC
+            LIMIT $4
             "#,
             group_id,
-            actual_limit
+            before_time,
+            before_id,
+            limit + 1,
+            viewer_id
         )
         .fetch_all(&*self.db)
         .await?;
 
-        // 转换为ActivityEntity结构
-        let entities = activities
-            .into_iter()
-            .map(|a| ActivityEntity {
-                id: a.id,
-                activity_type: a.activity_type,
-                user_id: a.user_id,
-                group_id: a.group_id.map(|id| id),
-                content: a.content,
-                description: a.description,
-                longitude: a.longitude,
-                latitude: a.latitude,
-                created_at: a.created_at,
-            })
-            .collect();
+        Ok(ActivityPage::from_rows(rows, limit))
+    }
 
-        Ok(entities)
+    /// 根据ID获取活动，对 `viewer_id` 不可见时返回 `None`
+    pub async fn find_visible_by_id(
+        &self,
+        activity_id: &str,
+        viewer_id: &str,
+    ) -> Result<Option<ActivityEntity>, SqlxError> {
+        sqlx::query_as!(
+            ActivityEntity,
+            r#"
+            SELECT
+                a.activity_id as "id!",
+                a.activity_type as "activity_type: ActivityType",
+                a.user_id as "user_id!",
+                a.group_id as "group_id",
+                a.event_id as "event_id",
+                a.activity_details as "content",
+                COALESCE(a.activity_details, a.activity_type) as "description!",
+                a.longitude as "longitude!",
+                a.latitude as "latitude!",
+                a.created_at as "created_at!",
+                a.visibility as "visibility: ActivityVisibility"
+            FROM user_activities a
+            WHERE a.activity_id = $1
+              AND activity_visible_to(a.user_id, a.visibility, a.group_id, $2)
+            "#,
+            activity_id,
+            viewer_id
+        )
+        .fetch_optional(&*self.db)
+        .await
     }
 
-    /// 查找附近用户
-    pub async fn find_nearby_users(
+    /// 从给定的活动ID中筛选出 `viewer_id` 可见的活动
+    pub async fn filter_visible(
         &self,
-        latitude: f64,
-        longitude: f64,
-        radius: f64,
-        limit: i64,
-    ) -> Result<Vec<NearbyUserActivity>, SqlxError> {
-        let actual_limit = if limit <= 0 { 20 } else { limit };
+        activity_ids: &[String],
+        viewer_id: &str,
+    ) -> Result<Vec<String>, SqlxError> {
+        if activity_ids.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        // 使用PostGIS进行精确的地理空间查询
-        let nearby_users = sqlx::query!(
+        let rows = sqlx::query!(
             r#"
-            WITH recent_activities AS (
-                SELECT DISTINCT ON (user_id)
-                    user_id,
-                    activity_id,
-                    activity_type,
-                    activity_details,
-                    created_at,
-                    latitude,
-                    longitude
-                FROM user_activities
-                ORDER BY user_id, created_at DESC
-            )
-            SELECT 
-                u.user_id,
-                u.nickname,
-                ra.activity_id as last_activity_id,
-                ra.activity_type as last_activity_type,
-                ra.activity_details as last_activity_description,
-                ra.created_at as last_activity_time,
-                ra.latitude,
-                ra.longitude,
-                -- 使用PostGIS计算精确的球面距离（米）
-                ST_Distance(
-                    ST_SetSRID(ST_MakePoint(ra.longitude, ra.latitude), 4326)::geography,
-                    ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography
-                ) AS distance
-            FROM users u
-            JOIN recent_activities ra ON u.user_id = ra.user_id
-            WHERE ST_DWithin(
-                ST_SetSRID(ST_MakePoint(ra.longitude, ra.latitude), 4326)::geography,
-                ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography,
-                $3
-            )
-            ORDER BY distance
-            LIMIT $4
+            SELECT a.activity_id
+            FROM user_activities a
+            WHERE a.activity_id = ANY($1)
+              AND activity_visible_to(a.user_id, a.visibility, a.group_id, $2)
             "#,
-            latitude,
-            longitude,
-            radius,  // 以米为单位的半径
-            actual_limit
+            activity_ids,
+            viewer_id
         )
         .fetch_all(&*self.db)
         .await?;
 
-        // 转换为NearbyUserActivity结构
-        let result = nearby_users
-            .into_iter()
-            .map(|u| NearbyUserActivity {
-                user_id: u.user_id,
-                nickname: u.nickname,
-                last_activity_id: Some(u.last_activity_id),
-                last_activity_type: Some(u.last_activity_type),
-                last_activity_description: u.last_activity_description,
-                last_activity_time: Some(u.last_activity_time),
-                distance: u.distance,
-            })
-            .collect();
+        Ok(rows.into_iter().map(|r| r.activity_id).collect())
+    }
 
-        Ok(result)
+    /// 批量获取多个用户各自最近的活动（每个用户最多 `per_user` 条），用于拼装首页信息流
+    ///
+    /// 结果在多个查看者之间共享缓存，不按可见范围过滤，由调用方通过 [`Self::filter_visible`] 过滤。
+    pub async fn find_recent_by_users(
+        &self,
+        user_ids: &[String],
+        per_user: i64,
+    ) -> Result<Vec<ActivityEntity>, SqlxError> {
+        sqlx::query_as!(
+            ActivityEntity,
+            r#"
+            SELECT
+                a.activity_id as "id!",
+                a.activity_type as "activity_type: ActivityType",
+                a.user_id as "user_id!",
+                a.group_id as "group_id",
+                a.event_id as "event_id",
+                a.activity_details as "content",
+                COALESCE(a.activity_details, a.activity_type) as "description!",
+                a.longitude as "longitude!",
+                a.latitude as "latitude!",
+                a.created_at as "created_at!",
+                a.visibility as "visibility: ActivityVisibility"
+            FROM unnest($1::varchar[]) AS src(user_id)
+            CROSS JOIN LATERAL (
+                SELECT *
+                FROM user_activities ua
+                WHERE ua.user_id = src.user_id
+                ORDER BY ua.created_at DESC, ua.activity_id DESC
+                LIMIT $2
+            ) a
+            "#,
+            user_ids,
+            per_user
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 批量获取多个群组各自最近的活动（每个群组最多 `per_group` 条），用于拼装首页信息流
+    ///
+    /// 与 [`Self::find_recent_by_users`] 一样不按可见范围过滤。
+    pub async fn find_recent_by_groups(
+        &self,
+        group_ids: &[String],
+        per_group: i64,
+    ) -> Result<Vec<ActivityEntity>, SqlxError> {
+        sqlx::query_as!(
+            ActivityEntity,
+            r#"
+            SELECT
+                a.activity_id as "id!",
+                a.activity_type as "activity_type: ActivityType",
+                a.user_id as "user_id!",
+                a.group_id as "group_id",
+                a.event_id as "event_id",
+                a.activity_details as "content",
+                COALESCE(a.activity_details, a.activity_type) as "description!",
+                a.longitude as "longitude!",
+                a.latitude as "latitude!",
+                a.created_at as "created_at!",
+                a.visibility as "visibility: ActivityVisibility"
+            FROM unnest($1::varchar[]) AS src(group_id)
+            CROSS JOIN LATERAL (
+                SELECT *
+                FROM user_activities ua
+                WHERE ua.group_id = src.group_id
+                ORDER BY ua.created_at DESC, ua.activity_id DESC
+                LIMIT $2
+            ) a
+            "#,
+            group_ids,
+            per_group
+        )
+        .fetch_all(&*self.db)
+        .await
     }
 
     /// 删除活动
@@ -430,3 +721,11 @@ impl ActivityOperation {
         Ok(result.rows_affected() > 0)
     }
 }
+
+/// 游标对应的 (时间, 活动ID) 上界，没有游标时均为空
+fn cursor_bounds(cursor: Option<&ActivityCursor>) -> (Option<DateTime<Utc>>, Option<&str>) {
+    (
+        cursor.map(|c| c.created_at),
+        cursor.map(|c| c.activity_id.as_str()),
+    )
+}
@@ -0,0 +1,361 @@
+// 群组聚会存储库
+// 包含聚会、回复以及聚会提醒相关的数据库操作
+
+use crate::database::models::activity::ActivityType;
+use crate::database::models::event::{EventEntity, NewEvent, RsvpStatus};
+use crate::database::operations::activity::ActivityOperation;
+use sqlx::{Error as SqlxError, PgPool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 群组聚会存储库
+pub struct EventOperation {
+    db: Arc<PgPool>,
+}
+
+impl EventOperation {
+    /// 创建新的群组聚会存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 创建聚会
+    ///
+    /// 未指定地点时使用群组的位置。聚会和创建聚会的群组活动在同一事务中写入。
+    /// 群组不存在或已归档时返回 `None`。
+    pub async fn create_event(
+        &self,
+        new_event: &NewEvent<'_>,
+    ) -> Result<Option<String>, SqlxError> {
+        let event_id = Uuid::new_v4().to_string();
+        let (latitude, longitude) = new_event.location.unzip();
+
+        let mut tx = self.db.begin().await?;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO group_events (
+                event_id, group_id, creator_id, title, description, starts_at, ends_at,
+                location_name, latitude, longitude
+            )
+            SELECT
+                $1, g.group_id, $3, $4, $5, $6, $7,
+                COALESCE($8, g.location_name), COALESCE($9, g.latitude), COALESCE($10, g.longitude)
+            FROM groups g
+            WHERE g.group_id = $2 AND g.archived_at IS NULL
+            "#,
+            event_id,
+            new_event.group_id,
+            new_event.creator_id,
+            new_event.title,
+            new_event.description.as_deref(),
+            new_event.starts_at,
+            new_event.ends_at,
+            new_event.location_name,
+            latitude,
+            longitude
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if inserted == 0 {
+            return Ok(None);
+        }
+
+        ActivityOperation::record_event_activity(
+            &mut tx,
+            &event_id,
+            new_event.creator_id,
+            &ActivityType::EventCreated,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(event_id))
+    }
+
+    /// 根据ID获取聚会，包括已取消和已结束的聚会
+    pub async fn find_by_id(
+        &self,
+        event_id: &str,
+        viewer_id: &str,
+    ) -> Result<Option<EventEntity>, SqlxError> {
+        sqlx::query_as!(
+            EventEntity,
+            r#"
+            SELECT
+                e.event_id,
+                e.group_id,
+                g.name as group_name,
+                e.creator_id,
+                e.title,
+                e.description,
+                e.starts_at,
+                e.ends_at,
+                e.location_name,
+                e.latitude,
+                e.longitude,
+                e.created_at,
+                e.cancelled_at,
+                (SELECT COUNT(*) FROM event_rsvps r
+                 WHERE r.event_id = e.event_id AND r.status = 'going') as "going_count!",
+                (SELECT COUNT(*) FROM event_rsvps r
+                 WHERE r.event_id = e.event_id AND r.status = 'maybe') as "maybe_count!",
+                (SELECT r.status FROM event_rsvps r
+                 WHERE r.event_id = e.event_id AND r.user_id = $2) as "my_rsvp: RsvpStatus",
+                NULL::float8 as "distance"
+            FROM group_events e
+            JOIN groups g ON g.group_id = e.group_id
+            WHERE e.event_id = $1
+            "#,
+            event_id,
+            viewer_id
+        )
+        .fetch_optional(&*self.db)
+        .await
+    }
+
+    /// 获取群组的聚会，按开始时间排序
+    ///
+    /// `include_past` 为 `false` 时只返回未结束且未取消的聚会。
+    pub async fn find_by_group(
+        &self,
+        group_id: &str,
+        viewer_id: &str,
+        include_past: bool,
+        limit: i64,
+    ) -> Result<Vec<EventEntity>, SqlxError> {
+        sqlx::query_as!(
+            EventEntity,
+            r#"
+            SELECT
+                e.event_id,
+                e.group_id,
+                g.name as group_name,
+                e.creator_id,
+                e.title,
+                e.description,
+                e.starts_at,
+                e.ends_at,
+                e.location_name,
+                e.latitude,
+                e.longitude,
+                e.created_at,
+                e.cancelled_at,
+                (SELECT COUNT(*) FROM event_rsvps r
+                 WHERE r.event_id = e.event_id AND r.status = 'going') as "going_count!",
+                (SELECT COUNT(*) FROM event_rsvps r
+                 WHERE r.event_id = e.event_id AND r.status = 'maybe') as "maybe_count!",
+                (SELECT r.status FROM event_rsvps r
+                 WHERE r.event_id = e.event_id AND r.user_id = $2) as "my_rsvp: RsvpStatus",
+                NULL::float8 as "distance"
+            FROM group_events e
+            JOIN groups g ON g.group_id = e.group_id
+            WHERE e.group_id = $1
+              AND ($3 OR (e.cancelled_at IS NULL AND e.ends_at > NOW()))
+            ORDER BY e.starts_at, e.event_id
+            LIMIT $4
+            "#,
+            group_id,
+            viewer_id,
+            include_past,
+            limit
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 查找附近即将开始或正在进行的聚会
+    ///
+    /// 已取消、已结束以及所属群组已归档的聚会不出现在结果中。
+    /// 正在进行的排在前面，其余按开始时间和距离排序。
+    pub async fn find_nearby(
+        &self,
+        viewer_id: &str,
+        (latitude, longitude): (f64, f64),
+        radius: f64,
+        limit: i64,
+    ) -> Result<Vec<EventEntity>, SqlxError> {
+        sqlx::query_as!(
+            EventEntity,
+            r#"
+            SELECT
+                e.event_id,
+                e.group_id,
+                g.name as group_name,
+                e.creator_id,
+                e.title,
+                e.description,
+                e.starts_at,
+                e.ends_at,
+                e.location_name,
+                e.latitude,
+                e.longitude,
+                e.created_at,
+                e.cancelled_at,
+                (SELECT COUNT(*) FROM event_rsvps r
+                 WHERE r.event_id = e.event_id AND r.status = 'going') as "going_count!",
+                (SELECT COUNT(*) FROM event_rsvps r
+                 WHERE r.event_id = e.event_id AND r.status = 'maybe') as "maybe_count!",
+                (SELECT r.status FROM event_rsvps r
+                 WHERE r.event_id = e.event_id AND r.user_id = $1) as "my_rsvp: RsvpStatus",
+                ST_Distance(
+                    ST_SetSRID(ST_MakePoint(e.longitude, e.latitude), 4326)::geography,
+                    ST_SetSRID(ST_MakePoint($3, $2), 4326)::geography
+                ) as "distance"
+            FROM group_events e
+            JOIN groups g ON g.group_id = e.group_id
+            WHERE e.cancelled_at IS NULL
+              AND e.ends_at > NOW()
+              AND g.archived_at IS NULL
+              AND ST_DWithin(
+                  ST_SetSRID(ST_MakePoint(e.longitude, e.latitude), 4326)::geography,
+                  ST_SetSRID(ST_MakePoint($3, $2), 4326)::geography,
+                  $4
+              )
+            ORDER BY GREATEST(e.starts_at, NOW()), "distance", e.event_id
+            LIMIT $5
+            "#,
+            viewer_id,
+            latitude,
+            longitude,
+            radius,
+            limit
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 设置用户对聚会的回复，已回复时覆盖
+    pub async fn set_rsvp(
+        &self,
+        event_id: &str,
+        user_id: &str,
+        status: RsvpStatus,
+    ) -> Result<(), SqlxError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO event_rsvps (event_id, user_id, status)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (event_id, user_id)
+            DO UPDATE SET status = EXCLUDED.status, updated_at = NOW()
+            "#,
+            event_id,
+            user_id,
+            status.as_db_str()
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 撤回用户对聚会的回复，返回是否存在该回复
+    pub async fn remove_rsvp(&self, event_id: &str, user_id: &str) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM event_rsvps
+            WHERE event_id = $1 AND user_id = $2
+            "#,
+            event_id,
+            user_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 取消聚会，并通知回复了参加或可能参加的成员（操作者本人除外）
+    ///
+    /// 返回是否取消成功，聚会已取消或已结束时返回 `false`。
+    pub async fn cancel_event(&self, event_id: &str, user_id: &str) -> Result<bool, SqlxError> {
+        let mut tx = self.db.begin().await?;
+
+        let cancelled = sqlx::query!(
+            r#"
+            UPDATE group_events
+            SET cancelled_at = NOW()
+            WHERE event_id = $1 AND cancelled_at IS NULL AND ends_at > NOW()
+            "#,
+            event_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if cancelled == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO notifications (user_id, kind, payload)
+            SELECT
+                r.user_id,
+                'event_cancelled',
+                jsonb_build_object(
+                    'event_id', e.event_id,
+                    'group_id', e.group_id,
+                    'title', e.title,
+                    'starts_at', e.starts_at
+                )
+            FROM group_events e
+            JOIN event_rsvps r ON r.event_id = e.event_id
+            WHERE e.event_id = $1
+              AND r.status IN ('going', 'maybe')
+              AND r.user_id <> $2
+            "#,
+            event_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// 为即将开始的聚会发送提醒
+    ///
+    /// 开始时间在 `lead_secs` 秒内且尚未提醒过的聚会，向回复了参加或可能参加的成员
+    /// 写入一条通知，并标记为已提醒。返回写入的通知数量。
+    pub async fn send_due_reminders(&self, lead_secs: u64) -> Result<u64, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            WITH due AS (
+                UPDATE group_events
+                SET reminder_sent_at = NOW()
+                WHERE reminder_sent_at IS NULL
+                  AND cancelled_at IS NULL
+                  AND starts_at > NOW()
+                  AND starts_at <= NOW() + make_interval(secs => $1::float8)
+                RETURNING event_id, group_id, title, starts_at, location_name
+            )
+            INSERT INTO notifications (user_id, kind, payload)
+            SELECT
+                r.user_id,
+                'event_reminder',
+                jsonb_build_object(
+                    'event_id', d.event_id,
+                    'group_id', d.group_id,
+                    'title', d.title,
+                    'starts_at', d.starts_at,
+                    'location_name', d.location_name,
+                    'rsvp', r.status
+                )
+            FROM due d
+            JOIN event_rsvps r ON r.event_id = d.event_id
+            WHERE r.status IN ('going', 'maybe')
+            "#,
+            lead_secs as f64
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
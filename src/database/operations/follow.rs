@@ -0,0 +1,146 @@
+// 关注关系存储库
+// 包含用户关注相关的数据库操作
+
+use crate::database::models::follow::FollowUserEntity;
+use sqlx::{Error as SqlxError, PgPool};
+use std::sync::Arc;
+
+/// 关注关系存储库
+pub struct FollowOperation {
+    db: Arc<PgPool>,
+}
+
+impl FollowOperation {
+    /// 创建新的关注关系存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 关注用户
+    ///
+    /// 已关注 `max_following` 个用户时不再写入，返回 `false`。已关注时直接返回 `true`。
+    pub async fn follow(
+        &self,
+        follower_id: &str,
+        followee_id: &str,
+        max_following: i64,
+    ) -> Result<bool, SqlxError> {
+        if self.is_following(follower_id, followee_id).await? {
+            return Ok(true);
+        }
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO user_follows (follower_id, followee_id)
+            SELECT $1::varchar, $2::varchar
+            WHERE (SELECT COUNT(*) FROM user_follows WHERE follower_id = $1) < $3
+            ON CONFLICT (follower_id, followee_id) DO NOTHING
+            "#,
+            follower_id,
+            followee_id,
+            max_following
+        )
+        .execute(&*self.db)
+        .await?
+        .rows_affected();
+
+        // 并发请求可能已经写入，此时同样视为已关注
+        Ok(inserted > 0 || self.is_following(follower_id, followee_id).await?)
+    }
+
+    /// 取消关注，返回是否存在该关注关系
+    pub async fn unfollow(&self, follower_id: &str, followee_id: &str) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM user_follows
+            WHERE follower_id = $1 AND followee_id = $2
+            "#,
+            follower_id,
+            followee_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 是否已关注
+    pub async fn is_following(
+        &self,
+        follower_id: &str,
+        followee_id: &str,
+    ) -> Result<bool, SqlxError> {
+        let exists = sqlx::query!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM user_follows
+                WHERE follower_id = $1 AND followee_id = $2
+            ) as "exists!"
+            "#,
+            follower_id,
+            followee_id
+        )
+        .fetch_one(&*self.db)
+        .await?
+        .exists;
+
+        Ok(exists)
+    }
+
+    /// 获取用户关注的用户ID，按关注时间倒序
+    pub async fn find_followee_ids(
+        &self,
+        follower_id: &str,
+        limit: i64,
+    ) -> Result<Vec<String>, SqlxError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT followee_id
+            FROM user_follows
+            WHERE follower_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+            follower_id,
+            limit
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| r.followee_id).collect())
+    }
+
+    /// 获取用户的关注列表，按关注时间倒序
+    pub async fn find_following(&self, user_id: &str) -> Result<Vec<FollowUserEntity>, SqlxError> {
+        sqlx::query_as!(
+            FollowUserEntity,
+            r#"
+            SELECT u.public_user_id as "public_user_id!", u.nickname, f.created_at as followed_at
+            FROM user_follows f
+            JOIN users u ON u.user_id = f.followee_id
+            WHERE f.follower_id = $1
+            ORDER BY f.created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 获取用户的粉丝列表，按关注时间倒序
+    pub async fn find_followers(&self, user_id: &str) -> Result<Vec<FollowUserEntity>, SqlxError> {
+        sqlx::query_as!(
+            FollowUserEntity,
+            r#"
+            SELECT u.public_user_id as "public_user_id!", u.nickname, f.created_at as followed_at
+            FROM user_follows f
+            JOIN users u ON u.user_id = f.follower_id
+            WHERE f.followee_id = $1
+            ORDER BY f.created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+}
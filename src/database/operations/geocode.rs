@@ -0,0 +1,104 @@
+// 逆地理编码存储库
+// 基于本地导入的行政区划地名库解析坐标，不依赖外部服务
+
+use crate::database::models::geocode::{AdminLevel, ResolvedPlace};
+use sqlx::{Error as SqlxError, PgPool};
+use std::sync::Arc;
+
+/// 逆地理编码存储库
+pub struct GeocodeOperation {
+    db: Arc<PgPool>,
+}
+
+impl GeocodeOperation {
+    /// 创建新的逆地理编码存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 解析单个坐标所在的行政区
+    pub async fn reverse(&self, latitude: f64, longitude: f64) -> Result<ResolvedPlace, SqlxError> {
+        let mut places = self.reverse_many(&[(latitude, longitude)]).await?;
+        Ok(places.pop().unwrap_or_default())
+    }
+
+    /// 批量解析坐标 (纬度, 经度) 所在的行政区，结果与输入顺序一致
+    pub async fn reverse_many(
+        &self,
+        points: &[(f64, f64)],
+    ) -> Result<Vec<ResolvedPlace>, SqlxError> {
+        if points.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (latitudes, longitudes): (Vec<f64>, Vec<f64>) = points.iter().copied().unzip();
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                resolve_admin_area($3, p.latitude, p.longitude, $4) AS country,
+                resolve_admin_area($5, p.latitude, p.longitude, $6) AS province,
+                resolve_admin_area($7, p.latitude, p.longitude, $8) AS city,
+                resolve_admin_area($9, p.latitude, p.longitude, $10) AS district
+            FROM unnest($1::float8[], $2::float8[]) WITH ORDINALITY AS p(latitude, longitude, idx)
+            ORDER BY p.idx
+            "#,
+            &latitudes,
+            &longitudes,
+            AdminLevel::Country.as_db_value(),
+            AdminLevel::Country.max_point_distance(),
+            AdminLevel::Province.as_db_value(),
+            AdminLevel::Province.max_point_distance(),
+            AdminLevel::City.as_db_value(),
+            AdminLevel::City.max_point_distance(),
+            AdminLevel::District.as_db_value(),
+            AdminLevel::District.max_point_distance()
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ResolvedPlace {
+                country: row.country,
+                province: row.province,
+                city: row.city,
+                district: row.district,
+            })
+            .collect())
+    }
+
+    /// 导入一条行政区划数据，返回新记录ID
+    ///
+    /// `geometry` 为 GeoJSON 几何对象，支持 Point、Polygon、MultiPolygon；
+    /// 点位数据没有边界，按距离匹配。
+    pub async fn import_area(
+        &self,
+        level: AdminLevel,
+        name: &str,
+        country_code: Option<&str>,
+        geometry: &str,
+    ) -> Result<i64, SqlxError> {
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO admin_areas (level, name, country_code, boundary, location)
+            SELECT
+                $1, $2, $3,
+                CASE WHEN GeometryType(src.geom) = 'POINT' THEN NULL
+                     ELSE ST_Multi(src.geom)::geography END,
+                CASE WHEN GeometryType(src.geom) = 'POINT' THEN src.geom::geography
+                     ELSE ST_PointOnSurface(src.geom)::geography END
+            FROM (SELECT ST_SetSRID(ST_GeomFromGeoJSON($4), 4326) AS geom) AS src
+            RETURNING area_id
+            "#,
+            level.as_db_value(),
+            name,
+            country_code,
+            geometry
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(record.area_id)
+    }
+}
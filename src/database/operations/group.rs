@@ -1,10 +1,18 @@
 // 群组存储库
 // 包含群组相关的数据库操作
 
-use crate::database::models::group::{CreatorInfo, GroupEntity, GroupWithDetails};
+use crate::database::models::activity::ActivityType;
+use crate::database::models::group::{
+    CreatorInfo, GeofenceCheck, GroupAccess, GroupEntity, GroupJoinRequestEntity,
+    GroupSearchFilter, GroupSortBy, GroupWithDetails, JoinOutcome, NewGroup,
+};
+use crate::database::models::map::MapArea;
+use crate::database::operations::activity::ActivityOperation;
+use crate::database::operations::map::push_intersects;
 use crate::utils::{hash_password, verify_password};
 use chrono::{DateTime, Utc};
-use sqlx::{Error as SqlxError, PgPool};
+use futures_util::stream::BoxStream;
+use sqlx::{Error as SqlxError, PgPool, Postgres, QueryBuilder, Transaction};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -20,20 +28,13 @@ impl GroupOperation {
     }
 
     /// 创建群组
-    pub async fn create(
-        &self,
-        name: &str,
-        location_name: &str,
-        latitude: f64,
-        longitude: f64,
-        description: &str,
-        password: Option<&str>,
-        creator_id: &str,
-    ) -> Result<String, SqlxError> {
+    ///
+    /// 群组记录、创建者的成员关系和创建群组活动在同一事务中写入。
+    pub async fn create(&self, new_group: &NewGroup<'_>) -> Result<String, SqlxError> {
         let group_id = Uuid::new_v4().to_string();
 
         // 处理可选密码
-        let password_hash = match password {
+        let password_hash = match new_group.password {
             Some(pwd) => Some(
                 hash_password(pwd)
                     .map_err(|e| SqlxError::Protocol(format!("Failed to hash password: {}", e)))?,
@@ -41,25 +42,38 @@ impl GroupOperation {
             None => None,
         };
 
+        let mut tx = self.db.begin().await?;
+
         // 创建群组记录
         sqlx::query!(
             r#"
             INSERT INTO groups (
                 group_id, name, location_name, latitude, longitude,
-                description, password_hash, creator_id, created_at, member_count
+                description, password_hash, creator_id, created_at, member_count, expires_at,
+                max_members, category, tags, geofence_radius, geofence, requires_approval
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, NOW(), 1, $9, $10, $11, $12, $13,
+                ST_GeogFromText($14), $15
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), 1)
             "#,
             group_id,
-            name,
-            location_name,
-            latitude,
-            longitude,
-            description,
+            new_group.name,
+            new_group.location_name,
+            new_group.latitude,
+            new_group.longitude,
+            new_group.description,
             password_hash,
-            creator_id,
+            new_group.creator_id,
+            new_group.expires_at,
+            new_group.max_members,
+            new_group.category,
+            new_group.tags,
+            new_group.geofence_radius,
+            new_group.geofence_polygon,
+            new_group.requires_approval,
         )
-        .execute(&*self.db)
+        .execute(&mut *tx)
         .await?;
 
         // 创建者加入群组
@@ -69,19 +83,28 @@ impl GroupOperation {
             VALUES ($1, $2, NOW(), NOW())
             "#,
             group_id,
-            creator_id,
+            new_group.creator_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        ActivityOperation::record_group_activity(
+            &mut tx,
+            &group_id,
+            new_group.creator_id,
+            &ActivityType::GroupCreated,
+            0,
         )
-        .execute(&*self.db)
         .await?;
 
+        tx.commit().await?;
+
         Ok(group_id)
     }
 
-    /// 根据名称查找群组
-    pub async fn find_by_name(&self, name: &str) -> Result<Vec<GroupEntity>, SqlxError> {
-        let search_pattern = format!("%{}%", name);
-
-        let groups = sqlx::query_as!(
+    /// 根据ID查找群组
+    pub async fn find_by_id(&self, group_id: &str) -> Result<Option<GroupEntity>, SqlxError> {
+        let group = sqlx::query_as!(
             GroupEntity,
             r#"
             SELECT 
@@ -94,23 +117,29 @@ impl GroupOperation {
                 password_hash as password, 
                 creator_id, 
                 created_at, 
-                created_at as last_active
+                created_at as last_active,
+                expires_at,
+                archived_at,
+                max_members,
+                category,
+                tags,
+                geofence_radius,
+                (geofence_radius IS NOT NULL OR geofence IS NOT NULL) as "is_geofenced!",
+                requires_approval
             FROM groups
-            WHERE name ILIKE $1
-            ORDER BY created_at DESC
-            LIMIT 20
+            WHERE group_id = $1
             "#,
-            search_pattern
+            group_id
         )
-        .fetch_all(&*self.db)
+        .fetch_optional(&*self.db)
         .await?;
 
-        Ok(groups)
+        Ok(group)
     }
 
-    /// 根据ID查找群组
-    pub async fn find_by_id(&self, group_id: &str) -> Result<Option<GroupEntity>, SqlxError> {
-        let group = sqlx::query_as!(
+    /// 按创建时间逐行读取所有未归档的群组，用于导出
+    pub fn stream_active_groups(&self) -> BoxStream<'_, Result<GroupEntity, SqlxError>> {
+        sqlx::query_as!(
             GroupEntity,
             r#"
             SELECT 
@@ -120,34 +149,50 @@ impl GroupOperation {
                 latitude, 
                 longitude,
                 description, 
-                password_hash as password, 
+                NULL::text as password, 
                 creator_id, 
                 created_at, 
-                created_at as last_active
+                created_at as last_active,
+                expires_at,
+                archived_at,
+                max_members,
+                category,
+                tags,
+                geofence_radius,
+                (geofence_radius IS NOT NULL OR geofence IS NOT NULL) as "is_geofenced!",
+                requires_approval
             FROM groups
-            WHERE group_id = $1
-            "#,
-            group_id
+            WHERE archived_at IS NULL
+            ORDER BY created_at, group_id
+            "#
         )
-        .fetch_optional(&*self.db)
-        .await?;
-
-        Ok(group)
+        .fetch(&*self.db)
     }
 
     /// 添加用户到群组
+    ///
+    /// 人数检查、成员写入和加入群组活动在同一事务中完成，并锁定群组行，
+    /// 避免并发加入时超出人数上限。群组已满时，如果 `join_waitlist` 为真，
+    /// 则将用户加入候补队列，否则返回错误。需要审批的群组只提交加入申请，
+    /// 由管理员通过后再加入。
     pub async fn add_user(
         &self,
         group_id: &str,
         user_id: &str,
         password: Option<&str>,
-    ) -> Result<(), SqlxError> {
+        join_waitlist: bool,
+    ) -> Result<JoinOutcome, SqlxError> {
         // 检查群组是否存在
         let group = self
             .find_by_id(group_id)
             .await?
             .ok_or_else(|| SqlxError::RowNotFound)?;
 
+        // 已归档的群组不再接受新成员
+        if group.archived_at.is_some() {
+            return Err(SqlxError::Protocol("Group is archived".into()));
+        }
+
         // 检查用户是否已经在群组
         let is_member = self.has_user(group_id, user_id).await?;
         if is_member {
@@ -164,7 +209,7 @@ impl GroupOperation {
             .execute(&*self.db)
             .await?;
 
-            return Ok(());
+            return Ok(JoinOutcome::AlreadyMember);
         }
 
         // 检查密码（如果需要）
@@ -181,17 +226,86 @@ impl GroupOperation {
             }
         }
 
+        if group.requires_approval {
+            // 提交加入申请，重复申请保留原来的申请时间
+            sqlx::query!(
+                r#"
+                INSERT INTO group_join_requests (group_id, user_id, requested_at)
+                VALUES ($1, $2, NOW())
+                ON CONFLICT (group_id, user_id) DO NOTHING
+                "#,
+                group_id,
+                user_id
+            )
+            .execute(&*self.db)
+            .await?;
+
+            return Ok(JoinOutcome::PendingApproval);
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        // 锁定群组行，串行化同一群组的加入操作
+        let capacity = sqlx::query!(
+            r#"
+            SELECT member_count, max_members
+            FROM groups
+            WHERE group_id = $1
+            FOR UPDATE
+            "#,
+            group_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| SqlxError::RowNotFound)?;
+
+        let is_full = capacity
+            .max_members
+            .is_some_and(|max| capacity.member_count >= max);
+
+        if is_full {
+            if !join_waitlist {
+                return Err(SqlxError::Protocol("Group is full".into()));
+            }
+
+            // 加入候补队列，重复申请保留原来的排队位置
+            sqlx::query!(
+                r#"
+                INSERT INTO group_waitlist (group_id, user_id, requested_at)
+                VALUES ($1, $2, NOW())
+                ON CONFLICT (group_id, user_id) DO NOTHING
+                "#,
+                group_id,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            let position = Self::waitlist_position(&mut tx, group_id, user_id).await?;
+            tx.commit().await?;
+
+            return Ok(JoinOutcome::Waitlisted(position));
+        }
+
         // 添加用户到群组
-        sqlx::query!(
+        let inserted = sqlx::query!(
             r#"
             INSERT INTO group_members (group_id, user_id, joined_at, last_active)
             VALUES ($1, $2, NOW(), NOW())
+            ON CONFLICT (group_id, user_id) DO NOTHING
             "#,
             group_id,
             user_id
         )
-        .execute(&*self.db)
-        .await?;
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if inserted == 0 {
+            // 并发请求已经把用户加入了群组
+            tx.commit().await?;
+            return Ok(JoinOutcome::AlreadyMember);
+        }
 
         // 更新群组成员数
         sqlx::query!(
@@ -202,10 +316,33 @@ impl GroupOperation {
             "#,
             group_id
         )
-        .execute(&*self.db)
+        .execute(&mut *tx)
         .await?;
 
-        Ok(())
+        // 如果用户之前在候补队列中，移除其排队记录
+        sqlx::query!(
+            r#"
+            DELETE FROM group_waitlist
+            WHERE group_id = $1 AND user_id = $2
+            "#,
+            group_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        ActivityOperation::record_group_activity(
+            &mut tx,
+            group_id,
+            user_id,
+            &ActivityType::UserJoined,
+            0,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(JoinOutcome::Joined)
     }
 
     /// 检查群组是否存在
@@ -245,15 +382,35 @@ impl GroupOperation {
     }
 
     /// 用户离开群组
-    pub async fn remove_user(&self, group_id: &str, user_id: &str) -> Result<(), SqlxError> {
-        // 检查用户是否在群组中
-        let is_member = self.has_user(group_id, user_id).await?;
-        if !is_member {
-            return Ok(()); // 用户不在群组中，无需操作
+    ///
+    /// 空出名额后，按先进先出的顺序从候补队列中补位。离开和补位都会记录活动。
+    /// 返回因此被自动加入群组的用户ID。
+    pub async fn remove_user(
+        &self,
+        group_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<String>, SqlxError> {
+        let mut tx = self.db.begin().await?;
+
+        // 锁定群组行，与加入操作互斥
+        let group = sqlx::query!(
+            r#"
+            SELECT group_id
+            FROM groups
+            WHERE group_id = $1
+            FOR UPDATE
+            "#,
+            group_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if group.is_none() {
+            return Ok(Vec::new());
         }
 
         // 移除用户
-        sqlx::query!(
+        let removed = sqlx::query!(
             r#"
             DELETE FROM group_members
             WHERE group_id = $1 AND user_id = $2
@@ -261,8 +418,13 @@ impl GroupOperation {
             group_id,
             user_id
         )
-        .execute(&*self.db)
-        .await?;
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if removed == 0 {
+            return Ok(Vec::new()); // 用户不在群组中，无需操作
+        }
 
         // 更新群组成员数
         sqlx::query!(
@@ -273,10 +435,411 @@ impl GroupOperation {
             "#,
             group_id
         )
+        .execute(&mut *tx)
+        .await?;
+
+        ActivityOperation::record_group_activity(
+            &mut tx,
+            group_id,
+            user_id,
+            &ActivityType::UserLeft,
+            0,
+        )
+        .await?;
+
+        let admitted = Self::admit_from_waitlist(&mut tx, group_id).await?;
+
+        tx.commit().await?;
+
+        Ok(admitted)
+    }
+
+    /// 从候补队列中按顺序补位，直到群组满员或队列为空
+    ///
+    /// 调用方需要已经在事务中锁定群组行。成员最近一次在围栏内的时间记为申请候补的时间，
+    /// 补位时不会重新获得发言宽限期，需要重新在围栏内签到才能在设置了地理围栏的群组中发言。
+    async fn admit_from_waitlist(
+        tx: &mut Transaction<'_, Postgres>,
+        group_id: &str,
+    ) -> Result<Vec<String>, SqlxError> {
+        let mut admitted = Vec::new();
+
+        loop {
+            let capacity = sqlx::query!(
+                r#"
+                SELECT member_count, max_members
+                FROM groups
+                WHERE group_id = $1
+                "#,
+                group_id
+            )
+            .fetch_one(&mut **tx)
+            .await?;
+
+            if capacity
+                .max_members
+                .is_some_and(|max| capacity.member_count >= max)
+            {
+                break;
+            }
+
+            // 取出队首的申请
+            let next = sqlx::query!(
+                r#"
+                DELETE FROM group_waitlist
+                WHERE (group_id, user_id) = (
+                    SELECT group_id, user_id
+                    FROM group_waitlist
+                    WHERE group_id = $1
+                    ORDER BY requested_at, user_id
+                    LIMIT 1
+                )
+                RETURNING user_id, requested_at
+                "#,
+                group_id
+            )
+            .fetch_optional(&mut **tx)
+            .await?;
+
+            let Some(next) = next else {
+                break;
+            };
+
+            if Self::admit_member(tx, group_id, &next.user_id, next.requested_at).await? {
+                admitted.push(next.user_id);
+            }
+        }
+
+        Ok(admitted)
+    }
+
+    /// 将候补或审批通过的用户加入群组，更新成员数并记录加入群组活动
+    ///
+    /// `last_in_fence_at` 为用户最近一次确认在围栏内的时间（申请时间），不给予新的发言宽限期。
+    /// 用户已是成员时不做任何修改，返回 `false`。
+    async fn admit_member(
+        tx: &mut Transaction<'_, Postgres>,
+        group_id: &str,
+        user_id: &str,
+        last_in_fence_at: DateTime<Utc>,
+    ) -> Result<bool, SqlxError> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO group_members (group_id, user_id, joined_at, last_active, last_in_fence_at)
+            VALUES ($1, $2, NOW(), NOW(), $3)
+            ON CONFLICT (group_id, user_id) DO NOTHING
+            "#,
+            group_id,
+            user_id,
+            last_in_fence_at
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        if inserted == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE groups
+            SET member_count = member_count + 1
+            WHERE group_id = $1
+            "#,
+            group_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        ActivityOperation::record_group_activity(
+            tx,
+            group_id,
+            user_id,
+            &ActivityType::UserJoined,
+            0,
+        )
+        .await?;
+
+        Ok(true)
+    }
+
+    /// 获取用户在候补队列中的位置（从1开始）
+    async fn waitlist_position(
+        tx: &mut Transaction<'_, Postgres>,
+        group_id: &str,
+        user_id: &str,
+    ) -> Result<i64, SqlxError> {
+        let position = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "position!"
+            FROM group_waitlist w
+            JOIN group_waitlist me ON me.group_id = w.group_id AND me.user_id = $2
+            WHERE w.group_id = $1
+              AND (w.requested_at, w.user_id) <= (me.requested_at, me.user_id)
+            "#,
+            group_id,
+            user_id
+        )
+        .fetch_one(&mut **tx)
+        .await?
+        .position;
+
+        Ok(position)
+    }
+
+    /// 用户退出候补队列
+    pub async fn leave_waitlist(&self, group_id: &str, user_id: &str) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM group_waitlist
+            WHERE group_id = $1 AND user_id = $2
+            "#,
+            group_id,
+            user_id
+        )
         .execute(&*self.db)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 获取群组待审批的加入申请，按申请时间排序
+    pub async fn find_join_requests(
+        &self,
+        group_id: &str,
+    ) -> Result<Vec<GroupJoinRequestEntity>, SqlxError> {
+        sqlx::query_as!(
+            GroupJoinRequestEntity,
+            r#"
+            SELECT u.public_user_id, u.nickname, r.requested_at
+            FROM group_join_requests r
+            JOIN users u ON u.user_id = r.user_id
+            WHERE r.group_id = $1
+            ORDER BY r.requested_at, r.user_id
+            "#,
+            group_id
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 通过加入申请，申请者为公开ID为 `public_user_id` 的用户
+    ///
+    /// 与加入群组一样锁定群组行；群组已满时申请者按原申请时间进入候补队列。
+    /// 申请不存在时返回 `None`，已归档的群组不再接受新成员。
+    pub async fn approve_join_request(
+        &self,
+        group_id: &str,
+        public_user_id: &str,
+    ) -> Result<Option<JoinOutcome>, SqlxError> {
+        let mut tx = self.db.begin().await?;
+
+        let capacity = sqlx::query!(
+            r#"
+            SELECT member_count, max_members, archived_at IS NOT NULL as "archived!"
+            FROM groups
+            WHERE group_id = $1
+            FOR UPDATE
+            "#,
+            group_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(capacity) = capacity else {
+            return Ok(None);
+        };
+        if capacity.archived {
+            return Err(SqlxError::Protocol("Group is archived".into()));
+        }
+
+        let request = sqlx::query!(
+            r#"
+            DELETE FROM group_join_requests r
+            USING users u
+            WHERE r.group_id = $1 AND r.user_id = u.user_id AND u.public_user_id = $2
+            RETURNING r.user_id, r.requested_at
+            "#,
+            group_id,
+            public_user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(request) = request else {
+            return Ok(None);
+        };
+
+        let is_full = capacity
+            .max_members
+            .is_some_and(|max| capacity.member_count >= max);
+
+        let outcome = if is_full {
+            sqlx::query!(
+                r#"
+                INSERT INTO group_waitlist (group_id, user_id, requested_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (group_id, user_id) DO NOTHING
+                "#,
+                group_id,
+                request.user_id,
+                request.requested_at
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            JoinOutcome::Waitlisted(
+                Self::waitlist_position(&mut tx, group_id, &request.user_id).await?,
+            )
+        } else if Self::admit_member(&mut tx, group_id, &request.user_id, request.requested_at)
+            .await?
+        {
+            JoinOutcome::Joined
+        } else {
+            JoinOutcome::AlreadyMember
+        };
+
+        tx.commit().await?;
+
+        Ok(Some(outcome))
+    }
+
+    /// 拒绝加入申请，申请者为公开ID为 `public_user_id` 的用户，申请不存在时返回 `false`
+    pub async fn reject_join_request(
+        &self,
+        group_id: &str,
+        public_user_id: &str,
+    ) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM group_join_requests r
+            USING users u
+            WHERE r.group_id = $1 AND r.user_id = u.user_id AND u.public_user_id = $2
+            "#,
+            group_id,
+            public_user_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 用户撤回自己的加入申请
+    pub async fn cancel_join_request(
+        &self,
+        group_id: &str,
+        user_id: &str,
+    ) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM group_join_requests
+            WHERE group_id = $1 AND user_id = $2
+            "#,
+            group_id,
+            user_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 检查位置是否在群组的地理围栏内
+    ///
+    /// 设置了多边形时判断点是否被多边形覆盖，否则判断与群组位置的距离是否在围栏半径内。
+    /// 未提供位置视为在围栏外；群组不存在时视为未设置围栏，由后续操作报告群组不存在。
+    pub async fn check_geofence(
+        &self,
+        group_id: &str,
+        location: Option<(f64, f64)>,
+    ) -> Result<GeofenceCheck, SqlxError> {
+        let (latitude, longitude) = location.unzip();
+
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                (geofence_radius IS NOT NULL OR geofence IS NOT NULL) as "fenced!",
+                COALESCE(
+                    CASE
+                        WHEN geofence IS NOT NULL THEN ST_Covers(
+                            geofence,
+                            ST_SetSRID(ST_MakePoint($3, $2), 4326)::geography
+                        )
+                        ELSE ST_DWithin(
+                            geom,
+                            ST_SetSRID(ST_MakePoint($3, $2), 4326)::geography,
+                            geofence_radius::float8
+                        )
+                    END,
+                    FALSE
+                ) as "inside!"
+            FROM groups
+            WHERE group_id = $1
+            "#,
+            group_id,
+            latitude as Option<f64>,
+            longitude as Option<f64>
+        )
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(match row {
+            Some(row) if row.fenced && row.inside => GeofenceCheck::Inside,
+            Some(row) if row.fenced => GeofenceCheck::Outside,
+            _ => GeofenceCheck::Unfenced,
+        })
+    }
+
+    /// 检查成员当前是否可以在设置了地理围栏的群组中发言
+    ///
+    /// 成员在围栏内时刷新其在围栏内的时间；在围栏外时，只要距离上次在围栏内
+    /// 不超过宽限期仍可发言。未设置围栏的群组始终允许。
+    pub async fn check_presence(
+        &self,
+        group_id: &str,
+        user_id: &str,
+        location: Option<(f64, f64)>,
+        grace_secs: i64,
+    ) -> Result<bool, SqlxError> {
+        match self.check_geofence(group_id, location).await? {
+            GeofenceCheck::Unfenced => Ok(true),
+            GeofenceCheck::Inside => {
+                sqlx::query!(
+                    r#"
+                    UPDATE group_members
+                    SET last_in_fence_at = NOW()
+                    WHERE group_id = $1 AND user_id = $2
+                    "#,
+                    group_id,
+                    user_id
+                )
+                .execute(&*self.db)
+                .await?;
+
+                Ok(true)
+            }
+            GeofenceCheck::Outside => {
+                let within_grace = sqlx::query!(
+                    r#"
+                    SELECT EXISTS(
+                        SELECT 1 FROM group_members
+                        WHERE group_id = $1 AND user_id = $2
+                        AND last_in_fence_at > NOW() - make_interval(secs => $3::float8)
+                    ) as "exists!"
+                    "#,
+                    group_id,
+                    user_id,
+                    grace_secs as f64
+                )
+                .fetch_one(&*self.db)
+                .await?
+                .exists;
+
+                Ok(within_grace)
+            }
+        }
     }
 
     /// 获取群组成员数量
@@ -371,7 +934,15 @@ impl GroupOperation {
                 g.password_hash as password, 
                 g.creator_id, 
                 g.created_at, 
-                gm.last_active as "last_active!"
+                gm.last_active as "last_active!",
+                g.expires_at,
+                g.archived_at,
+                g.max_members,
+                g.category,
+                g.tags,
+                g.geofence_radius,
+                (g.geofence_radius IS NOT NULL OR g.geofence IS NOT NULL) as "is_geofenced!",
+                g.requires_approval
             FROM groups g
             JOIN group_members gm ON g.group_id = gm.group_id
             WHERE gm.user_id = $1
@@ -461,22 +1032,150 @@ impl GroupOperation {
         Ok(())
     }
 
-    /// 根据位置查找附近的群组（详细信息版本）
+    /// 根据位置查找附近的群组（详细信息版本），同时返回满足条件的群组总数
     pub async fn find_by_location_with_details(
         &self,
         latitude: f64,
         longitude: f64,
         radius: f64,
-    ) -> Result<Vec<GroupWithDetails>, SqlxError> {
+        filter: &GroupSearchFilter,
+    ) -> Result<(Vec<GroupWithDetails>, i64), SqlxError> {
         tracing::debug!(
-            "查询详细的附近群组，位置: [{}, {}], 半径: {}米",
+            "查询详细的附近群组，位置: [{}, {}], 半径: {}米, 条件: {:?}",
             latitude,
             longitude,
-            radius
+            radius,
+            filter
         );
 
-        // 构建查询，使用PostGIS获取详细信息
-        let query = "
+        let (rows, total) = self
+            .fetch_details_page(filter, |filter| {
+                let mut query = Self::details_query(Some((latitude, longitude)), None);
+                query
+                    .push(
+                        " AND ST_DWithin(ST_SetSRID(ST_MakePoint(g.longitude, g.latitude), 4326)::geography, ST_SetSRID(ST_MakePoint(",
+                    )
+                    .push_bind(longitude)
+                    .push(", ")
+                    .push_bind(latitude)
+                    .push("), 4326)::geography, ")
+                    .push_bind(radius)
+                    .push(")");
+                Self::push_filter(&mut query, filter, true, false);
+                query
+            })
+            .await
+            .map_err(|e| {
+                tracing::error!("查询附近群组详情失败: {}", e);
+                e
+            })?;
+
+        tracing::debug!("找到 {} 个附近群组", rows.len());
+        Ok((rows, total))
+    }
+
+    /// 根据名称模糊查找群组（详细信息版本），同时返回满足条件的群组总数
+    ///
+    /// 使用 `pg_trgm` 的相似度匹配容忍错别字，同时保留子串匹配以支持
+    /// 部分中文名称。完全匹配排在最前，其次是前缀匹配、子串匹配，
+    /// 同一档内按相似度排序；提供位置时，距离越近得分越高。
+    pub async fn find_by_name_with_details(
+        &self,
+        name: &str,
+        origin: Option<(f64, f64)>,
+        filter: &GroupSearchFilter,
+    ) -> Result<(Vec<GroupWithDetails>, i64), SqlxError> {
+        let name = name.trim();
+        let contains_pattern = format!("%{}%", escape_like(name));
+
+        self.fetch_details_page(filter, |filter| {
+            let mut query = Self::details_query(origin, Some(name));
+            query
+                .push(" AND (g.name ILIKE ")
+                .push_bind(contains_pattern.clone())
+                .push(" OR g.name % ")
+                .push_bind(name.to_string())
+                .push(")");
+            Self::push_filter(&mut query, filter, origin.is_some(), true);
+            query
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("按名称查询群组详情失败: {}", e);
+            e
+        })
+    }
+
+    /// 查找地图区域内的群组（详细信息版本），按成员数量排序，同时返回满足条件的群组总数
+    pub async fn find_in_area_with_details(
+        &self,
+        area: &MapArea,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<GroupWithDetails>, i64), SqlxError> {
+        let filter = GroupSearchFilter {
+            sort_by: GroupSortBy::MemberCount,
+            offset,
+            limit,
+            ..Default::default()
+        };
+
+        self.fetch_details_page(&filter, |filter| {
+            let mut query = Self::details_query(None, None);
+            push_intersects(&mut query, "g.geom", area);
+            Self::push_filter(&mut query, filter, false, false);
+            query
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("查询地图区域内的群组失败: {}", e);
+            e
+        })
+    }
+
+    /// 执行群组详情查询，返回当前页的群组和满足条件的群组总数
+    ///
+    /// 总数取自 `COUNT(*) OVER ()`。页码超出范围时当前页为空，
+    /// 改为查询第一页的第一行以取得总数。
+    async fn fetch_details_page(
+        &self,
+        filter: &GroupSearchFilter,
+        build: impl Fn(&GroupSearchFilter) -> QueryBuilder<'static, Postgres>,
+    ) -> Result<(Vec<GroupWithDetails>, i64), SqlxError> {
+        let rows = build(filter)
+            .build_query_as::<GroupWithDetails>()
+            .fetch_all(self.db.as_ref())
+            .await?;
+
+        let total = match rows.first() {
+            Some(row) => row.total_count,
+            None if filter.offset > 0 => {
+                let first = GroupSearchFilter {
+                    offset: 0,
+                    limit: 1,
+                    ..filter.clone()
+                };
+                build(&first)
+                    .build_query_as::<GroupWithDetails>()
+                    .fetch_optional(self.db.as_ref())
+                    .await?
+                    .map_or(0, |row| row.total_count)
+            }
+            None => 0,
+        };
+
+        Ok((rows, total))
+    }
+
+    /// 构建群组详情查询的公共部分，调用方在其后追加 `AND ...` 条件
+    ///
+    /// 提供 `origin` 时计算距离，提供 `search` 时计算名称匹配得分。
+    fn details_query(
+        origin: Option<(f64, f64)>,
+        search: Option<&str>,
+    ) -> QueryBuilder<'static, Postgres> {
+        let mut query = QueryBuilder::new(
+            "
             WITH group_counts AS (
                 SELECT 
                     group_id, 
@@ -505,37 +1204,141 @@ impl GroupOperation {
                 COALESCE(gc.member_count, 0) as member_count,
                 COALESCE(gla.last_active_at, g.created_at) as last_active_at,
                 u.public_user_id as creator_public_id,
-                ST_Distance(
-                    ST_SetSRID(ST_MakePoint(g.longitude, g.latitude), 4326)::geography,
-                    ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography
-                ) as distance
+                g.expires_at,
+                g.max_members,
+                g.category,
+                g.tags,
+                g.password_hash IS NOT NULL as is_password_required,
+                g.geofence_radius,
+                (g.geofence_radius IS NOT NULL OR g.geofence IS NOT NULL) as is_geofenced,
+                g.requires_approval,
+                COUNT(*) OVER () as total_count,
+            ",
+        );
+
+        match origin {
+            Some(origin) => {
+                Self::push_distance(&mut query, origin);
+                query.push(" as distance, ");
+            }
+            None => {
+                query.push("NULL::float8 as distance, ");
+            }
+        }
+
+        match search {
+            Some(name) => {
+                // 完全匹配 > 前缀匹配 > 子串匹配，同档内按相似度排序
+                query
+                    .push("(CASE WHEN lower(g.name) = lower(")
+                    .push_bind(name.to_string())
+                    .push(") THEN 6 WHEN g.name ILIKE ")
+                    .push_bind(format!("{}%", escape_like(name)))
+                    .push(" THEN 4 WHEN g.name ILIKE ")
+                    .push_bind(format!("%{}%", escape_like(name)))
+                    .push(" THEN 2 ELSE 0 END + similarity(g.name, ")
+                    .push_bind(name.to_string())
+                    .push(")");
+
+                // 距离加成在 (0, 1] 之间，不会改变匹配档位
+                if let Some(origin) = origin {
+                    query.push(" + 1.0 / (1.0 + ");
+                    Self::push_distance(&mut query, origin);
+                    query.push(" / 1000.0)");
+                }
+
+                query.push(")::float8 as score");
+            }
+            None => {
+                query.push("NULL::float8 as score");
+            }
+        }
+
+        query.push(
+            "
             FROM groups g
             LEFT JOIN group_counts gc ON g.group_id = gc.group_id
             LEFT JOIN group_last_activity gla ON g.group_id = gla.group_id
             LEFT JOIN users u ON g.creator_id = u.user_id
             LEFT JOIN user_profiles p ON g.creator_id = p.user_id
-            WHERE ST_DWithin(
-                ST_SetSRID(ST_MakePoint(g.longitude, g.latitude), 4326)::geography,
-                ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography,
-                $3
+            WHERE g.archived_at IS NULL
+            ",
+        );
+
+        query
+    }
+
+    /// 追加群组与指定位置之间的球面距离（米）表达式
+    fn push_distance(
+        query: &mut QueryBuilder<'static, Postgres>,
+        (latitude, longitude): (f64, f64),
+    ) {
+        query
+            .push(
+                "ST_Distance(ST_SetSRID(ST_MakePoint(g.longitude, g.latitude), 4326)::geography, ST_SetSRID(ST_MakePoint(",
             )
-            ORDER BY distance
-            LIMIT 50
-        ";
-
-        let rows = sqlx::query_as::<_, GroupWithDetails>(query)
-            .bind(longitude) 
-            .bind(latitude)
-            .bind(radius)
-            .fetch_all(self.db.as_ref())
-            .await
-            .map_err(|e| {
-                tracing::error!("查询附近群组详情失败: {}", e);
-                e
-            })?;
+            .push_bind(longitude)
+            .push(", ")
+            .push_bind(latitude)
+            .push("), 4326)::geography)");
+    }
 
-        tracing::debug!("找到 {} 个附近群组", rows.len());
-        Ok(rows)
+    /// 追加过滤、排序与分页条件
+    fn push_filter(
+        query: &mut QueryBuilder<'static, Postgres>,
+        filter: &GroupSearchFilter,
+        has_origin: bool,
+        has_search: bool,
+    ) {
+        if !filter.tags.is_empty() {
+            query.push(" AND g.tags @> ").push_bind(filter.tags.clone());
+        }
+
+        if let Some(category) = &filter.category {
+            query.push(" AND g.category = ").push_bind(category.clone());
+        }
+
+        match filter.access {
+            Some(GroupAccess::Open) => {
+                query.push(" AND g.password_hash IS NULL AND NOT g.requires_approval");
+            }
+            Some(GroupAccess::Password) => {
+                query.push(" AND g.password_hash IS NOT NULL");
+            }
+            Some(GroupAccess::Approval) => {
+                query.push(" AND g.requires_approval");
+            }
+            None => {}
+        }
+
+        if let Some(min_members) = filter.min_members {
+            query
+                .push(" AND COALESCE(gc.member_count, 0) >= ")
+                .push_bind(min_members);
+        }
+
+        if let Some(hours) = filter.active_within_hours {
+            query
+                .push(" AND COALESCE(gla.last_active_at, g.created_at) >= NOW() - make_interval(hours => ")
+                .push_bind(hours)
+                .push(")");
+        }
+
+        let order_by = match filter.sort_by {
+            GroupSortBy::Relevance if has_search => " ORDER BY score DESC, g.group_id",
+            GroupSortBy::Distance | GroupSortBy::Relevance if has_origin => {
+                " ORDER BY distance, g.group_id"
+            }
+            GroupSortBy::MemberCount => " ORDER BY member_count DESC, g.group_id",
+            _ => " ORDER BY last_active_at DESC, g.group_id",
+        };
+        query.push(order_by);
+
+        query
+            .push(" LIMIT ")
+            .push_bind(filter.limit)
+            .push(" OFFSET ")
+            .push_bind(filter.offset);
     }
 
     /// 获取群组成员列表（包含公开ID）
@@ -575,66 +1378,6 @@ impl GroupOperation {
         Ok(members)
     }
 
-    /// 根据名称查找群组，同时获取创建者信息
-    pub async fn find_by_name_with_creator(
-        &self,
-        name: &str,
-    ) -> Result<Vec<(GroupEntity, CreatorInfo)>, SqlxError> {
-        let search_pattern = format!("%{}%", name);
-
-        let results = sqlx::query!(
-            r#"
-            SELECT 
-                g.group_id as id, 
-                g.name, 
-                g.location_name, 
-                g.latitude, 
-                g.longitude,
-                g.description, 
-                g.password_hash as password, 
-                g.creator_id, 
-                g.created_at, 
-                g.created_at as last_active,
-                u.nickname as creator_nickname,
-                u.public_user_id as creator_public_id
-            FROM groups g
-            JOIN users u ON g.creator_id = u.user_id
-            WHERE g.name ILIKE $1
-            ORDER BY g.created_at DESC
-            LIMIT 20
-            "#,
-            search_pattern
-        )
-        .fetch_all(&*self.db)
-        .await?;
-
-        let mut groups_with_creators = Vec::new();
-
-        for row in results {
-            let group = GroupEntity {
-                id: row.id,
-                name: row.name,
-                location_name: row.location_name,
-                latitude: row.latitude,
-                longitude: row.longitude,
-                description: Some(row.description),
-                password: row.password,
-                creator_id: row.creator_id,
-                created_at: row.created_at,
-                last_active: row.last_active,
-            };
-
-            let creator = CreatorInfo {
-                nickname: row.creator_nickname,
-                public_user_id: row.creator_public_id,
-            };
-
-            groups_with_creators.push((group, creator));
-        }
-
-        Ok(groups_with_creators)
-    }
-
     /// 根据ID查找群组和创建者信息
     pub async fn find_by_id_with_creator(
         &self,
@@ -653,6 +1396,14 @@ impl GroupOperation {
                 g.creator_id, 
                 g.created_at, 
                 g.created_at as last_active,
+                g.expires_at,
+                g.archived_at,
+                g.max_members,
+                g.category,
+                g.tags,
+                g.geofence_radius,
+                (g.geofence_radius IS NOT NULL OR g.geofence IS NOT NULL) as "is_geofenced!",
+                g.requires_approval,
                 u.nickname as creator_nickname,
                 u.public_user_id as creator_public_id
             FROM groups g
@@ -677,6 +1428,14 @@ impl GroupOperation {
                     creator_id: row.creator_id,
                     created_at: row.created_at,
                     last_active: row.last_active,
+                    expires_at: row.expires_at,
+                    archived_at: row.archived_at,
+                    max_members: row.max_members,
+                    category: row.category,
+                    tags: row.tags,
+                    geofence_radius: row.geofence_radius,
+                    is_geofenced: row.is_geofenced,
+                    requires_approval: row.requires_approval,
                 };
 
                 let creator = CreatorInfo {
@@ -708,6 +1467,14 @@ impl GroupOperation {
                 g.creator_id, 
                 g.created_at, 
                 gm.last_active,
+                g.expires_at,
+                g.archived_at,
+                g.max_members,
+                g.category,
+                g.tags,
+                g.geofence_radius,
+                (g.geofence_radius IS NOT NULL OR g.geofence IS NOT NULL) as "is_geofenced!",
+                g.requires_approval,
                 u.nickname as creator_nickname,
                 u.public_user_id as creator_public_id
             FROM groups g
@@ -735,6 +1502,14 @@ impl GroupOperation {
                 creator_id: row.creator_id,
                 created_at: row.created_at,
                 last_active: row.last_active,
+                expires_at: row.expires_at,
+                archived_at: row.archived_at,
+                max_members: row.max_members,
+                category: row.category,
+                tags: row.tags,
+                geofence_radius: row.geofence_radius,
+                is_geofenced: row.is_geofenced,
+                requires_approval: row.requires_approval,
             };
 
             let creator = CreatorInfo {
@@ -747,4 +1522,46 @@ impl GroupOperation {
 
         Ok(groups_with_creators)
     }
+
+    /// 归档已过期或长期不活跃的群组
+    ///
+    /// 群组的最后活跃时间与附近搜索中的 `last_active_at` 一致：
+    /// 取成员最后活跃时间的最大值，没有成员时取创建时间。
+    /// 返回本次被归档的群组ID，便于调用方清理缓存。
+    pub async fn archive_inactive_groups(
+        &self,
+        inactive_ttl_secs: i64,
+    ) -> Result<Vec<String>, SqlxError> {
+        let archived = sqlx::query!(
+            r#"
+            UPDATE groups g
+            SET archived_at = NOW()
+            WHERE g.archived_at IS NULL
+              AND (
+                  (g.expires_at IS NOT NULL AND g.expires_at <= NOW())
+                  OR COALESCE(
+                      (SELECT MAX(gm.last_active) FROM group_members gm WHERE gm.group_id = g.group_id),
+                      g.created_at
+                  ) < NOW() - make_interval(secs => $1::float8)
+              )
+            RETURNING g.group_id
+            "#,
+            inactive_ttl_secs as f64
+        )
+        .fetch_all(&*self.db)
+        .await?
+        .into_iter()
+        .map(|row| row.group_id)
+        .collect();
+
+        Ok(archived)
+    }
+}
+
+/// 转义 LIKE/ILIKE 模式中的通配符，使用户输入按字面匹配
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
@@ -0,0 +1,236 @@
+// 活动互动存储库
+// 包含活动点赞和评论相关的数据库操作
+
+use crate::database::models::interaction::{
+    ActivityCommentEntity, ActivityInteractionCounts, AddCommentOutcome, DeleteCommentOutcome,
+};
+use sqlx::{Error as SqlxError, PgPool};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 活动互动存储库
+pub struct InteractionOperation {
+    db: Arc<PgPool>,
+}
+
+impl InteractionOperation {
+    /// 创建新的活动互动存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 点赞活动，返回是否新增了点赞（已点赞时为 `false`）
+    pub async fn like(&self, activity_id: &str, user_id: &str) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO activity_likes (activity_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT (activity_id, user_id) DO NOTHING
+            "#,
+            activity_id,
+            user_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 取消点赞，返回是否存在该点赞
+    pub async fn unlike(&self, activity_id: &str, user_id: &str) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM activity_likes
+            WHERE activity_id = $1 AND user_id = $2
+            "#,
+            activity_id,
+            user_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 批量统计活动的点赞数、评论数（不含已删除的评论）以及查看者是否已点赞
+    ///
+    /// 没有任何互动的活动不出现在结果中。
+    pub async fn count_interactions(
+        &self,
+        activity_ids: &[String],
+        viewer_id: &str,
+    ) -> Result<HashMap<String, ActivityInteractionCounts>, SqlxError> {
+        if activity_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                ids.activity_id as "activity_id!",
+                (SELECT COUNT(*) FROM activity_likes l
+                 WHERE l.activity_id = ids.activity_id) as "like_count!",
+                (SELECT COUNT(*) FROM activity_comments c
+                 WHERE c.activity_id = ids.activity_id AND c.deleted_at IS NULL) as "comment_count!",
+                EXISTS(SELECT 1 FROM activity_likes l
+                       WHERE l.activity_id = ids.activity_id AND l.user_id = $2) as "liked!"
+            FROM unnest($1::varchar[]) AS ids(activity_id)
+            "#,
+            activity_ids,
+            viewer_id
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                (
+                    r.activity_id,
+                    ActivityInteractionCounts {
+                        like_count: r.like_count,
+                        comment_count: r.comment_count,
+                        liked: r.liked,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// 发表评论
+    ///
+    /// `parent_id` 不为空时为回复，被回复的评论必须属于同一活动且未被删除。
+    pub async fn add_comment(
+        &self,
+        activity_id: &str,
+        user_id: &str,
+        parent_id: Option<i64>,
+        content: &str,
+    ) -> Result<AddCommentOutcome, SqlxError> {
+        let comment = sqlx::query_as!(
+            ActivityCommentEntity,
+            r#"
+            WITH inserted AS (
+                INSERT INTO activity_comments (activity_id, user_id, parent_id, content)
+                SELECT $1::varchar, $2::varchar, $3::bigint, $4::text
+                WHERE $3::bigint IS NULL OR EXISTS(
+                    SELECT 1 FROM activity_comments p
+                    WHERE p.comment_id = $3 AND p.activity_id = $1::varchar AND p.deleted_at IS NULL
+                )
+                RETURNING *
+            )
+            SELECT
+                c.comment_id as "comment_id!",
+                c.activity_id as "activity_id!",
+                c.user_id as "user_id!",
+                u.public_user_id,
+                u.nickname,
+                c.parent_id,
+                c.content,
+                c.created_at as "created_at!",
+                c.deleted_at
+            FROM inserted c
+            JOIN users u ON u.user_id = c.user_id
+            "#,
+            activity_id,
+            user_id,
+            parent_id,
+            content
+        )
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(match comment {
+            Some(comment) => AddCommentOutcome::Added(comment),
+            None => AddCommentOutcome::ParentNotFound,
+        })
+    }
+
+    /// 按发表顺序获取活动的评论，包括仍有回复挂在其下的已删除评论占位
+    ///
+    /// `after_id` 为上一页最后一条评论的ID，为空时从最早的评论开始。
+    pub async fn find_comments(
+        &self,
+        activity_id: &str,
+        after_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<ActivityCommentEntity>, SqlxError> {
+        sqlx::query_as!(
+            ActivityCommentEntity,
+            r#"
+            SELECT
+                c.comment_id,
+                c.activity_id,
+                c.user_id,
+                u.public_user_id,
+                u.nickname,
+                c.parent_id,
+                c.content,
+                c.created_at,
+                c.deleted_at
+            FROM activity_comments c
+            JOIN users u ON u.user_id = c.user_id
+            WHERE c.activity_id = $1
+              AND ($2::bigint IS NULL OR c.comment_id > $2)
+              AND (c.deleted_at IS NULL OR EXISTS(
+                  SELECT 1 FROM activity_comments r
+                  WHERE r.parent_id = c.comment_id
+              ))
+            ORDER BY c.comment_id
+            LIMIT $3
+            "#,
+            activity_id,
+            after_id,
+            limit
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 删除评论，评论作者和活动发布者可以删除
+    ///
+    /// 删除后保留占位，内容被清空，回复不受影响。
+    pub async fn delete_comment(
+        &self,
+        activity_id: &str,
+        comment_id: i64,
+        user_id: &str,
+    ) -> Result<DeleteCommentOutcome, SqlxError> {
+        let comment = sqlx::query!(
+            r#"
+            SELECT c.user_id as author_id, a.user_id as owner_id
+            FROM activity_comments c
+            JOIN user_activities a ON a.activity_id = c.activity_id
+            WHERE c.comment_id = $1 AND c.activity_id = $2 AND c.deleted_at IS NULL
+            "#,
+            comment_id,
+            activity_id
+        )
+        .fetch_optional(&*self.db)
+        .await?;
+
+        let Some(comment) = comment else {
+            return Ok(DeleteCommentOutcome::NotFound);
+        };
+        if comment.author_id != user_id && comment.owner_id != user_id {
+            return Ok(DeleteCommentOutcome::Forbidden);
+        }
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE activity_comments
+            SET deleted_at = NOW(), content = NULL
+            WHERE comment_id = $1 AND deleted_at IS NULL
+            "#,
+            comment_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(if result.rows_affected() > 0 {
+            DeleteCommentOutcome::Deleted
+        } else {
+            DeleteCommentOutcome::NotFound
+        })
+    }
+}
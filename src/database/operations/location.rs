@@ -0,0 +1,429 @@
+// 用户位置存储库
+// 包含用户实时位置相关的数据库操作
+
+use crate::database::models::activity::ActivityType;
+use crate::database::models::location::{
+    GroupSharedLocation, NearbyUserLocation, TRIP_GAP_SECS, TRIP_SIMPLIFY_TOLERANCE, TripSummary,
+    TripTrack, UserLocationEntity,
+};
+use crate::database::models::privacy::CITY_MARGIN;
+use chrono::{DateTime, Utc};
+use sqlx::{Error as SqlxError, PgPool};
+use std::sync::Arc;
+
+/// 用户位置存储库，处理 user_locations 表的读写
+pub struct LocationOperation {
+    db: Arc<PgPool>,
+}
+
+impl LocationOperation {
+    /// 创建新的用户位置存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 记录用户定位
+    ///
+    /// 距上一次被接受的定位不足 `min_interval_secs` 秒时不写入，返回 `None`。
+    pub async fn record_location(
+        &self,
+        user_id: &str,
+        latitude: f64,
+        longitude: f64,
+        accuracy: Option<f64>,
+        min_interval_secs: u64,
+    ) -> Result<Option<UserLocationEntity>, SqlxError> {
+        sqlx::query_as!(
+            UserLocationEntity,
+            r#"
+            INSERT INTO user_locations (user_id, latitude, longitude, accuracy)
+            SELECT $1::varchar, $2::float8, $3::float8, $4::float8
+            WHERE NOT EXISTS (
+                SELECT 1 FROM user_locations
+                WHERE user_id = $1
+                AND updated_at > NOW() - make_interval(secs => $5::float8)
+            )
+            RETURNING location_id, user_id, latitude, longitude, accuracy, updated_at
+            "#,
+            user_id,
+            latitude,
+            longitude,
+            accuracy,
+            min_interval_secs as f64
+        )
+        .fetch_optional(&*self.db)
+        .await
+    }
+
+    /// 查找附近用户
+    ///
+    /// 以每个用户最新的定位为准，超过 `stale_after_secs` 秒未更新位置的用户不返回。
+    /// 位置和距离为 `obscure_location` 按用户的位置隐私设置处理后的结果，隐身的用户不返回；
+    /// 半径过滤、排序和数量限制都基于处理后的距离。
+    /// 附带的最近一条活动只取 `viewer_id` 可见的活动。
+    pub async fn find_nearby_users(
+        &self,
+        (viewer_id, fuzz_secret): (&str, &str),
+        latitude: f64,
+        longitude: f64,
+        radius: f64,
+        limit: i64,
+        stale_after_secs: u64,
+    ) -> Result<Vec<NearbyUserLocation>, SqlxError> {
+        let actual_limit = if limit <= 0 { 20 } else { limit };
+
+        sqlx::query_as!(
+            NearbyUserLocation,
+            r#"
+            WITH latest_locations AS (
+                SELECT DISTINCT ON (user_id)
+                    user_id,
+                    latitude,
+                    longitude,
+                    updated_at
+                FROM user_locations
+                WHERE updated_at > NOW() - make_interval(secs => $5::float8)
+                ORDER BY user_id, updated_at DESC
+            )
+            SELECT
+                u.user_id,
+                u.nickname,
+                o.latitude as "latitude!",
+                o.longitude as "longitude!",
+                ll.updated_at as location_updated_at,
+                ra.activity_id as "last_activity_id?",
+                ra.activity_type as "last_activity_type?: ActivityType",
+                ra.activity_details as "last_activity_description?",
+                ra.created_at as "last_activity_time?",
+                ST_Distance(
+                    ST_SetSRID(ST_MakePoint(o.longitude, o.latitude), 4326)::geography,
+                    ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography
+                ) AS distance
+            FROM latest_locations ll
+            JOIN users u ON u.user_id = ll.user_id
+            LEFT JOIN user_privacy_settings ps ON ps.user_id = ll.user_id
+            CROSS JOIN LATERAL obscure_location(
+                ll.user_id, $7, $8, ll.latitude, ll.longitude, ll.updated_at
+            ) o
+            LEFT JOIN LATERAL (
+                SELECT activity_id, activity_type, activity_details, created_at
+                FROM user_activities
+                WHERE user_id = ll.user_id
+                AND activity_visible_to(user_id, visibility, group_id, $7)
+                ORDER BY created_at DESC
+                LIMIT 1
+            ) ra ON TRUE
+            -- 先按真实位置加上最大偏差粗略过滤，再按处理后的位置精确过滤
+            WHERE ST_DWithin(
+                ST_SetSRID(ST_MakePoint(ll.longitude, ll.latitude), 4326)::geography,
+                ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography,
+                $3 + CASE ps.location_precision
+                    WHEN 'fuzzed' THEN ps.fuzz_radius::float8
+                    WHEN 'city' THEN $6::float8
+                    ELSE 0
+                END
+            )
+            AND ST_DWithin(
+                ST_SetSRID(ST_MakePoint(o.longitude, o.latitude), 4326)::geography,
+                ST_SetSRID(ST_MakePoint($2, $1), 4326)::geography,
+                $3
+            )
+            ORDER BY distance, u.user_id
+            LIMIT $4
+            "#,
+            latitude,
+            longitude,
+            radius, // 以米为单位的半径
+            actual_limit,
+            stale_after_secs as f64,
+            CITY_MARGIN,
+            viewer_id,
+            fuzz_secret
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 开始向群组共享位置，已在共享时重新计时
+    ///
+    /// 返回共享的过期时间。
+    pub async fn start_group_share(
+        &self,
+        group_id: &str,
+        user_id: &str,
+        duration_secs: u64,
+    ) -> Result<DateTime<Utc>, SqlxError> {
+        let share = sqlx::query!(
+            r#"
+            INSERT INTO group_location_shares (group_id, user_id, expires_at)
+            VALUES ($1, $2, NOW() + make_interval(secs => $3::float8))
+            ON CONFLICT (group_id, user_id) DO UPDATE SET
+                started_at = CASE
+                    WHEN group_location_shares.expires_at > NOW()
+                    THEN group_location_shares.started_at
+                    ELSE NOW()
+                END,
+                expires_at = EXCLUDED.expires_at
+            RETURNING expires_at
+            "#,
+            group_id,
+            user_id,
+            duration_secs as f64
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(share.expires_at)
+    }
+
+    /// 停止向群组共享位置
+    ///
+    /// 返回停止前是否正在共享。
+    pub async fn stop_group_share(&self, group_id: &str, user_id: &str) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM group_location_shares
+            WHERE group_id = $1 AND user_id = $2
+            RETURNING expires_at > NOW() as "active!"
+            "#,
+            group_id,
+            user_id
+        )
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(result.is_some_and(|share| share.active))
+    }
+
+    /// 查询群组内正在共享位置的成员及其最新定位
+    ///
+    /// 只返回共享开始之后的定位；已退出群组或共享已过期的成员不返回。
+    pub async fn find_group_shared_locations(
+        &self,
+        group_id: &str,
+    ) -> Result<Vec<GroupSharedLocation>, SqlxError> {
+        sqlx::query_as!(
+            GroupSharedLocation,
+            r#"
+            SELECT
+                u.user_id,
+                u.public_user_id,
+                u.nickname,
+                s.expires_at as sharing_expires_at,
+                l.latitude as "latitude?",
+                l.longitude as "longitude?",
+                l.accuracy as "accuracy?",
+                l.updated_at as "updated_at?"
+            FROM group_location_shares s
+            JOIN group_members gm ON gm.group_id = s.group_id AND gm.user_id = s.user_id
+            JOIN users u ON u.user_id = s.user_id
+            LEFT JOIN LATERAL (
+                SELECT latitude, longitude, accuracy, updated_at
+                FROM user_locations
+                WHERE user_id = s.user_id AND updated_at >= s.started_at
+                ORDER BY updated_at DESC
+                LIMIT 1
+            ) l ON TRUE
+            WHERE s.group_id = $1 AND s.expires_at > NOW()
+            ORDER BY s.started_at
+            "#,
+            group_id
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 按时间倒序列出用户的行程
+    ///
+    /// 相邻定位间隔超过 [`TRIP_GAP_SECS`] 时切分为新行程，只有一个定位点的行程不返回。
+    pub async fn find_trips(
+        &self,
+        user_id: &str,
+        limit: i64,
+    ) -> Result<Vec<TripSummary>, SqlxError> {
+        sqlx::query_as!(
+            TripSummary,
+            r#"
+            WITH fixes AS (
+                SELECT
+                    location_id,
+                    latitude,
+                    longitude,
+                    updated_at,
+                    COALESCE(
+                        updated_at - LAG(updated_at) OVER w > make_interval(secs => $2::float8),
+                        TRUE
+                    ) as starts_trip
+                FROM user_locations
+                WHERE user_id = $1
+                WINDOW w AS (ORDER BY updated_at, location_id)
+            ),
+            numbered AS (
+                SELECT
+                    *,
+                    COUNT(*) FILTER (WHERE starts_trip) OVER (
+                        ORDER BY updated_at, location_id
+                    ) as trip_no
+                FROM fixes
+            )
+            SELECT
+                (array_agg(location_id ORDER BY updated_at, location_id))[1] as "trip_id!",
+                MIN(updated_at) as "started_at!",
+                MAX(updated_at) as "ended_at!",
+                COUNT(*) as "point_count!",
+                ST_Length(
+                    ST_MakeLine(
+                        ST_SetSRID(ST_MakePoint(longitude, latitude), 4326)
+                        ORDER BY updated_at, location_id
+                    )::geography
+                ) as "distance!"
+            FROM numbered
+            GROUP BY trip_no
+            HAVING COUNT(*) >= 2
+            ORDER BY MIN(updated_at) DESC
+            LIMIT $3
+            "#,
+            user_id,
+            TRIP_GAP_SECS as f64,
+            limit
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 获取行程的全部定位点及简化后的路径
+    ///
+    /// `trip_id` 不是该用户某段行程的第一条定位时返回 `None`。
+    pub async fn find_trip_track(
+        &self,
+        user_id: &str,
+        trip_id: i32,
+    ) -> Result<Option<TripTrack>, SqlxError> {
+        let track = sqlx::query!(
+            r#"
+            WITH start AS (
+                SELECT s.updated_at
+                FROM user_locations s
+                WHERE s.location_id = $2 AND s.user_id = $1
+                AND NOT EXISTS (
+                    SELECT 1 FROM user_locations p
+                    WHERE p.user_id = $1
+                    AND (p.updated_at, p.location_id) < (s.updated_at, s.location_id)
+                    AND p.updated_at >= s.updated_at - make_interval(secs => $3::float8)
+                )
+            ),
+            fixes AS (
+                SELECT
+                    l.location_id,
+                    l.latitude,
+                    l.longitude,
+                    l.updated_at,
+                    COALESCE(
+                        l.updated_at - LAG(l.updated_at) OVER w > make_interval(secs => $3::float8),
+                        FALSE
+                    ) as gap
+                FROM user_locations l, start
+                WHERE l.user_id = $1 AND l.updated_at >= start.updated_at
+                WINDOW w AS (ORDER BY l.updated_at, l.location_id)
+            ),
+            trip AS (
+                SELECT
+                    *,
+                    COUNT(*) FILTER (WHERE gap) OVER (ORDER BY updated_at, location_id) as breaks
+                FROM fixes
+            )
+            SELECT
+                array_agg(latitude ORDER BY updated_at, location_id) as latitudes,
+                array_agg(longitude ORDER BY updated_at, location_id) as longitudes,
+                array_agg(updated_at ORDER BY updated_at, location_id) as recorded_at,
+                ST_AsGeoJSON(
+                    ST_Simplify(
+                        ST_MakeLine(
+                            ST_SetSRID(ST_MakePoint(longitude, latitude), 4326)
+                            ORDER BY updated_at, location_id
+                        ),
+                        $4
+                    ),
+                    15
+                ) as simplified
+            FROM trip
+            WHERE breaks = 0
+            "#,
+            user_id,
+            trip_id,
+            TRIP_GAP_SECS as f64,
+            TRIP_SIMPLIFY_TOLERANCE
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(
+            match (
+                track.latitudes,
+                track.longitudes,
+                track.recorded_at,
+                track.simplified,
+            ) {
+                (Some(latitudes), Some(longitudes), Some(recorded_at), Some(simplified)) => {
+                    Some(TripTrack {
+                        latitudes,
+                        longitudes,
+                        recorded_at,
+                        simplified,
+                    })
+                }
+                _ => None,
+            },
+        )
+    }
+
+    /// 删除用户的全部位置记录
+    pub async fn delete_history(&self, user_id: &str) -> Result<u64, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM user_locations
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// 清理过期的位置记录
+    ///
+    /// 所有用户超过 `retention_secs` 的定位都会被删除；未开启位置历史的用户
+    /// 只保留最近 `live_secs` 秒内的定位和最新的一条定位。
+    pub async fn prune_locations(
+        &self,
+        live_secs: u64,
+        retention_secs: u64,
+    ) -> Result<u64, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM user_locations l
+            WHERE l.updated_at < NOW() - make_interval(secs => $2::float8)
+            OR (
+                l.updated_at < NOW() - make_interval(secs => $1::float8)
+                AND NOT EXISTS (
+                    SELECT 1 FROM user_privacy_settings ps
+                    WHERE ps.user_id = l.user_id AND ps.location_history
+                )
+                AND EXISTS (
+                    SELECT 1 FROM user_locations newer
+                    WHERE newer.user_id = l.user_id
+                    AND newer.updated_at > l.updated_at
+                )
+            )
+            "#,
+            live_secs as f64,
+            retention_secs as f64
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,325 @@
+// 地图区域查询
+// 包含地图视窗查询共用的 SQL 片段
+
+use crate::database::models::map::{MapArea, MapCluster, MapLayer, expand_bounds, tile_bounds};
+use crate::database::models::privacy::MAX_PRIVACY_MARGIN;
+use sqlx::{Error as SqlxError, PgPool, Postgres, QueryBuilder};
+use std::sync::Arc;
+
+/// 地图存储库，处理按瓦片聚合的地图查询
+pub struct MapOperation {
+    db: Arc<PgPool>,
+}
+
+impl MapOperation {
+    /// 创建新的地图存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 按网格聚合矩形范围内的点
+    ///
+    /// 将范围划分为 `cells` x `cells` 的网格，同一网格内的点合并为一个聚合点，
+    /// 聚合点位置为网格内所有点的中心。`viewer` 为查看者的登录ID和模糊化位置使用的密钥，
+    /// 活动图层只包含查看者能看到的活动，位置按发布者的位置隐私设置处理。
+    pub async fn find_clusters(
+        &self,
+        layer: MapLayer,
+        viewer: (&str, &str),
+        bounds: (f64, f64, f64, f64),
+        cells: u32,
+    ) -> Result<Vec<MapCluster>, SqlxError> {
+        let (west, south, east, north) = bounds;
+        let cell_width = (east - west) / cells as f64;
+        let cell_height = (north - south) / cells as f64;
+
+        let mut query = QueryBuilder::new("WITH points AS (");
+        Self::push_points(&mut query, layer, viewer, bounds);
+        query
+            .push(
+                ")
+                SELECT
+                    AVG(latitude) as latitude,
+                    AVG(longitude) as longitude,
+                    COUNT(*) as count,
+                    CASE WHEN COUNT(*) = 1 THEN MIN(id) END as id,
+                    CASE WHEN COUNT(*) = 1 THEN MIN(label) END as label
+                FROM points
+                GROUP BY floor((longitude - ",
+            )
+            .push_bind(west)
+            .push(") / ")
+            .push_bind(cell_width)
+            .push("), floor((latitude - ")
+            .push_bind(south)
+            .push(") / ")
+            .push_bind(cell_height)
+            .push(")");
+
+        query
+            .build_query_as::<MapCluster>()
+            .fetch_all(&*self.db)
+            .await
+    }
+
+    /// 查询矩形范围内的单个点，最多返回 `limit` 个
+    ///
+    /// 活动图层的过滤和位置处理与 [`Self::find_clusters`] 相同。
+    pub async fn find_points(
+        &self,
+        layer: MapLayer,
+        viewer: (&str, &str),
+        bounds: (f64, f64, f64, f64),
+        limit: i64,
+    ) -> Result<Vec<MapCluster>, SqlxError> {
+        let mut query =
+            QueryBuilder::new("SELECT latitude, longitude, 1::bigint as count, id, label FROM (");
+        Self::push_points(&mut query, layer, viewer, bounds);
+        query.push(") points ORDER BY id LIMIT ").push_bind(limit);
+
+        query
+            .build_query_as::<MapCluster>()
+            .fetch_all(&*self.db)
+            .await
+    }
+
+    /// 生成图层在 XYZ 瓦片内的 Mapbox 矢量瓦片（MVT），瓦片坐标无效时返回 `RowNotFound`
+    ///
+    /// 群组要素带有 id、name、member_count、created_at 属性，
+    /// 活动要素带有 id、activity_type、created_at 属性，created_at 为 Unix 时间戳（秒），
+    /// 活动的过滤和位置处理与 [`Self::find_clusters`] 相同。
+    pub async fn render_tile(
+        &self,
+        layer: MapLayer,
+        viewer: (&str, &str),
+        (z, x, y): (u8, u32, u32),
+    ) -> Result<Vec<u8>, SqlxError> {
+        let bounds = tile_bounds(z, x, y).ok_or(SqlxError::RowNotFound)?;
+        let (west, south, east, north) = bounds;
+        let area = MapArea::BoundingBox {
+            min_longitude: west,
+            min_latitude: south,
+            max_longitude: east,
+            max_latitude: north,
+        };
+
+        let mut query = QueryBuilder::new("WITH bounds AS (SELECT ST_TileEnvelope(");
+        query
+            .push_bind(z as i32)
+            .push(", ")
+            .push_bind(x as i32)
+            .push(", ")
+            .push_bind(y as i32)
+            .push(") AS geom), features AS (");
+
+        let layer_name = match layer {
+            MapLayer::Groups => {
+                query.push(
+                    "SELECT
+                        ST_AsMVTGeom(ST_Transform(g.geom::geometry, 3857), bounds.geom) AS geom,
+                        g.group_id AS id,
+                        g.name,
+                        COALESCE(gc.member_count, 0) AS member_count,
+                        extract(epoch FROM g.created_at)::bigint AS created_at
+                    FROM groups g
+                    CROSS JOIN bounds
+                    LEFT JOIN (
+                        SELECT group_id, COUNT(*) AS member_count
+                        FROM group_members
+                        GROUP BY group_id
+                    ) gc ON gc.group_id = g.group_id
+                    WHERE g.archived_at IS NULL",
+                );
+                push_intersects(&mut query, "g.geom", &area);
+                "groups"
+            }
+            MapLayer::Activities => {
+                query.push(
+                    "SELECT
+                        ST_AsMVTGeom(
+                            ST_Transform(ST_SetSRID(ST_MakePoint(p.longitude, p.latitude), 4326), 3857),
+                            bounds.geom
+                        ) AS geom,
+                        p.id,
+                        p.label AS activity_type,
+                        extract(epoch FROM p.created_at)::bigint AS created_at
+                    FROM (",
+                );
+                Self::push_points(&mut query, layer, viewer, bounds);
+                query.push(") p CROSS JOIN bounds");
+                "activities"
+            }
+        };
+
+        query
+            .push(") SELECT COALESCE(ST_AsMVT(features.*, ")
+            .push_bind(layer_name)
+            .push(", 4096, 'geom'), ''::bytea) FROM features");
+
+        query
+            .build_query_scalar::<Vec<u8>>()
+            .fetch_one(&*self.db)
+            .await
+    }
+
+    /// 追加图层中位于矩形内的点的子查询，列为 id、label、latitude、longitude、created_at
+    ///
+    /// 活动图层只包含查看者能看到的活动，位置为 `obscure_location` 按发布者的位置隐私设置
+    /// 处理后的位置，隐身用户的活动被排除，按处理后的位置过滤，见 [`push_obscured_intersects`]。
+    fn push_points(
+        query: &mut QueryBuilder<'static, Postgres>,
+        layer: MapLayer,
+        (viewer_id, fuzz_secret): (&str, &str),
+        (west, south, east, north): (f64, f64, f64, f64),
+    ) {
+        let area = MapArea::BoundingBox {
+            min_longitude: west,
+            min_latitude: south,
+            max_longitude: east,
+            max_latitude: north,
+        };
+        match layer {
+            MapLayer::Groups => {
+                query.push(
+                    "SELECT g.group_id as id, g.name as label, g.latitude, g.longitude, g.created_at
+                    FROM groups g
+                    WHERE g.archived_at IS NULL",
+                );
+                push_intersects(query, "g.geom", &area);
+            }
+            MapLayer::Activities => {
+                query
+                    .push(
+                        "SELECT a.activity_id as id, a.activity_type as label,
+                            o.latitude, o.longitude, a.created_at
+                        FROM user_activities a
+                        CROSS JOIN LATERAL obscure_location(a.user_id, ",
+                    )
+                    .push_bind(viewer_id.to_string())
+                    .push(", ")
+                    .push_bind(fuzz_secret.to_string())
+                    .push(
+                        ", a.latitude, a.longitude, a.created_at) o
+                        WHERE activity_visible_to(a.user_id, a.visibility, a.group_id, ",
+                    )
+                    .push_bind(viewer_id.to_string())
+                    .push(")");
+                push_obscured_intersects(query, "a.geom", ("o.latitude", "o.longitude"), &area);
+            }
+        }
+    }
+}
+
+/// 追加经过位置隐私处理的点与查询区域相交的条件（以 `AND` 开头）
+///
+/// `column` 为真实位置的 geography 列，`latitude` 和 `longitude` 为处理后位置的表达式。
+/// 只按处理后的位置过滤，避免通过查询区域推断真实位置；真实位置的查询范围相应扩大
+/// [`MAX_PRIVACY_MARGIN`] 以使用空间索引。
+pub(crate) fn push_obscured_intersects(
+    query: &mut QueryBuilder<'static, Postgres>,
+    column: &str,
+    (latitude, longitude): (&str, &str),
+    area: &MapArea,
+) {
+    match *area {
+        MapArea::BoundingBox {
+            min_longitude,
+            min_latitude,
+            max_longitude,
+            max_latitude,
+        } => {
+            // 跨越180度经线的矩形以大于180的东边界传入
+            let east = if min_longitude > max_longitude {
+                max_longitude + 360.0
+            } else {
+                max_longitude
+            };
+            push_intersects(
+                query,
+                column,
+                &expand_bounds(
+                    (min_longitude, min_latitude, east, max_latitude),
+                    MAX_PRIVACY_MARGIN,
+                ),
+            );
+            query
+                .push(" AND ")
+                .push(latitude)
+                .push(" BETWEEN ")
+                .push_bind(min_latitude)
+                .push(" AND ")
+                .push_bind(max_latitude)
+                .push(" AND (")
+                .push(longitude)
+                .push(" >= ")
+                .push_bind(min_longitude)
+                .push(if min_longitude <= max_longitude {
+                    " AND "
+                } else {
+                    " OR "
+                })
+                .push(longitude)
+                .push(" <= ")
+                .push_bind(max_longitude)
+                .push(")");
+        }
+        MapArea::Polygon(ref geojson) => {
+            query
+                .push(" AND ST_DWithin(")
+                .push(column)
+                .push(", ST_SetSRID(ST_GeomFromGeoJSON(")
+                .push_bind(geojson.clone())
+                .push("), 4326)::geography, ")
+                .push_bind(MAX_PRIVACY_MARGIN)
+                .push(") AND ST_Intersects(ST_SetSRID(ST_MakePoint(")
+                .push(longitude)
+                .push(", ")
+                .push(latitude)
+                .push("), 4326)::geography, ST_SetSRID(ST_GeomFromGeoJSON(")
+                .push_bind(geojson.clone())
+                .push("), 4326)::geography)");
+        }
+    }
+}
+
+/// 追加 `column` 与查询区域相交的条件（以 `AND` 开头）
+///
+/// `column` 为 geography 类型的列。矩形的边沿经线和纬线，按经纬度平面判断相交，
+/// 使用 `column::geometry` 上的 GIST 索引；多边形按 geography 判断，使用列本身的 GIST 索引。
+pub(crate) fn push_intersects(
+    query: &mut QueryBuilder<'static, Postgres>,
+    column: &str,
+    area: &MapArea,
+) {
+    match area {
+        MapArea::BoundingBox { .. } => {
+            query.push(" AND (");
+            for (i, (west, south, east, north)) in area.envelopes().into_iter().enumerate() {
+                if i > 0 {
+                    query.push(" OR ");
+                }
+                query
+                    .push("ST_Intersects(")
+                    .push(column)
+                    .push("::geometry, ST_MakeEnvelope(")
+                    .push_bind(west)
+                    .push(", ")
+                    .push_bind(south)
+                    .push(", ")
+                    .push_bind(east)
+                    .push(", ")
+                    .push_bind(north)
+                    .push(", 4326))");
+            }
+            query.push(")");
+        }
+        MapArea::Polygon(geojson) => {
+            query
+                .push(" AND ST_Intersects(")
+                .push(column)
+                .push(", ST_SetSRID(ST_GeomFromGeoJSON(")
+                .push_bind(geojson.clone())
+                .push("), 4326)::geography)");
+        }
+    }
+}
@@ -1,7 +1,11 @@
 // 消息存储库
 // 包含消息相关的数据库操作
 
-use crate::database::models::message::MessageWithUser;
+use crate::database::models::activity::ActivityType;
+use crate::database::models::message::{MessageType, MessageWithUser};
+use crate::database::models::poll::NewPoll;
+use crate::database::operations::activity::ActivityOperation;
+use crate::database::operations::poll::PollOperation;
 use sqlx::{Error as SqlxError, PgPool};
 use std::sync::Arc;
 use uuid::Uuid;
@@ -18,12 +22,20 @@ impl MessageOperation {
     }
 
     /// 保存消息
+    ///
+    /// 同时在同一事务中记录发送消息活动，同一用户在同一群组
+    /// `activity_interval_secs` 秒内只记录一次。
+    /// 指定 `poll` 时保存为投票消息，`content` 为投票的问题。
     pub async fn save_message(
         &self,
         group_id: &str,
         user_id: &str,
         content: &str,
+        poll: Option<&NewPoll<'_>>,
+        activity_interval_secs: i64,
     ) -> Result<String, SqlxError> {
+        let mut tx = self.db.begin().await?;
+
         // 先检查用户是否在群组中
         let is_member = sqlx::query!(
             r#"
@@ -35,7 +47,7 @@ impl MessageOperation {
             group_id,
             user_id
         )
-        .fetch_one(&*self.db)
+        .fetch_one(&mut *tx)
         .await?
         .exists;
 
@@ -53,26 +65,47 @@ impl MessageOperation {
             "#,
             user_id
         )
-        .fetch_one(&*self.db)
+        .fetch_one(&mut *tx)
         .await?
         .public_user_id;
 
         let message_id = Uuid::new_v4().to_string();
+        let message_type = if poll.is_some() {
+            MessageType::Poll
+        } else {
+            MessageType::Text
+        };
 
         // 使用公开ID作为user_id存储
         sqlx::query!(
             r#"
-            INSERT INTO messages (message_id, group_id, user_id, content, created_at)
-            VALUES ($1, $2, $3, $4, NOW())
+            INSERT INTO messages (message_id, group_id, user_id, message_type, content, created_at)
+            VALUES ($1, $2, $3, $4, $5, NOW())
             "#,
             message_id,
             group_id,
             user_public_id, // 使用公开ID
+            message_type as i32,
             content
         )
-        .execute(&*self.db)
+        .execute(&mut *tx)
         .await?;
 
+        if let Some(poll) = poll {
+            PollOperation::create_poll(&mut tx, &message_id, poll).await?;
+        }
+
+        ActivityOperation::record_group_activity(
+            &mut tx,
+            group_id,
+            user_id,
+            &ActivityType::MessageSent,
+            activity_interval_secs,
+        )
+        .await?;
+
+        tx.commit().await?;
+
         Ok(message_id)
     }
 
@@ -95,6 +128,7 @@ impl MessageOperation {
                     m.group_id,
                     m.user_id,
                     u.nickname,
+                    m.message_type,
                     m.content,
                     m.created_at
                 FROM messages m
@@ -123,6 +157,7 @@ impl MessageOperation {
                     m.group_id,
                     m.user_id,
                     u.nickname,
+                    m.message_type,
                     m.content,
                     m.created_at
                 FROM messages m
@@ -152,6 +187,7 @@ impl MessageOperation {
                 m.group_id,
                 m.user_id,
                 u.nickname,
+                m.message_type,
                 m.content,
                 m.created_at
             FROM messages m
@@ -183,6 +219,7 @@ impl MessageOperation {
                 m.group_id,
                 m.user_id,
                 u.nickname,
+                m.message_type,
                 m.content,
                 m.created_at
             FROM messages m
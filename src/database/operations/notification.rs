@@ -0,0 +1,95 @@
+// 通知存储库
+// 包含通知收件箱相关的数据库操作
+
+use crate::database::models::notification::NotificationEntity;
+use sqlx::{Error as SqlxError, PgPool};
+use std::sync::Arc;
+
+/// 通知存储库
+pub struct NotificationOperation {
+    db: Arc<PgPool>,
+}
+
+impl NotificationOperation {
+    /// 创建新的通知存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 按时间倒序获取用户的通知
+    ///
+    /// `before_id` 为上一页最后一条通知的ID，为空时从最新的通知开始。
+    pub async fn find_by_user(
+        &self,
+        user_id: &str,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<NotificationEntity>, SqlxError> {
+        sqlx::query_as!(
+            NotificationEntity,
+            r#"
+            SELECT notification_id, user_id, kind, payload, created_at, read_at
+            FROM notifications
+            WHERE user_id = $1
+            AND ($2::bigint IS NULL OR notification_id < $2)
+            ORDER BY notification_id DESC
+            LIMIT $3
+            "#,
+            user_id,
+            before_id,
+            limit
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 统计用户的未读通知数量
+    pub async fn count_unread(&self, user_id: &str) -> Result<i64, SqlxError> {
+        let count = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM notifications
+            WHERE user_id = $1 AND read_at IS NULL
+            "#,
+            user_id
+        )
+        .fetch_one(&*self.db)
+        .await?
+        .count;
+
+        Ok(count)
+    }
+
+    /// 将一条通知标记为已读
+    pub async fn mark_read(&self, user_id: &str, notification_id: i64) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE notifications
+            SET read_at = COALESCE(read_at, NOW())
+            WHERE notification_id = $1 AND user_id = $2
+            "#,
+            notification_id,
+            user_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 将用户的所有未读通知标记为已读，返回标记的数量
+    pub async fn mark_all_read(&self, user_id: &str) -> Result<u64, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE notifications
+            SET read_at = NOW()
+            WHERE user_id = $1 AND read_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
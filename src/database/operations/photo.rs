@@ -0,0 +1,165 @@
+// 活动照片存储库
+// 包含活动照片相关的数据库操作
+
+use crate::database::models::photo::{ActivityPhotoEntity, AddPhotoOutcome, NewActivityPhoto};
+use sqlx::{Error as SqlxError, PgPool};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 活动照片存储库
+pub struct PhotoOperation {
+    db: Arc<PgPool>,
+}
+
+impl PhotoOperation {
+    /// 创建新的活动照片存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 统计活动已有的照片数量
+    pub async fn count_by_activity(&self, activity_id: &str) -> Result<i64, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM activity_photos
+            WHERE activity_id = $1
+            "#,
+            activity_id
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(row.count)
+    }
+
+    /// 保存照片记录
+    ///
+    /// 锁定所属活动后再检查数量，同一活动的并发上传不会超过 `max_photos`。
+    pub async fn add_photo(
+        &self,
+        photo: &NewActivityPhoto<'_>,
+        max_photos: i64,
+    ) -> Result<AddPhotoOutcome, SqlxError> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query!(
+            r#"
+            SELECT activity_id
+            FROM user_activities
+            WHERE activity_id = $1
+            FOR UPDATE
+            "#,
+            photo.activity_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+        let count = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM activity_photos
+            WHERE activity_id = $1
+            "#,
+            photo.activity_id
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .count;
+
+        if count >= max_photos {
+            return Ok(AddPhotoOutcome::LimitReached);
+        }
+
+        let entity = sqlx::query_as!(
+            ActivityPhotoEntity,
+            r#"
+            INSERT INTO activity_photos (
+                photo_id, activity_id, user_id, storage_key, thumbnail_key,
+                content_type, width, height, size_bytes, keeps_location
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING
+                photo_id, activity_id, user_id, storage_key, thumbnail_key,
+                content_type, width, height, size_bytes, keeps_location, created_at
+            "#,
+            photo.photo_id,
+            photo.activity_id,
+            photo.user_id,
+            photo.storage_key,
+            photo.thumbnail_key,
+            photo.content_type,
+            photo.width,
+            photo.height,
+            photo.size_bytes,
+            photo.keeps_location
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(AddPhotoOutcome::Added(entity))
+    }
+
+    /// 批量获取活动的照片，按上传顺序排列
+    pub async fn find_by_activities(
+        &self,
+        activity_ids: &[String],
+    ) -> Result<HashMap<String, Vec<ActivityPhotoEntity>>, SqlxError> {
+        if activity_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let photos = sqlx::query_as!(
+            ActivityPhotoEntity,
+            r#"
+            SELECT
+                photo_id, activity_id, user_id, storage_key, thumbnail_key,
+                content_type, width, height, size_bytes, keeps_location, created_at
+            FROM activity_photos
+            WHERE activity_id = ANY($1)
+            ORDER BY created_at, photo_id
+            "#,
+            activity_ids
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        let mut by_activity: HashMap<String, Vec<ActivityPhotoEntity>> = HashMap::new();
+        for photo in photos {
+            by_activity
+                .entry(photo.activity_id.clone())
+                .or_default()
+                .push(photo);
+        }
+        Ok(by_activity)
+    }
+
+    /// 删除照片，只有上传者可以删除
+    ///
+    /// 返回被删除的照片记录，用于清理存储中的文件；照片不存在或不属于该用户时返回 `None`。
+    pub async fn delete_photo(
+        &self,
+        activity_id: &str,
+        photo_id: &str,
+        user_id: &str,
+    ) -> Result<Option<ActivityPhotoEntity>, SqlxError> {
+        sqlx::query_as!(
+            ActivityPhotoEntity,
+            r#"
+            DELETE FROM activity_photos
+            WHERE photo_id = $1 AND activity_id = $2 AND user_id = $3
+            RETURNING
+                photo_id, activity_id, user_id, storage_key, thumbnail_key,
+                content_type, width, height, size_bytes, keeps_location, created_at
+            "#,
+            photo_id,
+            activity_id,
+            user_id
+        )
+        .fetch_optional(&*self.db)
+        .await
+    }
+}
@@ -0,0 +1,85 @@
+// 收藏地点存储库
+// 包含收藏地点相关的数据库操作
+
+use crate::database::models::place::SavedPlaceEntity;
+use sqlx::{Error as SqlxError, PgPool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 收藏地点存储库
+pub struct PlaceOperation {
+    db: Arc<PgPool>,
+}
+
+impl PlaceOperation {
+    /// 创建新的收藏地点存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 收藏地点
+    ///
+    /// 用户已收藏 `max_places` 个地点时不再创建，返回 `None`。
+    pub async fn create(
+        &self,
+        user_id: &str,
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+        radius: i32,
+        max_places: i64,
+    ) -> Result<Option<SavedPlaceEntity>, SqlxError> {
+        let place_id = Uuid::new_v4().to_string();
+
+        sqlx::query_as!(
+            SavedPlaceEntity,
+            r#"
+            INSERT INTO saved_places (place_id, user_id, name, latitude, longitude, radius)
+            SELECT $1, $2::varchar, $3, $4, $5, $6
+            WHERE (SELECT COUNT(*) FROM saved_places WHERE user_id = $2) < $7
+            RETURNING place_id, user_id, name, latitude, longitude, radius, created_at
+            "#,
+            place_id,
+            user_id,
+            name,
+            latitude,
+            longitude,
+            radius,
+            max_places
+        )
+        .fetch_optional(&*self.db)
+        .await
+    }
+
+    /// 获取用户收藏的所有地点
+    pub async fn find_by_user(&self, user_id: &str) -> Result<Vec<SavedPlaceEntity>, SqlxError> {
+        sqlx::query_as!(
+            SavedPlaceEntity,
+            r#"
+            SELECT place_id, user_id, name, latitude, longitude, radius, created_at
+            FROM saved_places
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+            user_id
+        )
+        .fetch_all(&*self.db)
+        .await
+    }
+
+    /// 删除用户收藏的地点
+    pub async fn delete(&self, place_id: &str, user_id: &str) -> Result<bool, SqlxError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM saved_places
+            WHERE place_id = $1 AND user_id = $2
+            "#,
+            place_id,
+            user_id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
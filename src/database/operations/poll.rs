@@ -0,0 +1,260 @@
+// 群聊投票存储库
+// 包含投票的创建、结果查询以及成员投票相关的数据库操作
+
+use crate::database::models::poll::{
+    NewPoll, PollEntity, PollOptionEntity, PollVoter, VoteOutcome,
+};
+use sqlx::{Error as SqlxError, PgPool, Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// 群聊投票存储库
+pub struct PollOperation {
+    db: Arc<PgPool>,
+}
+
+impl PollOperation {
+    /// 创建新的群聊投票存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 在保存投票消息的事务中写入投票及其选项
+    pub async fn create_poll(
+        tx: &mut Transaction<'_, Postgres>,
+        message_id: &str,
+        poll: &NewPoll<'_>,
+    ) -> Result<(), SqlxError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO message_polls (message_id, multiple_choice, anonymous, closes_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            message_id,
+            poll.multiple_choice,
+            poll.anonymous,
+            poll.closes_at
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO message_poll_options (message_id, option_id, text)
+            SELECT $1, (o.ordinality - 1)::int4, o.text
+            FROM UNNEST($2::text[]) WITH ORDINALITY AS o(text, ordinality)
+            "#,
+            message_id,
+            &poll.options[..] as &[&str]
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 获取单个投票及其结果
+    pub async fn find_by_message(
+        &self,
+        message_id: &str,
+        viewer_id: &str,
+    ) -> Result<Option<PollEntity>, SqlxError> {
+        let mut polls = self
+            .find_by_messages(&[message_id.to_string()], viewer_id)
+            .await?;
+        Ok(polls.remove(message_id))
+    }
+
+    /// 批量获取投票及其结果，按消息ID分组
+    ///
+    /// 匿名投票不返回投票者，`viewer_id` 为查看者的登录ID，用于标记其已选的选项。
+    pub async fn find_by_messages(
+        &self,
+        message_ids: &[String],
+        viewer_id: &str,
+    ) -> Result<HashMap<String, PollEntity>, SqlxError> {
+        if message_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                p.message_id,
+                m.group_id,
+                m.content as question,
+                p.multiple_choice,
+                p.anonymous,
+                p.closes_at,
+                o.option_id,
+                o.text,
+                (SELECT COUNT(*) FROM message_poll_votes v
+                 WHERE v.message_id = o.message_id AND v.option_id = o.option_id) as "vote_count!",
+                (SELECT COUNT(DISTINCT v.user_id) FROM message_poll_votes v
+                 WHERE v.message_id = p.message_id) as "total_voters!",
+                EXISTS(
+                    SELECT 1 FROM message_poll_votes v
+                    WHERE v.message_id = o.message_id AND v.option_id = o.option_id
+                      AND v.user_id = $2
+                ) as "voted!"
+            FROM message_polls p
+            JOIN messages m ON m.message_id = p.message_id
+            JOIN message_poll_options o ON o.message_id = p.message_id
+            WHERE p.message_id = ANY($1)
+            ORDER BY p.message_id, o.option_id
+            "#,
+            message_ids,
+            viewer_id
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        let voters = sqlx::query!(
+            r#"
+            SELECT v.message_id, v.option_id, u.public_user_id, u.nickname
+            FROM message_poll_votes v
+            JOIN message_polls p ON p.message_id = v.message_id
+            JOIN users u ON u.user_id = v.user_id
+            WHERE v.message_id = ANY($1) AND NOT p.anonymous
+            ORDER BY v.voted_at, u.public_user_id
+            "#,
+            message_ids
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        let mut voters_by_option: HashMap<(String, i32), Vec<PollVoter>> = HashMap::new();
+        for voter in voters {
+            voters_by_option
+                .entry((voter.message_id, voter.option_id))
+                .or_default()
+                .push(PollVoter {
+                    user_id: voter.public_user_id,
+                    nickname: voter.nickname,
+                });
+        }
+
+        let mut polls: HashMap<String, PollEntity> = HashMap::new();
+        for row in rows {
+            let poll = polls
+                .entry(row.message_id.clone())
+                .or_insert_with(|| PollEntity {
+                    message_id: row.message_id.clone(),
+                    group_id: row.group_id,
+                    question: row.question,
+                    multiple_choice: row.multiple_choice,
+                    anonymous: row.anonymous,
+                    closes_at: row.closes_at,
+                    options: Vec::new(),
+                    total_voters: row.total_voters,
+                    my_votes: Vec::new(),
+                });
+            if row.voted {
+                poll.my_votes.push(row.option_id);
+            }
+            poll.options.push(PollOptionEntity {
+                option_id: row.option_id,
+                text: row.text,
+                vote_count: row.vote_count,
+                voters: voters_by_option
+                    .remove(&(row.message_id, row.option_id))
+                    .unwrap_or_default(),
+            });
+        }
+
+        Ok(polls)
+    }
+
+    /// 投票，覆盖用户之前的选择
+    ///
+    /// 只有投票所在群组的成员可以投票，与发送消息一样在事务中检查成员资格。
+    /// `option_ids` 为空时撤回投票。
+    pub async fn vote(
+        &self,
+        message_id: &str,
+        user_id: &str,
+        option_ids: &[i32],
+    ) -> Result<VoteOutcome, SqlxError> {
+        let mut tx = self.db.begin().await?;
+
+        // 锁定投票，避免同一用户的并发请求写入多份选择
+        let poll = sqlx::query!(
+            r#"
+            SELECT
+                m.group_id,
+                p.multiple_choice,
+                (p.closes_at IS NOT NULL AND p.closes_at <= NOW()) as "closed!",
+                (SELECT COUNT(*) FROM message_poll_options o
+                 WHERE o.message_id = p.message_id) as "option_count!"
+            FROM message_polls p
+            JOIN messages m ON m.message_id = p.message_id
+            WHERE p.message_id = $1
+            FOR UPDATE OF p
+            "#,
+            message_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(poll) = poll else {
+            return Ok(VoteOutcome::NotFound);
+        };
+
+        let is_member = sqlx::query!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM group_members
+                WHERE group_id = $1 AND user_id = $2
+            ) as "exists!"
+            "#,
+            poll.group_id,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .exists;
+
+        if !is_member {
+            return Ok(VoteOutcome::NotMember);
+        }
+        if poll.closed {
+            return Ok(VoteOutcome::Closed);
+        }
+
+        let mut seen = HashSet::new();
+        let valid = (poll.multiple_choice || option_ids.len() <= 1)
+            && option_ids.iter().all(|&option_id| {
+                option_id >= 0 && (option_id as i64) < poll.option_count && seen.insert(option_id)
+            });
+        if !valid {
+            return Ok(VoteOutcome::InvalidOption);
+        }
+
+        sqlx::query!(
+            r#"
+            DELETE FROM message_poll_votes
+            WHERE message_id = $1 AND user_id = $2
+            "#,
+            message_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO message_poll_votes (message_id, option_id, user_id)
+            SELECT $1, UNNEST($3::int4[]), $2
+            "#,
+            message_id,
+            user_id,
+            option_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(VoteOutcome::Voted)
+    }
+}
@@ -0,0 +1,97 @@
+// 用户隐私设置存储库
+// 包含用户隐私设置相关的数据库操作
+
+use crate::database::models::privacy::{LocationPrivacy, PrivacySettingsEntity};
+use sqlx::{Error as SqlxError, PgPool};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 用户隐私设置存储库
+pub struct PrivacyOperation {
+    db: Arc<PgPool>,
+}
+
+impl PrivacyOperation {
+    /// 创建新的隐私设置存储库实例
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// 获取用户的隐私设置，未设置时返回 `None`
+    pub async fn get_settings(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<PrivacySettingsEntity>, SqlxError> {
+        sqlx::query_as!(
+            PrivacySettingsEntity,
+            r#"
+            SELECT user_id, location_precision, fuzz_radius, location_history, updated_at
+            FROM user_privacy_settings
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&*self.db)
+        .await
+    }
+
+    /// 批量获取用户的位置隐私级别，未设置的用户不在结果中
+    pub async fn find_location_privacy(
+        &self,
+        user_ids: &[String],
+    ) -> Result<HashMap<String, LocationPrivacy>, SqlxError> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let settings = sqlx::query_as!(
+            PrivacySettingsEntity,
+            r#"
+            SELECT user_id, location_precision, fuzz_radius, location_history, updated_at
+            FROM user_privacy_settings
+            WHERE user_id = ANY($1)
+            "#,
+            user_ids
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(settings
+            .into_iter()
+            .map(|settings| {
+                let privacy = settings.location_privacy();
+                (settings.user_id, privacy)
+            })
+            .collect())
+    }
+
+    /// 更新用户的位置隐私级别和位置历史开关
+    pub async fn update_settings(
+        &self,
+        user_id: &str,
+        privacy: LocationPrivacy,
+        location_history: bool,
+    ) -> Result<PrivacySettingsEntity, SqlxError> {
+        sqlx::query_as!(
+            PrivacySettingsEntity,
+            r#"
+            INSERT INTO user_privacy_settings (
+                user_id, location_precision, fuzz_radius, location_history
+            )
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id) DO UPDATE SET
+                location_precision = EXCLUDED.location_precision,
+                fuzz_radius = EXCLUDED.fuzz_radius,
+                location_history = EXCLUDED.location_history,
+                updated_at = NOW()
+            RETURNING user_id, location_precision, fuzz_radius, location_history, updated_at
+            "#,
+            user_id,
+            privacy.as_db_str(),
+            privacy.fuzz_radius(),
+            location_history
+        )
+        .fetch_one(&*self.db)
+        .await
+    }
+}
@@ -120,6 +120,33 @@ impl UserOperation {
         Ok(user)
     }
 
+    /// 根据公开ID查找用户
+    pub async fn find_by_public_id(
+        pool: &PgPool,
+        public_user_id: &str,
+    ) -> Result<Option<UserEntity>, sqlx::Error> {
+        let user = sqlx::query_as!(
+            UserEntity,
+            r#"
+            SELECT 
+                user_id as "user_id!", 
+                nickname as "nickname!", 
+                is_temporary, 
+                password_hash, 
+                recovery_code, 
+                created_at,
+                public_user_id as "public_user_id!"
+            FROM users
+            WHERE public_user_id = $1
+            "#,
+            public_user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user)
+    }
+
     /// 更新用户昵称
     pub async fn update_nickname(
         pool: &PgPool,
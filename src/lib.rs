@@ -1,4 +1,5 @@
 use config::Config;
+use media::MediaStorage;
 use redis::Client as RedisClient;
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -7,7 +8,9 @@ pub mod api;
 pub mod cache;
 pub mod config;
 pub mod database;
+pub mod media;
 pub mod middleware;
+pub mod tasks;
 pub mod utils;
 
 #[derive(Clone)]
@@ -15,4 +18,5 @@ pub struct AppState {
     pub pool: PgPool,
     pub config: Config,
     pub redis: Arc<RedisClient>,
+    pub media: MediaStorage,
 }
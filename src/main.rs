@@ -3,18 +3,27 @@ use std::sync::Arc;
 
 use axum::{
     Router,
+    extract::DefaultBodyLimit,
     routing::{delete, get, patch, post, put},
 };
 use backend::{
     AppState, api,
     config::Config,
-    middleware::{RateLimiter, auth_middleware, log_errors, rate_limit},
+    media::MediaStorage,
+    middleware::{RateLimiter, admin_middleware, auth_middleware, log_errors, rate_limit},
+    tasks,
 };
 use sqlx::Executor;
 use sqlx::postgres::PgPoolOptions;
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// 管理员导入行政区划时的请求体大小上限（字节）
+const ADMIN_AREA_IMPORT_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// 照片上传表单中除照片外其他内容（字段头、`keep_location` 等）预留的大小（字节）
+const PHOTO_UPLOAD_FORM_OVERHEAD: usize = 64 * 1024;
+
 #[tokio::main]
 async fn main() {
     // 初始化日志
@@ -53,13 +62,23 @@ async fn main() {
         redis::Client::open(config.redis_url.clone()).expect("Failed to create Redis client");
     let redis_arc = Arc::new(redis_client.clone());
 
+    // 设置媒体文件存储
+    let media = MediaStorage::from_config(&config).expect("Failed to create media storage");
+
     // 设置应用状态
     let state = AppState {
         pool,
         config: config.clone(),
         redis: redis_arc,
+        media,
     };
 
+    // 启动后台任务
+    tasks::spawn_event_reminder(state.clone());
+    tasks::spawn_group_archiver(state.clone());
+    tasks::spawn_heatmap_aggregator(state.clone());
+    tasks::spawn_location_pruner(state.clone());
+
     // 设置限流器
     let rate_limiter = Arc::new(RateLimiter::new(redis_client, config.clone()));
 
@@ -85,6 +104,27 @@ async fn main() {
             "/profile/password/reset",
             post(api::operations::user::reset_password),
         )
+        .route(
+            "/location",
+            post(api::operations::location::update_location),
+        )
+        .route(
+            "/privacy",
+            get(api::operations::location::get_privacy_settings),
+        )
+        .route(
+            "/privacy",
+            put(api::operations::location::update_privacy_settings),
+        )
+        .route(
+            "/location/history",
+            delete(api::operations::trip::delete_location_history),
+        )
+        .route("/trips", get(api::operations::trip::get_trips))
+        .route(
+            "/trips/{trip_id}/export",
+            get(api::operations::trip::export_trip),
+        )
         .route(
             "/location/nearby",
             get(api::operations::activity::find_nearby_users),
@@ -96,6 +136,16 @@ async fn main() {
         .route(
             "/{user_id}/activities",
             get(api::operations::activity::find_user_activities),
+        )
+        .route("/following", get(api::operations::follow::get_following))
+        .route("/followers", get(api::operations::follow::get_followers))
+        .route(
+            "/following/{public_user_id}",
+            put(api::operations::follow::follow_user),
+        )
+        .route(
+            "/following/{public_user_id}",
+            delete(api::operations::follow::unfollow_user),
         );
 
     // 群组相关路由（需要认证）
@@ -119,6 +169,14 @@ async fn main() {
             "/{group_id}/activities",
             get(api::operations::activity::get_group_activities),
         )
+        .route(
+            "/{group_id}/events",
+            get(api::operations::event::get_group_events),
+        )
+        .route(
+            "/{group_id}/events",
+            post(api::operations::event::create_event),
+        )
         .route(
             "/{group_id}/members",
             get(api::operations::group::get_group_members),
@@ -131,6 +189,26 @@ async fn main() {
             "/{group_id}/members/my",
             delete(api::operations::group::leave_group),
         )
+        .route(
+            "/{group_id}/waitlist/my",
+            delete(api::operations::group::leave_waitlist),
+        )
+        .route(
+            "/{group_id}/requests",
+            get(api::operations::group::get_join_requests),
+        )
+        .route(
+            "/{group_id}/requests/my",
+            delete(api::operations::group::cancel_join_request),
+        )
+        .route(
+            "/{group_id}/requests/{public_user_id}",
+            put(api::operations::group::approve_join_request),
+        )
+        .route(
+            "/{group_id}/requests/{public_user_id}",
+            delete(api::operations::group::reject_join_request),
+        )
         .route(
             "/{group_id}/members/{user_id}",
             delete(api::operations::group::remove_group_member),
@@ -138,6 +216,22 @@ async fn main() {
         .route(
             "/{group_id}/members/{user_id}/role",
             put(api::operations::group::update_user_role),
+        )
+        .route(
+            "/{group_id}/locations",
+            get(api::operations::location::get_group_shared_locations),
+        )
+        .route(
+            "/{group_id}/locations/stream",
+            get(api::operations::location::stream_group_shared_locations),
+        )
+        .route(
+            "/{group_id}/locations/my",
+            put(api::operations::location::start_group_location_sharing),
+        )
+        .route(
+            "/{group_id}/locations/my",
+            delete(api::operations::location::stop_group_location_sharing),
         );
 
     // 消息相关路由（需要认证）
@@ -150,6 +244,15 @@ async fn main() {
         .route(
             "/{message_id}",
             delete(api::operations::message::delete_message),
+        )
+        .route("/{message_id}/poll", get(api::operations::poll::get_poll))
+        .route(
+            "/{message_id}/poll/votes",
+            put(api::operations::poll::vote_poll),
+        )
+        .route(
+            "/{message_id}/poll/stream",
+            get(api::operations::poll::stream_poll_results),
         );
 
     // 活动相关路由（需要认证）
@@ -159,8 +262,124 @@ async fn main() {
         .route(
             "/nearby",
             get(api::operations::activity::get_nearby_activities),
+        )
+        .route(
+            "/heatmap",
+            get(api::operations::activity::get_activity_heatmap),
+        )
+        .route(
+            "/{activity_id}/like",
+            put(api::operations::interaction::like_activity),
+        )
+        .route(
+            "/{activity_id}/like",
+            delete(api::operations::interaction::unlike_activity),
+        )
+        .route(
+            "/{activity_id}/comments",
+            get(api::operations::interaction::get_activity_comments),
+        )
+        .route(
+            "/{activity_id}/comments",
+            post(api::operations::interaction::create_activity_comment),
+        )
+        .route(
+            "/{activity_id}/comments/{comment_id}",
+            delete(api::operations::interaction::delete_activity_comment),
+        )
+        .route(
+            "/{activity_id}/photos",
+            post(api::operations::photo::upload_activity_photo).layer(DefaultBodyLimit::max(
+                config.media_max_upload_size + PHOTO_UPLOAD_FORM_OVERHEAD,
+            )),
+        )
+        .route(
+            "/{activity_id}/photos/{photo_id}",
+            delete(api::operations::photo::delete_activity_photo),
+        );
+
+    // 群组聚会路由（需要认证）
+    let event_routes = Router::new()
+        .route("/nearby", get(api::operations::event::get_nearby_events))
+        .route("/{event_id}", get(api::operations::event::get_event))
+        .route("/{event_id}", delete(api::operations::event::cancel_event))
+        .route("/{event_id}/rsvp", put(api::operations::event::rsvp_event))
+        .route(
+            "/{event_id}/rsvp",
+            delete(api::operations::event::remove_event_rsvp),
+        );
+
+    // 收藏地点路由（需要认证）
+    let place_routes = Router::new()
+        .route("/", post(api::operations::place::create_saved_place))
+        .route("/", get(api::operations::place::get_saved_places))
+        .route(
+            "/{place_id}",
+            delete(api::operations::place::delete_saved_place),
+        );
+
+    // 通知收件箱路由（需要认证）
+    let notification_routes = Router::new()
+        .route("/", get(api::operations::notification::get_notifications))
+        .route(
+            "/read",
+            put(api::operations::notification::mark_all_notifications_read),
+        )
+        .route(
+            "/{notification_id}/read",
+            put(api::operations::notification::mark_notification_read),
+        );
+
+    // 首页信息流路由（需要认证）
+    let feed_routes = Router::new().route("/", get(api::operations::feed::get_home_feed));
+
+    // 逆地理编码路由（需要认证）
+    let geocode_routes =
+        Router::new().route("/reverse", get(api::operations::geocode::reverse_geocode));
+
+    // 地图视窗查询路由（需要认证）
+    let map_routes = Router::new()
+        .route("/groups", post(api::operations::map::get_groups_in_area))
+        .route(
+            "/activities",
+            post(api::operations::map::get_activities_in_area),
+        )
+        .route(
+            "/clusters/{layer}/{z}/{x}/{y}",
+            get(api::operations::map::get_map_clusters),
         );
 
+    // 矢量瓦片路由（需要认证），最后一段为 {y}.mvt
+    let tile_routes = Router::new().route(
+        "/{layer}/{z}/{x}/{tile}",
+        get(api::operations::map::get_vector_tile),
+    );
+
+    // 管理路由（需要认证和管理员权限）
+    let admin_routes = Router::new()
+        .route("/export/groups", get(api::operations::admin::export_groups))
+        .route(
+            "/export/users/{public_user_id}/activities",
+            get(api::operations::admin::export_user_activities),
+        )
+        .route(
+            "/import/groups",
+            post(api::operations::admin::import_groups),
+        )
+        // 行政边界数据较大，放宽请求体大小限制
+        .route(
+            "/import/admin-areas",
+            post(api::operations::admin::import_admin_areas)
+                .layer(DefaultBodyLimit::max(ADMIN_AREA_IMPORT_BODY_LIMIT)),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            admin_middleware,
+        ));
+
+    // 媒体文件下载路由（凭签名地址访问），仅本地存储后端使用
+    let media_routes = Router::new().route("/{*key}", get(api::operations::photo::get_media_file));
+
     // 系统健康检查路由（公开）
     let health_routes = Router::new().route("/ping", get(api::operations::test::ping));
 
@@ -170,6 +389,14 @@ async fn main() {
         .nest("/groups", group_routes)
         .nest("/messages", message_routes)
         .nest("/activities", activity_routes)
+        .nest("/events", event_routes)
+        .nest("/feed", feed_routes)
+        .nest("/places", place_routes)
+        .nest("/notifications", notification_routes)
+        .nest("/geocode", geocode_routes)
+        .nest("/map", map_routes)
+        .nest("/tiles", tile_routes)
+        .nest("/admin", admin_routes)
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
@@ -178,7 +405,8 @@ async fn main() {
     // 将公开路由组织到一起
     let public_routes = Router::new()
         .nest("/auth", auth_routes)
-        .nest("/health", health_routes);
+        .nest("/health", health_routes)
+        .nest("/media", media_routes);
 
     // 合并所有路由
     let api_routes = Router::new()
@@ -0,0 +1,9 @@
+// 媒体模块
+// 包含媒体文件的存储后端和图片处理
+
+pub mod photo;
+pub mod storage;
+
+// 重新导出常用类型
+pub use photo::{ProcessedPhoto, process_photo};
+pub use storage::MediaStorage;
@@ -0,0 +1,254 @@
+// 照片处理
+// 校验上传的图片、生成缩略图，并按用户选择去除 EXIF 中的 GPS 信息
+
+use bytes::Bytes;
+use exif::experimental::Writer;
+use exif::{Context, Field, In, Tag};
+use image::codecs::jpeg::JpegEncoder;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use img_parts::jpeg::markers;
+use img_parts::webp::CHUNK_XMP;
+use img_parts::{DynImage, ImageEXIF};
+use std::io::Cursor;
+
+/// 缩略图最长边的像素数
+const THUMBNAIL_SIZE: u32 = 320;
+
+/// 缩略图的 JPEG 压缩质量
+const THUMBNAIL_QUALITY: u8 = 80;
+
+/// 照片单边允许的最大像素数，防止解码超大图片耗尽内存
+const MAX_PHOTO_DIMENSION: u32 = 12_000;
+
+/// JPEG 中 XMP 数据段（包括扩展 XMP）的标识前缀
+const JPEG_XMP_PREFIX: &[u8] = b"http://ns.adobe.com/x";
+
+/// PNG 中保存 XMP 的 iTXt 块关键字
+const PNG_XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp\0";
+
+/// 处理后的照片
+#[derive(Debug)]
+pub struct ProcessedPhoto {
+    /// 要保存的原图，未选择保留位置时已去除 GPS 信息
+    pub original: Bytes,
+    /// 原图的内容类型
+    pub content_type: &'static str,
+    /// 原图的文件扩展名
+    pub extension: &'static str,
+    /// JPEG 格式的缩略图
+    pub thumbnail: Bytes,
+    /// 按 EXIF 方向旋转后的宽度
+    pub width: u32,
+    /// 按 EXIF 方向旋转后的高度
+    pub height: u32,
+}
+
+/// 处理上传的照片
+///
+/// 只接受 JPEG、PNG 和 WebP。原图保持原始编码，仅在 `keep_location` 为 `false` 时
+/// 改写其中的元数据：EXIF 只保留主图的非 GPS 字段（无法解析时整段删除），
+/// XMP 整段删除。解码和缩放较耗时，应在阻塞线程中调用。
+pub fn process_photo(data: Bytes, keep_location: bool) -> Result<ProcessedPhoto, String> {
+    let format = image::guess_format(&data).map_err(|_| "无法识别的图片格式".to_string())?;
+    let (content_type, extension) = match format {
+        ImageFormat::Jpeg => ("image/jpeg", "jpg"),
+        ImageFormat::Png => ("image/png", "png"),
+        ImageFormat::WebP => ("image/webp", "webp"),
+        _ => return Err("只支持 JPEG、PNG 和 WebP 格式的图片".to_string()),
+    };
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_PHOTO_DIMENSION);
+    limits.max_image_height = Some(MAX_PHOTO_DIMENSION);
+    let mut reader = ImageReader::with_format(Cursor::new(&data[..]), format);
+    reader.limits(limits);
+
+    let invalid = |e: image::ImageError| format!("图片无法解码: {}", e);
+    let mut decoder = reader.into_decoder().map_err(invalid)?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut decoded = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+    decoded.apply_orientation(orientation);
+
+    let mut thumbnail = Vec::new();
+    JpegEncoder::new_with_quality(&mut thumbnail, THUMBNAIL_QUALITY)
+        .encode_image(&decoded.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8())
+        .map_err(|e| format!("生成缩略图失败: {}", e))?;
+
+    let original = if keep_location {
+        data
+    } else {
+        strip_location(data)?
+    };
+
+    Ok(ProcessedPhoto {
+        original,
+        content_type,
+        extension,
+        thumbnail: Bytes::from(thumbnail),
+        width: decoded.width(),
+        height: decoded.height(),
+    })
+}
+
+/// 去除图片元数据中的位置信息
+fn strip_location(data: Bytes) -> Result<Bytes, String> {
+    let mut image = match DynImage::from_bytes(data) {
+        Ok(Some(image)) => image,
+        _ => return Err("图片文件已损坏".to_string()),
+    };
+
+    match &mut image {
+        DynImage::Jpeg(jpeg) => jpeg.segments_mut().retain(|segment| {
+            !(segment.marker() == markers::APP1 && segment.contents().starts_with(JPEG_XMP_PREFIX))
+        }),
+        DynImage::Png(png) => png.chunks_mut().retain(|chunk| {
+            !(chunk.kind() == *b"iTXt" && chunk.contents().starts_with(PNG_XMP_KEYWORD))
+        }),
+        DynImage::WebP(webp) => webp.remove_chunks_by_id(CHUNK_XMP),
+    }
+
+    let exif = image.exif().and_then(strip_gps);
+    image.set_exif(exif);
+
+    Ok(image.encoder().bytes())
+}
+
+/// 重新编码 EXIF，去除 GPS 字段
+///
+/// 只保留主图的字段：缩略图 IFD 需要连同内嵌的缩略图一起重写，直接丢弃；
+/// 厂商私有的 MakerNote 无法检查其内容，同样丢弃。解析或编码失败时返回 `None`，
+/// 由调用方删除整段 EXIF。
+fn strip_gps(raw: Bytes) -> Option<Bytes> {
+    let exif = exif::Reader::new().read_raw(raw.to_vec()).ok()?;
+    let fields: Vec<&Field> = exif
+        .fields()
+        .filter(|field| {
+            field.ifd_num == In::PRIMARY
+                && field.tag.context() != Context::Gps
+                && field.tag != Tag::MakerNote
+        })
+        .collect();
+
+    let mut writer = Writer::new();
+    for field in fields {
+        writer.push_field(field);
+    }
+    let mut buf = Cursor::new(Vec::new());
+    writer.write(&mut buf, exif.little_endian()).ok()?;
+
+    Some(Bytes::from(buf.into_inner()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exif::{Rational, Value};
+    use image::{ImageEncoder, RgbImage};
+
+    /// 生成 4x2 的 JPEG，附带方向为“顺时针旋转90度”和 GPS 坐标的 EXIF
+    fn jpeg_with_gps() -> Bytes {
+        let mut jpeg = Vec::new();
+        JpegEncoder::new(&mut jpeg)
+            .write_image(&RgbImage::new(4, 2), 4, 2, image::ExtendedColorType::Rgb8)
+            .unwrap();
+
+        let orientation = Field {
+            tag: Tag::Orientation,
+            ifd_num: In::PRIMARY,
+            value: Value::Short(vec![6]),
+        };
+        let latitude_ref = Field {
+            tag: Tag::GPSLatitudeRef,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"N".to_vec()]),
+        };
+        let latitude = Field {
+            tag: Tag::GPSLatitude,
+            ifd_num: In::PRIMARY,
+            value: Value::Rational(vec![
+                Rational::from((31, 1)),
+                Rational::from((13, 1)),
+                Rational::from((48, 1)),
+            ]),
+        };
+        let mut writer = Writer::new();
+        writer.push_field(&orientation);
+        writer.push_field(&latitude_ref);
+        writer.push_field(&latitude);
+        let mut exif = Cursor::new(Vec::new());
+        writer.write(&mut exif, false).unwrap();
+
+        let mut image = DynImage::from_bytes(Bytes::from(jpeg)).unwrap().unwrap();
+        image.set_exif(Some(Bytes::from(exif.into_inner())));
+        image.encoder().bytes()
+    }
+
+    /// 读取图片中的 EXIF 字段
+    fn read_exif(data: Bytes) -> Option<exif::Exif> {
+        let raw = DynImage::from_bytes(data).unwrap().unwrap().exif()?;
+        Some(exif::Reader::new().read_raw(raw.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn strip_location_removes_gps_and_keeps_orientation() {
+        let data = jpeg_with_gps();
+        let before = read_exif(data.clone()).unwrap();
+        assert!(before.fields().any(|f| f.tag.context() == Context::Gps));
+
+        let after = read_exif(strip_location(data).unwrap()).unwrap();
+        assert!(!after.fields().any(|f| f.tag.context() == Context::Gps));
+        let orientation = after.get_field(Tag::Orientation, In::PRIMARY).unwrap();
+        assert_eq!(orientation.value.get_uint(0), Some(6));
+    }
+
+    #[test]
+    fn strip_location_removes_jpeg_xmp() {
+        let mut image = DynImage::from_bytes(jpeg_with_gps()).unwrap().unwrap();
+        let DynImage::Jpeg(jpeg) = &mut image else {
+            unreachable!();
+        };
+        let xmp = [JPEG_XMP_PREFIX, b"ap/1.0/\0<x:xmpmeta/>"].concat();
+        jpeg.segments_mut().insert(
+            1,
+            img_parts::jpeg::JpegSegment::new_with_contents(markers::APP1, Bytes::from(xmp)),
+        );
+
+        let stripped = strip_location(image.encoder().bytes()).unwrap();
+        let Some(DynImage::Jpeg(jpeg)) = DynImage::from_bytes(stripped).unwrap() else {
+            panic!("应仍为 JPEG");
+        };
+        assert!(!jpeg.segments().iter().any(|segment| {
+            segment.marker() == markers::APP1 && segment.contents().starts_with(JPEG_XMP_PREFIX)
+        }));
+    }
+
+    #[test]
+    fn strip_gps_returns_none_for_invalid_exif() {
+        assert!(strip_gps(Bytes::from_static(b"not exif")).is_none());
+    }
+
+    #[test]
+    fn process_photo_keeps_location_only_when_requested() {
+        let data = jpeg_with_gps();
+
+        let kept = process_photo(data.clone(), true).unwrap();
+        assert_eq!(kept.original, data);
+
+        let stripped = process_photo(data, false).unwrap();
+        let exif = read_exif(stripped.original).unwrap();
+        assert!(!exif.fields().any(|f| f.tag.context() == Context::Gps));
+        assert_eq!(stripped.content_type, "image/jpeg");
+    }
+
+    #[test]
+    fn process_photo_applies_exif_orientation() {
+        let photo = process_photo(jpeg_with_gps(), false).unwrap();
+        assert_eq!((photo.width, photo.height), (2, 4));
+    }
+
+    #[test]
+    fn process_photo_rejects_unsupported_format() {
+        assert!(process_photo(Bytes::from_static(b"GIF89a\x01\x00\x01\x00"), false).is_err());
+    }
+}
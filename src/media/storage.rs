@@ -0,0 +1,300 @@
+// 媒体文件存储
+// 通过 object_store 统一封装本地目录和 S3 兼容的对象存储
+
+use crate::config::Config;
+use bytes::Bytes;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::signer::Signer;
+use object_store::{Attribute, Attributes, ObjectStore, PutOptions, PutPayload};
+use reqwest::Method;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 存储后端类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    /// 本地目录，由服务自身提供下载
+    Local,
+    /// S3 兼容的对象存储，通过配置的公开地址访问
+    S3,
+}
+
+/// 媒体文件存储
+///
+/// 文件以对象键（如 `activities/{activity_id}/{photo_id}.jpg`）保存。对外地址都会过期，
+/// 只有通过接口看到照片的用户才能拿到有效的地址：本地存储后端为 `MEDIA_PUBLIC_BASE_URL`
+/// 加上对象键和签名，S3 兼容存储为对象存储的预签名地址。
+#[derive(Clone)]
+pub struct MediaStorage {
+    store: Arc<dyn ObjectStore>,
+    backend: Backend,
+    /// S3 兼容存储用于生成预签名地址，本地存储后端为空
+    signer: Option<Arc<dyn Signer>>,
+    public_base_url: String,
+    url_secret: String,
+    url_ttl_secs: i64,
+}
+
+impl MediaStorage {
+    /// 根据配置创建存储后端
+    pub fn from_config(config: &Config) -> Result<Self, object_store::Error> {
+        let backend = match config.media_storage.as_str() {
+            "s3" => Backend::S3,
+            _ => Backend::Local,
+        };
+        let mut signer: Option<Arc<dyn Signer>> = None;
+        let store: Arc<dyn ObjectStore> = match backend {
+            Backend::S3 => {
+                let mut builder = AmazonS3Builder::new()
+                    .with_bucket_name(&config.media_s3_bucket)
+                    .with_region(&config.media_s3_region)
+                    .with_access_key_id(&config.media_s3_access_key_id)
+                    .with_secret_access_key(&config.media_s3_secret_access_key);
+                if !config.media_s3_endpoint.is_empty() {
+                    // 自建的 S3 兼容服务（如 MinIO）通常使用路径风格的地址，且可能不启用 HTTPS
+                    builder = builder
+                        .with_endpoint(&config.media_s3_endpoint)
+                        .with_allow_http(config.media_s3_endpoint.starts_with("http://"));
+                }
+                let s3 = Arc::new(builder.build()?);
+                signer = Some(s3.clone());
+                s3
+            }
+            Backend::Local => {
+                std::fs::create_dir_all(&config.media_local_dir).map_err(|e| {
+                    object_store::Error::Generic {
+                        store: "LocalFileSystem",
+                        source: Box::new(e),
+                    }
+                })?;
+                Arc::new(LocalFileSystem::new_with_prefix(&config.media_local_dir)?)
+            }
+        };
+
+        Ok(Self {
+            store,
+            backend,
+            signer,
+            public_base_url: config.media_public_base_url.clone(),
+            url_secret: config.media_url_secret.clone(),
+            url_ttl_secs: config.media_url_ttl().as_secs().max(1) as i64,
+        })
+    }
+
+    /// 是否需要由服务自身提供文件下载
+    pub fn serves_files(&self) -> bool {
+        self.backend == Backend::Local
+    }
+
+    /// 对象键对应的对外访问地址
+    ///
+    /// 本地存储后端的地址带有 `expires` 和 `signature` 参数。过期时间按有效期取整，
+    /// 同一时段内生成的地址相同，便于浏览器缓存，实际有效期在一到两倍有效期之间。
+    /// S3 兼容存储返回有效期为 `MEDIA_URL_TTL` 的预签名 GET 地址。
+    pub async fn url(&self, key: &str) -> Result<String, object_store::Error> {
+        if let Some(signer) = &self.signer {
+            let url = signer
+                .signed_url(
+                    Method::GET,
+                    &Path::parse(key)?,
+                    Duration::from_secs(self.url_ttl_secs as u64),
+                )
+                .await?;
+            return Ok(url.into());
+        }
+
+        let now = Utc::now().timestamp();
+        let expires = (now.div_euclid(self.url_ttl_secs) + 2) * self.url_ttl_secs;
+        Ok(format!(
+            "{}/{}?expires={}&signature={}",
+            self.public_base_url,
+            key,
+            expires,
+            self.sign(key, expires)
+        ))
+    }
+
+    /// 校验本地存储后端地址的签名，签名不匹配或在 `now` 时已过期时返回 `false`
+    pub fn verify_url(&self, key: &str, expires: i64, signature: &str, now: i64) -> bool {
+        let expected = self.sign(key, expires);
+        // 逐字节比较全部内容，避免通过响应时间推断签名
+        let matches = expected.len() == signature.len()
+            && expected
+                .bytes()
+                .zip(signature.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0;
+        matches && now < expires
+    }
+
+    /// 对象键和过期时间的 HMAC-SHA256 签名（十六进制）
+    fn sign(&self, key: &str, expires: i64) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.url_secret.as_bytes())
+            .expect("HMAC 接受任意长度的密钥");
+        mac.update(format!("{}:{}", expires, key).as_bytes());
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// 保存文件，已存在时覆盖
+    pub async fn put(
+        &self,
+        key: &str,
+        data: Bytes,
+        content_type: &'static str,
+    ) -> Result<(), object_store::Error> {
+        // 本地目录不支持保存对象属性，下载时按扩展名推断内容类型
+        let attributes = match self.backend {
+            Backend::Local => Attributes::new(),
+            Backend::S3 => Attributes::from_iter([(Attribute::ContentType, content_type)]),
+        };
+        let options = PutOptions {
+            attributes,
+            ..Default::default()
+        };
+
+        self.store
+            .put_opts(&Path::parse(key)?, PutPayload::from(data), options)
+            .await?;
+        Ok(())
+    }
+
+    /// 读取文件内容
+    pub async fn get(&self, key: &str) -> Result<Bytes, object_store::Error> {
+        self.store.get(&Path::parse(key)?).await?.bytes().await
+    }
+
+    /// 删除文件，文件不存在时同样返回成功
+    pub async fn delete(&self, key: &str) -> Result<(), object_store::Error> {
+        match self.store.delete(&Path::parse(key)?).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// 按对象键的扩展名推断内容类型
+pub fn content_type_for(key: &str) -> &'static str {
+    match key.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("jpg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::aws::AmazonS3Builder;
+    use object_store::memory::InMemory;
+
+    fn storage(backend: Backend) -> MediaStorage {
+        let signer: Option<Arc<dyn Signer>> = match backend {
+            Backend::Local => None,
+            Backend::S3 => Some(Arc::new(
+                AmazonS3Builder::new()
+                    .with_bucket_name("photos")
+                    .with_region("us-east-1")
+                    .with_access_key_id("key")
+                    .with_secret_access_key("secret")
+                    .build()
+                    .unwrap(),
+            )),
+        };
+        MediaStorage {
+            store: Arc::new(InMemory::new()),
+            backend,
+            signer,
+            public_base_url: "http://localhost/media".to_string(),
+            url_secret: "secret".to_string(),
+            url_ttl_secs: 3600,
+        }
+    }
+
+    /// 从地址中取出对象键、过期时间和签名
+    fn parse(url: &str) -> (&str, i64, &str) {
+        let (path, query) = url.split_once('?').unwrap();
+        let key = path.strip_prefix("http://localhost/media/").unwrap();
+        let (expires, signature) = query
+            .strip_prefix("expires=")
+            .and_then(|query| query.split_once("&signature="))
+            .unwrap();
+        (key, expires.parse().unwrap(), signature)
+    }
+
+    #[tokio::test]
+    async fn local_url_is_signed() {
+        let media = storage(Backend::Local);
+        let url = media.url("activities/a/p.jpg").await.unwrap();
+        let (key, expires, signature) = parse(&url);
+        let now = Utc::now().timestamp();
+
+        assert_eq!(key, "activities/a/p.jpg");
+        assert!(expires > now + 3600 - 1 && expires <= now + 2 * 3600);
+        assert!(media.verify_url(key, expires, signature, now));
+    }
+
+    #[tokio::test]
+    async fn s3_url_is_presigned() {
+        let media = storage(Backend::S3);
+        let url = media.url("activities/a/p.jpg").await.unwrap();
+
+        assert!(url.starts_with("https://s3.us-east-1.amazonaws.com/photos/activities/a/p.jpg?"));
+        assert!(url.contains("X-Amz-Expires=3600"));
+        assert!(url.contains("X-Amz-Signature="));
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_other_key() {
+        let media = storage(Backend::Local);
+        let url = media.url("activities/a/p.jpg").await.unwrap();
+        let (_, expires, signature) = parse(&url);
+        assert!(!media.verify_url("activities/a/q.jpg", expires, signature, 0));
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_tampered_expiry_and_signature() {
+        let media = storage(Backend::Local);
+        let url = media.url("activities/a/p.jpg").await.unwrap();
+        let (key, expires, signature) = parse(&url);
+
+        assert!(!media.verify_url(key, expires + 3600, signature, 0));
+        let mut tampered = signature.to_string();
+        let last = if tampered.ends_with('0') { "1" } else { "0" };
+        tampered.replace_range(tampered.len() - 1.., last);
+        assert!(!media.verify_url(key, expires, &tampered, 0));
+        assert!(!media.verify_url(key, expires, "", 0));
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_expired_url() {
+        let media = storage(Backend::Local);
+        let url = media.url("activities/a/p.jpg").await.unwrap();
+        let (key, expires, signature) = parse(&url);
+
+        assert!(media.verify_url(key, expires, signature, expires - 1));
+        assert!(!media.verify_url(key, expires, signature, expires));
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_other_secret() {
+        let media = storage(Backend::Local);
+        let url = media.url("activities/a/p.jpg").await.unwrap();
+        let (key, expires, signature) = parse(&url);
+
+        let other = MediaStorage {
+            url_secret: "other".to_string(),
+            ..storage(Backend::Local)
+        };
+        assert!(!other.verify_url(key, expires, signature, 0));
+    }
+}
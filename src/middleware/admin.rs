@@ -0,0 +1,37 @@
+use crate::{
+    AppState,
+    utils::{Claims, error_codes, error_to_api_response},
+};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// 管理员权限中间件，需在认证中间件之后执行
+pub async fn admin_middleware(
+    State(app_state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, Response> {
+    let is_admin = request
+        .extensions()
+        .get::<Claims>()
+        .is_some_and(|claims| app_state.config.is_admin(&claims.sub));
+
+    if !is_admin {
+        tracing::warn!("非管理员尝试访问管理接口: {}", request.uri().path());
+        return Err((
+            StatusCode::OK,
+            error_to_api_response::<()>(
+                error_codes::PERMISSION_DENIED,
+                "需要管理员权限".to_string(),
+            ),
+        )
+            .into_response());
+    }
+
+    Ok(next.run(request).await)
+}
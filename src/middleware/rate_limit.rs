@@ -52,7 +52,7 @@ impl RateLimiter {
                     .and_then(|h| h.to_str().ok())
                     .and_then(|s| s.split(',').find(|ip| !ip.trim().is_empty()))
             })
-            .or_else(|| remote_ip.as_deref()) // 降级使用连接IP
+            .or(remote_ip.as_deref()) // 降级使用连接IP
             .unwrap_or("unknown")
             .trim()
             .to_string();
@@ -0,0 +1,40 @@
+// 活动热力图聚合任务
+// 定期将新增的用户活动增量聚合到热力图网格
+
+use crate::AppState;
+use crate::database::operations::activity::ActivityOperation;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// 预聚合的 geohash 精度，对应约 39km、4.9km、1.2km、153m 的网格
+pub const HEATMAP_PRECISIONS: [i32; 4] = [4, 5, 6, 7];
+
+/// 聚合时跳过最近创建的活动（秒），避免遗漏尚未提交的写入
+const HEATMAP_LAG_SECS: i64 = 30;
+
+/// 启动活动热力图聚合任务
+pub fn spawn_heatmap_aggregator(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let repo = ActivityOperation::new(Arc::new(state.pool.clone()));
+        let retention_secs = state.config.activity_heatmap_retention().as_secs() as i64;
+
+        let mut interval = tokio::time::interval(state.config.activity_heatmap_interval());
+        loop {
+            interval.tick().await;
+
+            match repo
+                .aggregate_heatmap(
+                    &state.config.location_fuzz_secret,
+                    &HEATMAP_PRECISIONS,
+                    HEATMAP_LAG_SECS,
+                    retention_secs,
+                )
+                .await
+            {
+                Ok(0) => {}
+                Ok(updated) => tracing::debug!("活动热力图已更新 {} 个网格", updated),
+                Err(e) => tracing::error!("聚合活动热力图失败: {}", e),
+            }
+        }
+    })
+}
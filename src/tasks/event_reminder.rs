@@ -0,0 +1,26 @@
+// 群组聚会提醒任务
+// 定期向即将开始的聚会的参加者发送提醒通知
+
+use crate::AppState;
+use crate::database::operations::event::EventOperation;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// 启动群组聚会提醒任务
+pub fn spawn_event_reminder(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let repo = EventOperation::new(Arc::new(state.pool.clone()));
+        let lead_secs = state.config.event_reminder_lead().as_secs();
+
+        let mut interval = tokio::time::interval(state.config.event_reminder_interval());
+        loop {
+            interval.tick().await;
+
+            match repo.send_due_reminders(lead_secs).await {
+                Ok(0) => {}
+                Ok(sent) => tracing::info!("已发送 {} 条聚会提醒", sent),
+                Err(e) => tracing::error!("发送聚会提醒失败: {}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,42 @@
+// 群组归档任务
+// 定期归档已过期或长期不活跃的群组
+
+use crate::AppState;
+use crate::cache::operations::group::GroupCacheOperations;
+use crate::database::operations::group::GroupOperation;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// 启动群组归档任务
+pub fn spawn_group_archiver(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let repo = GroupOperation::new(Arc::new(state.pool.clone()));
+        let cache = GroupCacheOperations::new(state.redis.clone());
+        let ttl_secs = state.config.group_inactive_ttl().as_secs() as i64;
+
+        let mut interval = tokio::time::interval(state.config.group_archive_interval());
+        loop {
+            interval.tick().await;
+
+            let archived = match repo.archive_inactive_groups(ttl_secs).await {
+                Ok(archived) => archived,
+                Err(e) => {
+                    tracing::error!("归档不活跃群组失败: {}", e);
+                    continue;
+                }
+            };
+
+            if archived.is_empty() {
+                continue;
+            }
+            tracing::info!("已归档 {} 个过期或不活跃的群组", archived.len());
+
+            // 归档后的群组不应再从缓存中被搜索到
+            for group_id in &archived {
+                if let Err(e) = cache.clear_group_cache(group_id).await {
+                    tracing::warn!("清除已归档群组 {} 的缓存失败: {}", group_id, e);
+                }
+            }
+        }
+    })
+}
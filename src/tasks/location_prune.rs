@@ -0,0 +1,27 @@
+// 位置记录清理任务
+// 定期删除超出保留期限的用户定位
+
+use crate::AppState;
+use crate::database::operations::location::LocationOperation;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// 启动位置记录清理任务
+pub fn spawn_location_pruner(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let repo = LocationOperation::new(Arc::new(state.pool.clone()));
+        let live_secs = state.config.location_stale_after().as_secs();
+        let retention_secs = state.config.location_history_retention().as_secs();
+
+        let mut interval = tokio::time::interval(state.config.location_prune_interval());
+        loop {
+            interval.tick().await;
+
+            match repo.prune_locations(live_secs, retention_secs).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("已清理 {} 条过期的位置记录", deleted),
+                Err(e) => tracing::error!("清理过期位置记录失败: {}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,13 @@
+// 后台任务模块
+// 包含随服务启动的定时任务
+
+pub mod activity_heatmap;
+pub mod event_reminder;
+pub mod group_archive;
+pub mod location_prune;
+
+// 重新导出任务启动函数
+pub use activity_heatmap::spawn_heatmap_aggregator;
+pub use event_reminder::spawn_event_reminder;
+pub use group_archive::spawn_group_archiver;
+pub use location_prune::spawn_location_pruner;
@@ -121,9 +121,36 @@ pub mod error_codes {
     pub const PERMISSION_DENIED: i32 = 1003;
     pub const NOT_FOUND: i32 = 1004;
     pub const RATE_LIMIT: i32 = 1005;
+    pub const GROUP_FULL: i32 = 1006;
+    pub const OUTSIDE_GEOFENCE: i32 = 1007;
     pub const INTERNAL_ERROR: i32 = 5000;
 }
 
+/// 用户发布的文本内容（群组消息、活动评论）的最大字符数
+pub const MAX_CONTENT_LEN: usize = 2000;
+
+/// 校验用户发布的文本内容，返回去除首尾空白并统一换行符后的内容
+///
+/// Windows（`\r\n`）和旧版 Mac（`\r`）的换行统一为 `\n`。内容不能为空、
+/// 不能超过 [`MAX_CONTENT_LEN`] 个字符，除换行和制表符外不能包含控制字符。
+pub fn validate_content(content: &str) -> Result<String, String> {
+    let content = content.trim().replace("\r\n", "\n").replace('\r', "\n");
+    if content.is_empty() {
+        return Err("内容不能为空".to_string());
+    }
+    if content.chars().count() > MAX_CONTENT_LEN {
+        return Err(format!("内容不能超过{}个字符", MAX_CONTENT_LEN));
+    }
+    if content
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return Err("内容包含非法字符".to_string());
+    }
+
+    Ok(content)
+}
+
 /// 根据真实用户ID生成公开ID
 ///
 /// 使用单向哈希+截断的方法，生成一个不可逆但稳定的公开ID
@@ -141,3 +168,59 @@ pub fn generate_public_id(real_id: &str, salt: &str) -> String {
 
 /// 默认用于生成公开用户ID的盐值
 pub const PUBLIC_USER_ID_SALT: &str = "3a91b3cd4e7f8b2d5c6a8f1e0d9c7b4a";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_content_normalizes_line_breaks() {
+        assert_eq!(
+            validate_content("a\r\nb\rc\nd"),
+            Ok("a\nb\nc\nd".to_string())
+        );
+    }
+
+    #[test]
+    fn validate_content_trims_whitespace() {
+        assert_eq!(
+            validate_content("  \r\n hello \t\n"),
+            Ok("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn validate_content_rejects_empty_input() {
+        assert!(validate_content("").is_err());
+        assert!(validate_content(" \r\n\t ").is_err());
+    }
+
+    #[test]
+    fn validate_content_counts_characters_not_bytes() {
+        // 每个汉字占3个字节，按字符计数时恰好等于上限
+        let at_limit = "字".repeat(MAX_CONTENT_LEN);
+        assert_eq!(validate_content(&at_limit), Ok(at_limit.clone()));
+
+        let over_limit = "字".repeat(MAX_CONTENT_LEN + 1);
+        assert!(validate_content(&over_limit).is_err());
+    }
+
+    #[test]
+    fn validate_content_checks_length_after_normalizing() {
+        // `\r\n` 统一为 `\n` 后恰好等于上限
+        let content = format!("{}\r\n", "a".repeat(MAX_CONTENT_LEN - 2)) + "b";
+        assert!(validate_content(&content).is_ok());
+    }
+
+    #[test]
+    fn validate_content_allows_only_tab_and_newline_controls() {
+        assert_eq!(validate_content("a\tb\nc"), Ok("a\tb\nc".to_string()));
+
+        for control in [
+            '\0', '\u{7}', '\u{8}', '\u{b}', '\u{c}', '\u{1b}', '\u{7f}', '\u{85}',
+        ] {
+            let content = format!("a{}b", control);
+            assert!(validate_content(&content).is_err(), "{:?}", control);
+        }
+    }
+}